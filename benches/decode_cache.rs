@@ -0,0 +1,58 @@
+use chip8rs::assembler;
+use chip8rs::machine::Machine;
+use std::time::Instant;
+
+// The logic of a game's main loop. Drawing is left out, as copying the sprite
+// takes far longer than decoding and would hide the difference.
+const SOURCE: &str = "
+: main
+  loop
+    v0 += 1
+    v1 += v0
+    v2 := v1
+    v2 >>= v2
+    v3 := random 0xFF
+    if v0 == 0 then v4 += 1
+    i := scratch
+    save v3
+    load v3
+  again
+
+: scratch
+  0x00 0x00 0x00 0x00
+";
+
+const INSTRUCTIONS: usize = 5_000_000;
+const ROUNDS: usize = 3;
+
+fn main() {
+    let program: Vec<u8> = assembler::assemble(SOURCE).expect("The benchmark source should assemble.");
+
+    let mut without_cache: f64 = 0.0;
+    let mut with_cache: f64 = 0.0;
+
+    // The best of a few rounds, taken in turns so both see the same noise.
+    for _ in 0..ROUNDS {
+        without_cache = without_cache.max(measure(&program, false));
+        with_cache = with_cache.max(measure(&program, true));
+    }
+
+    println!("Without the decode cache: {:>12.0} instructions per second", without_cache);
+    println!("With the decode cache:    {:>12.0} instructions per second", with_cache);
+    println!("Speedup:                  {:>12.2}x", with_cache / without_cache);
+}
+
+fn measure(program: &[u8], decode_cache: bool) -> f64 {
+    let mut machine: Machine = Machine::initialize();
+
+    machine.set_decode_cache(decode_cache);
+    machine.load_rom(program.to_vec()).expect("The benchmark ROM should fit in memory.");
+
+    let start: Instant = Instant::now();
+
+    for _ in 0..INSTRUCTIONS {
+        machine.step().expect("The benchmark ROM should only have known instructions.");
+    }
+
+    INSTRUCTIONS as f64 / start.elapsed().as_secs_f64()
+}
@@ -0,0 +1,341 @@
+use crate::disassembler::{self, Syntax};
+use crate::op::{self, Op};
+use std::collections::{BTreeMap, BTreeSet};
+
+// What an address was reached as. When it's reached in more than one way the
+// first of these wins, so a subroutine that is also jumped to stays one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Label {
+    Main,
+    Subroutine,
+    Table,
+    Jump,
+    Data,
+}
+
+impl Label {
+    fn get_name(&self, address: usize) -> String {
+        match self {
+            Label::Main => String::from("main"),
+            Label::Subroutine => format!("sub_{:03X}", address),
+            Label::Table => format!("table_{:03X}", address),
+            Label::Jump => format!("label_{:03X}", address),
+            Label::Data => format!("data_{:03X}", address),
+        }
+    }
+}
+
+// Follows every path the program can take from its start, instead of reading
+// it from one end to the other, so the sprites in between aren't taken for
+// code. Bnnn can't be followed, so the tables it jumps into are only labelled.
+pub struct Analysis {
+    origin: usize,
+    program: Vec<u8>,
+    code: BTreeSet<usize>,
+    indirect_jumps: BTreeSet<usize>,
+    labels: BTreeMap<usize, Label>,
+}
+
+impl Analysis {
+    const DATA_PER_LINE: usize = 8;
+
+    pub fn initialize(program: Vec<u8>, origin: usize) -> Self {
+        let mut analysis: Analysis = Analysis {
+            origin,
+            program,
+            code: BTreeSet::new(),
+            indirect_jumps: BTreeSet::new(),
+            labels: BTreeMap::new(),
+        };
+
+        analysis.add_label(origin, Label::Main);
+        analysis.follow(origin);
+
+        analysis
+    }
+
+    pub fn is_code(&self, address: usize) -> bool {
+        self.code.contains(&address)
+    }
+
+    pub fn get_label(&self, address: usize) -> Option<Label> {
+        self.labels.get(&address).copied()
+    }
+
+    pub fn get_indirect_jumps(&self) -> Vec<usize> {
+        self.indirect_jumps.iter().copied().collect()
+    }
+
+    fn follow(&mut self, start: usize) {
+        let mut pending: Vec<usize> = vec![start];
+
+        while let Some(address) = pending.pop() {
+            if self.code.contains(&address) {
+                continue;
+            }
+
+            let op: Op = match self.get_word(address).map(op::decode) {
+                Some(Op::Unknown { .. }) | None => continue,
+                Some(op) => op,
+            };
+
+            let length: usize = op.get_length();
+
+            if address + length > self.origin + self.program.len() {
+                continue;
+            }
+
+            self.code.insert(address);
+
+            let next: usize = address + length;
+
+            match op {
+                Op::Return | Op::Exit => {},
+                Op::Jump { nnn } => {
+                    self.add_label(usize::from(nnn), Label::Jump);
+                    pending.push(usize::from(nnn));
+                },
+                Op::Call { nnn } => {
+                    self.add_label(usize::from(nnn), Label::Subroutine);
+                    pending.push(usize::from(nnn));
+                    pending.push(next);
+                },
+                Op::SkipIfEqualImm { .. }
+                | Op::SkipIfNotEqualImm { .. }
+                | Op::SkipIfEqual { .. }
+                | Op::SkipIfNotEqual { .. }
+                | Op::SkipIfKey { .. }
+                | Op::SkipIfNotKey { .. } => {
+                    pending.push(next);
+                    pending.push(next + self.get_length(next));
+                },
+                Op::LoadI { nnn } => {
+                    self.add_label(usize::from(nnn), Label::Data);
+                    pending.push(next);
+                },
+                Op::JumpOffset { nnn, .. } => {
+                    self.indirect_jumps.insert(address);
+                    self.add_label(usize::from(nnn), Label::Table);
+                },
+                Op::LoadLongI => {
+                    if let Some(location) = self.get_word(address + 2) {
+                        self.add_label(usize::from(location), Label::Data);
+                    }
+
+                    pending.push(next);
+                },
+                _ => pending.push(next),
+            }
+        }
+    }
+
+    fn add_label(&mut self, address: usize, label: Label) {
+        let label: Label = match self.labels.get(&address) {
+            Some(existing) => label.min(*existing),
+            None => label,
+        };
+
+        self.labels.insert(address, label);
+    }
+
+    fn get_word(&self, address: usize) -> Option<u16> {
+        let offset: usize = address.checked_sub(self.origin)?;
+
+        match self.program.get(offset..offset + 2) {
+            Some([first_byte, second_byte]) => Some(u16::from(*first_byte) << 8 | u16::from(*second_byte)),
+            _ => None,
+        }
+    }
+
+    // On XO-CHIP, F000 nnnn carries its address in the next two bytes.
+    fn get_length(&self, address: usize) -> usize {
+        match self.get_word(address) {
+            Some(word) => op::decode(word).get_length(),
+            None => 2,
+        }
+    }
+
+    // Every instruction and every run of data up to the next label or
+    // instruction, which is where the listing can put the labels.
+    fn get_line_starts(&self) -> Vec<(usize, usize, bool)> {
+        let end: usize = self.origin + self.program.len();
+        let mut lines: Vec<(usize, usize, bool)> = Vec::new();
+        let mut address: usize = self.origin;
+
+        while address < end {
+            if self.code.contains(&address) {
+                let length: usize = self.get_length(address);
+
+                lines.push((address, length, true));
+                address += length;
+
+                continue;
+            }
+
+            let length: usize = (address + 1..end)
+                .take_while(|position| !self.code.contains(position) && !self.labels.contains_key(position))
+                .take(Analysis::DATA_PER_LINE - 1)
+                .count() + 1;
+
+            lines.push((address, length, false));
+            address += length;
+        }
+
+        lines
+    }
+
+    // Octo source that assembles back to the same bytes. Only the labels that
+    // start a line can be placed, and anything pointing somewhere else keeps
+    // its address.
+    pub fn listing(&self) -> String {
+        let lines: Vec<(usize, usize, bool)> = self.get_line_starts();
+
+        let names: BTreeMap<usize, String> = lines.iter()
+            .filter_map(|(address, _, _)| self.labels.get(address).map(|label| (*address, label.get_name(*address))))
+            .collect();
+
+        let mut listing: String = String::new();
+
+        for (address, length, is_code) in lines {
+            if let Some(name) = names.get(&address) {
+                listing.push_str(&format!(": {}\n", name));
+            }
+
+            let offset: usize = address - self.origin;
+            let bytes: &[u8] = &self.program[offset..offset + length];
+
+            let text: String = match is_code {
+                true => self.format_instruction(address, &names),
+                false => disassembler::format_data(bytes, Syntax::Octo),
+            };
+
+            listing.push_str(&format!("\t{}\n", text));
+        }
+
+        listing
+    }
+
+    fn format_instruction(&self, address: usize, names: &BTreeMap<usize, String>) -> String {
+        let opcode: u16 = self.get_word(address).unwrap_or(0x0);
+        let name = |target: u16| names.get(&usize::from(target)).cloned();
+
+        let text: Option<String> = match op::decode(opcode) {
+            Op::Jump { nnn } => name(nnn).map(|name| format!("jump {}", name)),
+            Op::Call { nnn } => name(nnn),
+            Op::LoadI { nnn } => name(nnn).map(|name| format!("i := {}", name)),
+            Op::JumpOffset { nnn, .. } => name(nnn).map(|name| format!("jump0 {}", name)),
+            Op::LoadLongI => {
+                let location: u16 = self.get_word(address + 2).unwrap_or(0x0);
+
+                Some(match name(location) {
+                    Some(name) => format!("i := long {}", name),
+                    None => format!("i := long {:#06X}", location),
+                })
+            },
+            _ => None,
+        };
+
+        text.or_else(|| disassembler::format_opcode(opcode, Syntax::Octo))
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Draws the sprite after the code, then calls a subroutine that skips on
+    // a key before returning, and loops.
+    const PROGRAM: [u8; 20] = [
+        0xA2, 0x10, 0xD0, 0x15, 0x22, 0x08, 0x12, 0x06, 0xE1, 0x9E, 0x70, 0x01, 0x00, 0xEE,
+        0x00, 0x00, 0xF0, 0x90, 0x90, 0xF0,
+    ];
+
+    #[test]
+    fn it_should_follow_the_code() {
+        let analysis: Analysis = Analysis::initialize(PROGRAM.to_vec(), 0x200);
+
+        let code: Vec<usize> = (0x200..0x214).filter(|address| analysis.is_code(*address)).collect();
+
+        assert_eq!(vec![0x200, 0x202, 0x204, 0x206, 0x208, 0x20A, 0x20C], code);
+    }
+
+    #[test]
+    fn it_should_label_the_targets() {
+        let analysis: Analysis = Analysis::initialize(PROGRAM.to_vec(), 0x200);
+
+        assert_eq!(Some(Label::Main), analysis.get_label(0x200));
+        assert_eq!(Some(Label::Jump), analysis.get_label(0x206));
+        assert_eq!(Some(Label::Subroutine), analysis.get_label(0x208));
+        assert_eq!(Some(Label::Data), analysis.get_label(0x210));
+        assert_eq!(None, analysis.get_label(0x202));
+    }
+
+    #[test]
+    fn it_should_follow_both_sides_of_a_skip() {
+        let analysis: Analysis = Analysis::initialize(vec![0x30, 0x01, 0x00, 0xFD, 0x12, 0x00], 0x200);
+
+        assert!(analysis.is_code(0x202));
+        assert!(analysis.is_code(0x204));
+    }
+
+    #[test]
+    fn it_should_skip_a_long_instruction_whole() {
+        let analysis: Analysis = Analysis::initialize(vec![0x30, 0x01, 0xF0, 0x00, 0x02, 0x08, 0x00, 0xFD, 0xAA], 0x200);
+
+        assert!(analysis.is_code(0x202));
+        assert!(!analysis.is_code(0x204));
+        assert!(analysis.is_code(0x206));
+        assert_eq!(Some(Label::Data), analysis.get_label(0x208));
+    }
+
+    #[test]
+    fn it_should_not_follow_an_indirect_jump() {
+        let analysis: Analysis = Analysis::initialize(vec![0xB2, 0x04, 0x12, 0x00, 0x12, 0x04], 0x200);
+
+        assert_eq!(vec![0x200], analysis.get_indirect_jumps());
+        assert_eq!(Some(Label::Table), analysis.get_label(0x204));
+        assert!(!analysis.is_code(0x202));
+        assert!(!analysis.is_code(0x204));
+    }
+
+    #[test]
+    fn it_should_keep_the_strongest_label() {
+        let analysis: Analysis = Analysis::initialize(vec![0x22, 0x06, 0x12, 0x06, 0x00, 0xFD, 0x00, 0xEE], 0x200);
+
+        assert_eq!(Some(Label::Subroutine), analysis.get_label(0x206));
+    }
+
+    #[test]
+    fn it_should_list_the_program_with_labels() {
+        let analysis: Analysis = Analysis::initialize(PROGRAM.to_vec(), 0x200);
+
+        assert_eq!(
+            ": main\n\
+             \ti := data_210\n\
+             \tsprite v0 v1 5\n\
+             \tsub_208\n\
+             : label_206\n\
+             \tjump label_206\n\
+             : sub_208\n\
+             \tif v1 -key then\n\
+             \tv0 += 0x01\n\
+             \treturn\n\
+             \t0x00 0x00\n\
+             : data_210\n\
+             \t0xF0 0x90 0x90 0xF0\n",
+            analysis.listing()
+        );
+    }
+
+    #[test]
+    fn it_should_keep_the_addresses_that_cannot_be_labelled() {
+        // The second jump lands in the middle of the long instruction.
+        let analysis: Analysis = Analysis::initialize(vec![0x12, 0x04, 0xF0, 0x00, 0x12, 0x02], 0x200);
+
+        assert_eq!(
+            ": main\n\tjump 0x204\n: label_202\n\ti := long 0x1202\n",
+            analysis.listing()
+        );
+    }
+}
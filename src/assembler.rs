@@ -0,0 +1,806 @@
+use crate::error::EmulatorError;
+use crate::memory::Memory;
+use std::collections::{HashMap, VecDeque};
+
+#[derive(Debug, Clone)]
+struct Token {
+    text: String,
+    line: usize,
+}
+
+struct Macro {
+    parameters: Vec<String>,
+    body: Vec<Token>,
+}
+
+// The jumps of the blocks are emitted before their targets are known, and
+// patched once the block closes.
+enum Block {
+    If { jump: usize },
+    Else { jump: usize },
+    Loop { start: usize, exits: Vec<usize> },
+}
+
+// A label used before it was defined, filled in at the end.
+struct Reference {
+    address: usize,
+    name: String,
+    line: usize,
+    is_long: bool,
+}
+
+// A condition as the two skips it can be written with: the one that skips
+// when it's false, for `then`, and the one that skips when it's true, for
+// jumping over a block.
+struct Condition {
+    skip_if_false: u16,
+    skip_if_true: u16,
+}
+
+// Assembles the Octo syntax, from the first byte of the program. Execution
+// starts at the main label, with a jump to it when it isn't the first thing
+// in the program.
+pub struct Assembler {
+    tokens: VecDeque<Token>,
+    line: usize,
+    rom: Vec<u8>,
+    has_jump_to_main: bool,
+    labels: HashMap<String, usize>,
+    constants: HashMap<String, i64>,
+    aliases: HashMap<String, usize>,
+    macros: HashMap<String, Macro>,
+    references: Vec<Reference>,
+    blocks: Vec<Block>,
+}
+
+pub fn assemble(source: &str) -> Result<Vec<u8>, EmulatorError> {
+    Assembler::initialize(source).assemble()
+}
+
+impl Assembler {
+    pub fn initialize(source: &str) -> Self {
+        let tokens: VecDeque<Token> = source.lines()
+            .enumerate()
+            .flat_map(|(index, line)| {
+                let code: &str = line.split('#').next().unwrap_or("");
+
+                code.split_whitespace()
+                    .map(move |text| Token { text: text.to_string(), line: index + 1 })
+                    .collect::<Vec<Token>>()
+            })
+            .collect();
+
+        Assembler {
+            tokens,
+            line: 0,
+            rom: vec![0x10, 0x0],
+            has_jump_to_main: true,
+            labels: HashMap::new(),
+            constants: HashMap::new(),
+            aliases: HashMap::new(),
+            macros: HashMap::new(),
+            references: Vec::new(),
+            blocks: Vec::new(),
+        }
+    }
+
+    pub fn assemble(mut self) -> Result<Vec<u8>, EmulatorError> {
+        while let Some(token) = self.tokens.pop_front() {
+            self.line = token.line;
+            self.compile_statement(&token.text)?;
+        }
+
+        if !self.blocks.is_empty() {
+            return Err(self.error("A block is missing its end or again."));
+        }
+
+        let main: usize = match self.labels.get("main") {
+            Some(main) => *main,
+            None => return Err(self.error("The program has no main label.")),
+        };
+
+        if self.has_jump_to_main {
+            self.patch(0x0, main);
+        }
+
+        for reference in std::mem::take(&mut self.references) {
+            let address: usize = match self.labels.get(&reference.name) {
+                Some(address) => *address,
+                None => {
+                    return Err(EmulatorError::InvalidSource {
+                        line: reference.line,
+                        message: format!("The label {} is not defined.", reference.name),
+                    });
+                },
+            };
+
+            let offset: usize = reference.address - Memory::PROGRAM_START;
+
+            match reference.is_long {
+                true => self.rom[offset..offset + 2].copy_from_slice(&(address as u16).to_be_bytes()),
+                false => self.patch(offset, address),
+            }
+        }
+
+        Ok(self.rom)
+    }
+
+    fn compile_statement(&mut self, token: &str) -> Result<(), EmulatorError> {
+        match token {
+            ":" => {
+                let name: String = self.next_token()?;
+
+                self.define_label(name)?;
+            },
+            ":const" => {
+                let name: String = self.next_token()?;
+                let value: String = self.next_token()?;
+                let value: i64 = self.get_value(&value)?;
+
+                self.constants.insert(name, value);
+            },
+            ":alias" => {
+                let name: String = self.next_token()?;
+                let register: String = self.next_token()?;
+                let register: usize = self.get_register(&register)?;
+
+                self.aliases.insert(name, register);
+            },
+            ":macro" => self.define_macro()?,
+            ":byte" => {
+                let value: String = self.next_token()?;
+                let byte: u8 = self.get_byte(&value)?;
+
+                self.rom.push(byte);
+            },
+            ":call" => {
+                let nnn: u16 = self.next_address()?;
+
+                self.emit(0x2000 | nnn);
+            },
+            "clear" => self.emit(0x00E0),
+            "return" | ";" => self.emit(0x00EE),
+            "scroll-down" => {
+                let n: u16 = self.next_nibble()?;
+
+                self.emit(0x00C0 | n);
+            },
+            "scroll-right" => self.emit(0x00FB),
+            "scroll-left" => self.emit(0x00FC),
+            "exit" => self.emit(0x00FD),
+            "lores" => self.emit(0x00FE),
+            "hires" => self.emit(0x00FF),
+            "jump" => {
+                let nnn: u16 = self.next_address()?;
+
+                self.emit(0x1000 | nnn);
+            },
+            "jump0" => {
+                let nnn: u16 = self.next_address()?;
+
+                self.emit(0xB000 | nnn);
+            },
+            "sprite" => {
+                let x: u16 = self.next_register()?;
+                let y: u16 = self.next_register()?;
+                let n: u16 = self.next_nibble()?;
+
+                self.emit(0xD000 | x << 8 | y << 4 | n);
+            },
+            "save" | "load" => self.compile_save_or_load(token)?,
+            "bcd" | "saveflags" | "loadflags" => {
+                let x: u16 = self.next_register()?;
+                let kk: u16 = match token {
+                    "bcd" => 0x33,
+                    "saveflags" => 0x75,
+                    _ => 0x85,
+                };
+
+                self.emit(0xF000 | x << 8 | kk);
+            },
+            "plane" => {
+                let n: u16 = self.next_nibble()?;
+
+                self.emit(0xF001 | n << 8);
+            },
+            "audio" => self.emit(0xF002),
+            "delay" | "buzzer" | "pitch" => {
+                self.expect(":=")?;
+
+                let x: u16 = self.next_register()?;
+                let kk: u16 = match token {
+                    "delay" => 0x15,
+                    "buzzer" => 0x18,
+                    _ => 0x3A,
+                };
+
+                self.emit(0xF000 | x << 8 | kk);
+            },
+            "i" => self.compile_i_register()?,
+            "if" => self.compile_if()?,
+            "else" => match self.blocks.pop() {
+                Some(Block::If { jump }) => {
+                    let address: usize = self.get_address();
+
+                    self.emit(0x1000);
+                    self.patch(jump - Memory::PROGRAM_START, self.get_address());
+                    self.blocks.push(Block::Else { jump: address });
+                },
+                _ => return Err(self.error("There is an else without an if.")),
+            },
+            "end" => match self.blocks.pop() {
+                Some(Block::If { jump } | Block::Else { jump }) => {
+                    self.patch(jump - Memory::PROGRAM_START, self.get_address());
+                },
+                _ => return Err(self.error("There is an end without an if.")),
+            },
+            "loop" => self.blocks.push(Block::Loop { start: self.get_address(), exits: Vec::new() }),
+            "while" => {
+                let condition: Condition = self.next_condition()?;
+                let address: usize = self.get_address() + 2;
+
+                match self.blocks.iter_mut().rev().find(|block| matches!(block, Block::Loop { .. })) {
+                    Some(Block::Loop { exits, .. }) => exits.push(address),
+                    _ => return Err(self.error("There is a while outside of a loop.")),
+                }
+
+                self.emit(condition.skip_if_true);
+                self.emit(0x1000);
+            },
+            "again" => match self.blocks.pop() {
+                Some(Block::Loop { start, exits }) => {
+                    self.emit(0x1000 | start as u16);
+
+                    for exit in exits {
+                        self.patch(exit - Memory::PROGRAM_START, self.get_address());
+                    }
+                },
+                _ => return Err(self.error("There is an again without a loop.")),
+            },
+            _ if self.macros.contains_key(token) => self.expand_macro(token)?,
+            _ if Assembler::is_register(token) || self.aliases.contains_key(token) => {
+                let x: u16 = self.get_register(token)? as u16;
+
+                self.compile_register(x)?;
+            },
+            _ if parse_number(token).is_some() || self.constants.contains_key(token) => {
+                let byte: u8 = self.get_byte(token)?;
+
+                self.rom.push(byte);
+            },
+            // Any other name calls the label, which may come later.
+            _ => {
+                let nnn: u16 = self.get_address_of(token)?;
+
+                self.emit(0x2000 | nnn);
+            },
+        }
+
+        Ok(())
+    }
+
+    fn compile_save_or_load(&mut self, token: &str) -> Result<(), EmulatorError> {
+        let x: u16 = self.next_register()?;
+
+        if self.peek_token() == Some("-") {
+            self.tokens.pop_front();
+
+            let y: u16 = self.next_register()?;
+
+            self.emit(match token {
+                "save" => 0x5002 | x << 8 | y << 4,
+                _ => 0x5003 | x << 8 | y << 4,
+            });
+        } else {
+            self.emit(match token {
+                "save" => 0xF055 | x << 8,
+                _ => 0xF065 | x << 8,
+            });
+        }
+
+        Ok(())
+    }
+
+    fn compile_i_register(&mut self) -> Result<(), EmulatorError> {
+        let operator: String = self.next_token()?;
+
+        match operator.as_str() {
+            ":=" => match self.next_token()?.as_str() {
+                "hex" => {
+                    let x: u16 = self.next_register()?;
+
+                    self.emit(0xF029 | x << 8);
+                },
+                "bighex" => {
+                    let x: u16 = self.next_register()?;
+
+                    self.emit(0xF030 | x << 8);
+                },
+                "long" => {
+                    let value: String = self.next_token()?;
+
+                    self.emit(0xF000);
+
+                    let word: u16 = match self.find_value(&value) {
+                        Some(value) if (0x0..=0xFFFF).contains(&value) => value as u16,
+                        Some(value) => return Err(self.error(&format!("The address {} does not fit in 16 bits.", value))),
+                        None => {
+                            self.add_reference(&value, true)?;
+
+                            0x0
+                        },
+                    };
+
+                    self.emit(word);
+                },
+                address => {
+                    let nnn: u16 = self.get_address_of(address)?;
+
+                    self.emit(0xA000 | nnn);
+                },
+            },
+            "+=" => {
+                let x: u16 = self.next_register()?;
+
+                self.emit(0xF01E | x << 8);
+            },
+            _ => return Err(self.error(&format!("The operator {} can't be used on i.", operator))),
+        }
+
+        Ok(())
+    }
+
+    fn compile_register(&mut self, x: u16) -> Result<(), EmulatorError> {
+        let operator: String = self.next_token()?;
+        let operand: String = self.next_token()?;
+
+        let is_register: bool = Assembler::is_register(&operand) || self.aliases.contains_key(&operand);
+
+        let opcode: u16 = match (operator.as_str(), operand.as_str()) {
+            (":=", "random") => {
+                let kk: u16 = self.next_byte()?;
+
+                0xC000 | x << 8 | kk
+            },
+            (":=", "key") => 0xF00A | x << 8,
+            (":=", "delay") => 0xF007 | x << 8,
+            (operator, _) if is_register => {
+                let y: u16 = self.get_register(&operand)? as u16;
+                let n: u16 = match operator {
+                    ":=" => 0x0,
+                    "|=" => 0x1,
+                    "&=" => 0x2,
+                    "^=" => 0x3,
+                    "+=" => 0x4,
+                    "-=" => 0x5,
+                    ">>=" => 0x6,
+                    "=-" => 0x7,
+                    "<<=" => 0xE,
+                    _ => return Err(self.error(&format!("The operator {} can't be used on registers.", operator))),
+                };
+
+                0x8000 | x << 8 | y << 4 | n
+            },
+            (":=", _) => 0x6000 | x << 8 | u16::from(self.get_byte(&operand)?),
+            ("+=", _) => 0x7000 | x << 8 | u16::from(self.get_byte(&operand)?),
+            ("-=", _) => 0x7000 | x << 8 | u16::from(self.get_byte(&operand)?.wrapping_neg()),
+            _ => return Err(self.error(&format!("The operator {} can't be used with {}.", operator, operand))),
+        };
+
+        self.emit(opcode);
+
+        Ok(())
+    }
+
+    fn compile_if(&mut self) -> Result<(), EmulatorError> {
+        let condition: Condition = self.next_condition()?;
+
+        match self.next_token()?.as_str() {
+            "then" => self.emit(condition.skip_if_false),
+            "begin" => {
+                self.emit(condition.skip_if_true);
+                self.blocks.push(Block::If { jump: self.get_address() });
+                self.emit(0x1000);
+            },
+            token => return Err(self.error(&format!("Expected then or begin, found {}.", token))),
+        }
+
+        Ok(())
+    }
+
+    fn next_condition(&mut self) -> Result<Condition, EmulatorError> {
+        let x: u16 = self.next_register()?;
+        let operator: String = self.next_token()?;
+
+        let (skip_if_false, skip_if_true): (u16, u16) = match operator.as_str() {
+            "key" => (0xE0A1, 0xE09E),
+            "-key" => (0xE09E, 0xE0A1),
+            "==" | "!=" => {
+                let operand: String = self.next_token()?;
+
+                let (equal, different): (u16, u16) = match Assembler::is_register(&operand) || self.aliases.contains_key(&operand) {
+                    true => {
+                        let y: u16 = self.get_register(&operand)? as u16;
+
+                        (0x5000 | y << 4, 0x9000 | y << 4)
+                    },
+                    false => {
+                        let kk: u16 = u16::from(self.get_byte(&operand)?);
+
+                        (0x3000 | kk, 0x4000 | kk)
+                    },
+                };
+
+                // Skipping when equal is how != runs the next instruction.
+                match operator.as_str() {
+                    "==" => (different, equal),
+                    _ => (equal, different),
+                }
+            },
+            _ => return Err(self.error(&format!("The comparison {} is not supported.", operator))),
+        };
+
+        Ok(Condition {
+            skip_if_false: skip_if_false | x << 8,
+            skip_if_true: skip_if_true | x << 8,
+        })
+    }
+
+    // When nothing was emitted before main, the jump to it isn't needed and
+    // the program starts right there.
+    fn define_label(&mut self, name: String) -> Result<(), EmulatorError> {
+        if self.labels.contains_key(&name) {
+            return Err(self.error(&format!("The label {} is defined twice.", name)));
+        }
+
+        if Assembler::is_register(&name) || parse_number(&name).is_some() {
+            return Err(self.error(&format!("The name {} can't be a label.", name)));
+        }
+
+        if name == "main" && self.has_jump_to_main && self.rom.len() == 2 {
+            self.rom.clear();
+            self.has_jump_to_main = false;
+
+            for address in self.labels.values_mut() {
+                *address = Memory::PROGRAM_START;
+            }
+
+            for block in self.blocks.iter_mut() {
+                if let Block::Loop { start, .. } = block {
+                    *start = Memory::PROGRAM_START;
+                }
+            }
+        }
+
+        self.labels.insert(name, self.get_address());
+
+        Ok(())
+    }
+
+    fn define_macro(&mut self) -> Result<(), EmulatorError> {
+        let name: String = self.next_token()?;
+        let mut parameters: Vec<String> = Vec::new();
+
+        loop {
+            match self.next_token()?.as_str() {
+                "{" => break,
+                parameter => parameters.push(parameter.to_string()),
+            }
+        }
+
+        let mut body: Vec<Token> = Vec::new();
+        let mut depth: usize = 1;
+
+        while depth > 0 {
+            let token: Token = match self.tokens.pop_front() {
+                Some(token) => token,
+                None => return Err(self.error(&format!("The macro {} is missing its }}.", name))),
+            };
+
+            match token.text.as_str() {
+                "{" => depth += 1,
+                "}" => depth -= 1,
+                _ => {},
+            }
+
+            if depth > 0 {
+                body.push(token);
+            }
+        }
+
+        self.macros.insert(name, Macro { parameters, body });
+
+        Ok(())
+    }
+
+    // The arguments replace the parameters in the body, which is then
+    // assembled as if it was written there.
+    fn expand_macro(&mut self, name: &str) -> Result<(), EmulatorError> {
+        let parameters: Vec<String> = self.macros[name].parameters.clone();
+        let mut arguments: HashMap<String, String> = HashMap::new();
+
+        for parameter in parameters {
+            let argument: String = self.next_token()?;
+
+            arguments.insert(parameter, argument);
+        }
+
+        let line: usize = self.line;
+
+        let body: Vec<Token> = self.macros[name].body
+            .iter()
+            .map(|token| Token {
+                text: arguments.get(&token.text).cloned().unwrap_or_else(|| token.text.clone()),
+                line,
+            })
+            .collect();
+
+        for token in body.into_iter().rev() {
+            self.tokens.push_front(token);
+        }
+
+        Ok(())
+    }
+
+    fn emit(&mut self, opcode: u16) {
+        self.rom.extend_from_slice(&opcode.to_be_bytes());
+    }
+
+    fn patch(&mut self, offset: usize, address: usize) {
+        self.rom[offset] = self.rom[offset] & 0xF0 | (address >> 8) as u8 & 0x0F;
+        self.rom[offset + 1] = address as u8;
+    }
+
+    fn get_address(&self) -> usize {
+        Memory::PROGRAM_START + self.rom.len()
+    }
+
+    fn error(&self, message: &str) -> EmulatorError {
+        EmulatorError::InvalidSource { line: self.line, message: message.to_string() }
+    }
+
+    fn next_token(&mut self) -> Result<String, EmulatorError> {
+        match self.tokens.pop_front() {
+            Some(token) => Ok(token.text),
+            None => Err(self.error("The source ends in the middle of a statement.")),
+        }
+    }
+
+    fn peek_token(&self) -> Option<&str> {
+        self.tokens.front().map(|token| token.text.as_str())
+    }
+
+    fn expect(&mut self, expected: &str) -> Result<(), EmulatorError> {
+        match self.next_token()? {
+            token if token == expected => Ok(()),
+            token => Err(self.error(&format!("Expected {}, found {}.", expected, token))),
+        }
+    }
+
+    fn is_register(token: &str) -> bool {
+        token.len() == 2
+            && token.starts_with(['v', 'V'])
+            && token[1..].chars().all(|digit| digit.is_ascii_hexdigit())
+    }
+
+    fn get_register(&self, token: &str) -> Result<usize, EmulatorError> {
+        if let Some(register) = self.aliases.get(token) {
+            return Ok(*register);
+        }
+
+        match Assembler::is_register(token) {
+            true => Ok(usize::from_str_radix(&token[1..], 16).unwrap_or(0x0)),
+            false => Err(self.error(&format!("Expected a register, found {}.", token))),
+        }
+    }
+
+    fn next_register(&mut self) -> Result<u16, EmulatorError> {
+        let token: String = self.next_token()?;
+
+        Ok(self.get_register(&token)? as u16)
+    }
+
+    fn find_value(&self, token: &str) -> Option<i64> {
+        parse_number(token)
+            .or_else(|| self.constants.get(token).copied())
+            .or_else(|| self.labels.get(token).map(|address| *address as i64))
+    }
+
+    fn get_value(&self, token: &str) -> Result<i64, EmulatorError> {
+        self.find_value(token)
+            .ok_or_else(|| self.error(&format!("The value {} is not defined.", token)))
+    }
+
+    // Octo takes negative bytes too, as their two's complement.
+    fn get_byte(&self, token: &str) -> Result<u8, EmulatorError> {
+        match self.get_value(token)? {
+            value if (-0x80..=0xFF).contains(&value) => Ok(value as u8),
+            value => Err(self.error(&format!("The value {} does not fit in a byte.", value))),
+        }
+    }
+
+    fn next_byte(&mut self) -> Result<u16, EmulatorError> {
+        let token: String = self.next_token()?;
+
+        Ok(u16::from(self.get_byte(&token)?))
+    }
+
+    fn next_nibble(&mut self) -> Result<u16, EmulatorError> {
+        let token: String = self.next_token()?;
+
+        match self.get_value(&token)? {
+            value if (0x0..=0xF).contains(&value) => Ok(value as u16),
+            value => Err(self.error(&format!("The value {} does not fit in a nibble.", value))),
+        }
+    }
+
+    fn get_address_of(&mut self, token: &str) -> Result<u16, EmulatorError> {
+        match self.find_value(token) {
+            Some(value) if (0x0..=0xFFF).contains(&value) => Ok(value as u16),
+            Some(value) => Err(self.error(&format!("The address {} does not fit in 12 bits.", value))),
+            None => {
+                self.add_reference(token, false)?;
+
+                Ok(0x0)
+            },
+        }
+    }
+
+    fn next_address(&mut self) -> Result<u16, EmulatorError> {
+        let token: String = self.next_token()?;
+
+        self.get_address_of(&token)
+    }
+
+    // The reference is to the next instruction, or to the word after F000.
+    fn add_reference(&mut self, name: &str, is_long: bool) -> Result<(), EmulatorError> {
+        if Assembler::is_register(name) || name.starts_with(':') {
+            return Err(self.error(&format!("Expected an address, found {}.", name)));
+        }
+
+        self.references.push(Reference {
+            address: self.get_address(),
+            name: name.to_string(),
+            line: self.line,
+            is_long,
+        });
+
+        Ok(())
+    }
+}
+
+fn parse_number(token: &str) -> Option<i64> {
+    if let Some(digits) = token.strip_prefix("0x").or_else(|| token.strip_prefix("0X")) {
+        return i64::from_str_radix(digits, 16).ok();
+    }
+
+    if let Some(digits) = token.strip_prefix("0b").or_else(|| token.strip_prefix("0B")) {
+        return i64::from_str_radix(digits, 2).ok();
+    }
+
+    token.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+    use super::*;
+    use crate::analysis::Analysis;
+
+    fn assemble_main(body: &str) -> Result<Vec<u8>, EmulatorError> {
+        assemble(&format!(": main\n{}", body))
+    }
+
+    #[test_case("clear return ; exit lores hires", vec![0x00E0, 0x00EE, 0x00EE, 0x00FD, 0x00FE, 0x00FF] ; "with the screen and flow instructions")]
+    #[test_case("scroll-down 4 scroll-left scroll-right", vec![0x00C4, 0x00FC, 0x00FB] ; "with the scrolls")]
+    #[test_case("jump 0x2A4 jump0 0x300 :call 0x206", vec![0x12A4, 0xB300, 0x2206] ; "with the jumps")]
+    #[test_case("va := 0x02 v1 += 1 v1 -= 1", vec![0x6A02, 0x7101, 0x71FF] ; "with the immediate values")]
+    #[test_case("v1 := v2 v1 |= v2 v1 &= v2 v1 ^= v2", vec![0x8120, 0x8121, 0x8122, 0x8123] ; "with the logic")]
+    #[test_case("v1 += v2 v1 -= v2 v1 >>= v2 v1 =- v2 v1 <<= v2", vec![0x8124, 0x8125, 0x8126, 0x8127, 0x812E] ; "with the arithmetic")]
+    #[test_case("v3 := random 0x0F v5 := key v5 := delay", vec![0xC30F, 0xF50A, 0xF507] ; "with the special values")]
+    #[test_case("delay := v5 buzzer := v5 pitch := v5", vec![0xF515, 0xF518, 0xF53A] ; "with the timers")]
+    #[test_case("i := 0x300 i += v5 i := hex v5 i := bighex v5", vec![0xA300, 0xF51E, 0xF529, 0xF530] ; "with the i register")]
+    #[test_case("sprite v1 v2 5 bcd v5 save v5 load v5", vec![0xD125, 0xF533, 0xF555, 0xF565] ; "with the memory")]
+    #[test_case("save v1 - v2 load v1 - v2 saveflags v5 loadflags v5", vec![0x5122, 0x5123, 0xF575, 0xF585] ; "with the ranges and flags")]
+    #[test_case("plane 2 audio", vec![0xF201, 0xF002] ; "with the xo-chip instructions")]
+    fn it_should_assemble_the_instructions(source: &str, opcodes: Vec<u16>) {
+        let bytes: Vec<u8> = opcodes.iter().flat_map(|opcode| opcode.to_be_bytes()).collect();
+
+        assert_eq!(Ok(bytes), assemble_main(source));
+    }
+
+    #[test_case("if v1 == 0x10 then", vec![0x41, 0x10] ; "with an equal value")]
+    #[test_case("if v1 != 0x10 then", vec![0x31, 0x10] ; "with a different value")]
+    #[test_case("if v1 == v2 then", vec![0x91, 0x20] ; "with an equal register")]
+    #[test_case("if v1 != v2 then", vec![0x51, 0x20] ; "with a different register")]
+    #[test_case("if v1 key then", vec![0xE1, 0xA1] ; "with a key pressed")]
+    #[test_case("if v1 -key then", vec![0xE1, 0x9E] ; "with a key not pressed")]
+    fn it_should_assemble_the_conditions(source: &str, bytes: Vec<u8>) {
+        assert_eq!(Ok(bytes), assemble_main(source));
+    }
+
+    #[test]
+    fn it_should_assemble_an_if_else_block() {
+        assert_eq!(
+            Ok(vec![0x31, 0x01, 0x12, 0x08, 0x62, 0x01, 0x12, 0x0A, 0x62, 0x02, 0x00, 0xFD]),
+            assemble_main("if v1 == 1 begin v2 := 1 else v2 := 2 end exit")
+        );
+    }
+
+    #[test]
+    fn it_should_assemble_a_loop() {
+        assert_eq!(
+            Ok(vec![0x71, 0x01, 0x41, 0x05, 0x12, 0x08, 0x12, 0x00, 0x00, 0xFD]),
+            assemble_main("loop v1 += 1 while v1 != 5 again exit")
+        );
+    }
+
+    #[test]
+    fn it_should_assemble_labels_used_before_and_after_their_definition() {
+        assert_eq!(
+            Ok(vec![0x22, 0x06, 0xA2, 0x08, 0x12, 0x02, 0x00, 0xEE, 0xF0, 0x90]),
+            assemble_main("draw\n: redraw\ni := digit\njump redraw\n: draw\nreturn\n: digit\n0xF0 0b10010000")
+        );
+    }
+
+    #[test]
+    fn it_should_jump_to_main_when_it_is_not_first() {
+        assert_eq!(
+            Ok(vec![0x12, 0x04, 0x00, 0xEE, 0x22, 0x02]),
+            assemble(": draw\nreturn\n: main\ndraw")
+        );
+    }
+
+    #[test]
+    fn it_should_assemble_a_long_load() {
+        assert_eq!(
+            Ok(vec![0xF0, 0x00, 0x02, 0x04, 0xAB]),
+            assemble_main("i := long data : data 0xAB")
+        );
+    }
+
+    #[test]
+    fn it_should_assemble_constants_and_aliases() {
+        assert_eq!(
+            Ok(vec![0x6A, 0x20, 0x00, 0xFD, 0x20]),
+            assemble_main(":const SPEED 0x20 :alias speed va\nspeed := SPEED exit :byte SPEED")
+        );
+    }
+
+    #[test]
+    fn it_should_expand_macros() {
+        assert_eq!(
+            Ok(vec![0x71, 0x02, 0x72, 0x03, 0x00, 0xFD]),
+            assemble_main(":macro increase register amount { register += amount }\nincrease v1 2\nincrease v2 3\nexit")
+        );
+    }
+
+    #[test]
+    fn it_should_ignore_comments() {
+        assert_eq!(Ok(vec![0x00, 0xE0]), assemble_main("clear # jump 0x200"));
+    }
+
+    #[test_case("clear", 1, "The program has no main label." ; "without a main label")]
+    #[test_case(": main\n: main", 2, "The label main is defined twice." ; "with a label defined twice")]
+    #[test_case(": main\njump nowhere", 2, "The label nowhere is not defined." ; "with an undefined label")]
+    #[test_case(": main\nv1 := 0x100", 2, "The value 256 does not fit in a byte." ; "with a value too large")]
+    #[test_case(": main\nif v1 > 2 then", 2, "The comparison > is not supported." ; "with an unsupported comparison")]
+    #[test_case(": main\nloop\nclear", 3, "A block is missing its end or again." ; "with an open loop")]
+    #[test_case(": main\nelse", 2, "There is an else without an if." ; "with an else without an if")]
+    #[test_case(": main\nsprite v1", 2, "The source ends in the middle of a statement." ; "with a statement cut short")]
+    fn it_should_report_the_errors(source: &str, line: usize, message: &str) {
+        assert_eq!(
+            Err(EmulatorError::InvalidSource { line, message: String::from(message) }),
+            assemble(source)
+        );
+    }
+
+    #[test_case(vec![0xA2, 0x10, 0xD0, 0x15, 0x22, 0x08, 0x12, 0x06, 0xE1, 0x9E, 0x70, 0x01, 0x00, 0xEE, 0x00, 0x00, 0xF0, 0x90, 0x90, 0xF0] ; "with code and sprites")]
+    #[test_case(vec![0x12, 0x04, 0xF0, 0x00, 0x12, 0x02] ; "with a jump into a long instruction")]
+    #[test_case(vec![0xB2, 0x04, 0x00, 0xFF, 0x12, 0x00, 0x12, 0x02, 0x01] ; "with an indirect jump")]
+    fn it_should_assemble_the_analysis_listing_back_to_the_rom(program: Vec<u8>) {
+        let listing: String = Analysis::initialize(program.clone(), Memory::PROGRAM_START).listing();
+
+        assert_eq!(Ok(program), assemble(&listing));
+    }
+}
@@ -0,0 +1,300 @@
+use crate::cpu::Cpu;
+use crate::error::EmulatorError;
+use crate::scheduler::Scheduler;
+use std::io::{self, Seek, SeekFrom, Write};
+
+pub trait Audio {
+    fn play(&mut self, samples: &[f32]) -> Result<(), EmulatorError>;
+
+    // Called once when the emulation stops, for the backends that have to
+    // close what they were writing.
+    fn finish(&mut self) -> Result<(), EmulatorError> {
+        Ok(())
+    }
+}
+
+pub struct NullAudio;
+
+impl Audio for NullAudio {
+    fn play(&mut self, _samples: &[f32]) -> Result<(), EmulatorError> {
+        Ok(())
+    }
+}
+
+pub struct ToneGenerator {
+    sample_rate: u32,
+    position: u64,
+    pattern_position: f64,
+}
+
+impl ToneGenerator {
+    pub const SAMPLE_RATE: u32 = 44100;
+    pub const FREQUENCY: u64 = 440;
+    pub const VOLUME: f32 = 0.25;
+    pub const PATTERN_RATE: f64 = 4000.0;
+
+    pub fn initialize(sample_rate: u32) -> Self {
+        ToneGenerator {
+            sample_rate,
+            position: 0,
+            pattern_position: 0.0,
+        }
+    }
+
+    pub fn get_samples_per_frame(&self) -> usize {
+        (self.sample_rate / Scheduler::FRAMES_PER_SECOND) as usize
+    }
+
+    // Generates the samples of a single frame: the XO-CHIP audio pattern or a
+    // square wave while the sound timer is running and silence otherwise.
+    pub fn generate(&mut self, cpu: &Cpu) -> Vec<f32> {
+        let samples: usize = self.get_samples_per_frame();
+
+        if cpu.get_sound_timer() == 0 {
+            self.position = 0;
+            self.pattern_position = 0.0;
+
+            return vec![0.0; samples];
+        }
+
+        if let Some(pattern) = cpu.get_audio_pattern() {
+            return self.generate_pattern(&pattern, cpu.get_pitch(), samples);
+        }
+
+        (0..samples)
+            .map(|_| {
+                let half_periods: u64 = self.position * 2 * ToneGenerator::FREQUENCY / u64::from(self.sample_rate);
+
+                self.position += 1;
+
+                match half_periods % 2 {
+                    0 => ToneGenerator::VOLUME,
+                    _ => -ToneGenerator::VOLUME,
+                }
+            })
+            .collect()
+    }
+
+    // The 128 bits of the pattern are played from the most significant one at
+    // 4000 * 2 ^ ((pitch - 64) / 48) bits per second, looping back to the start.
+    fn generate_pattern(&mut self, pattern: &[u8; Cpu::AUDIO_PATTERN_SIZE], pitch: u8, samples: usize) -> Vec<f32> {
+        let bits: f64 = (Cpu::AUDIO_PATTERN_SIZE * 8) as f64;
+        let rate: f64 = ToneGenerator::PATTERN_RATE * 2f64.powf((f64::from(pitch) - 64.0) / 48.0);
+        let step: f64 = rate / f64::from(self.sample_rate);
+
+        (0..samples)
+            .map(|_| {
+                let bit: usize = self.pattern_position as usize;
+
+                self.pattern_position = (self.pattern_position + step) % bits;
+
+                match pattern[bit / 8] >> (7 - bit % 8) & 0x1 {
+                    0x1 => ToneGenerator::VOLUME,
+                    _ => -ToneGenerator::VOLUME,
+                }
+            })
+            .collect()
+    }
+}
+
+pub struct WavAudio<W: Write + Seek> {
+    writer: W,
+    sample_rate: u32,
+    samples_written: u32,
+}
+
+impl<W: Write + Seek> WavAudio<W> {
+    const HEADER_SIZE: u32 = 44;
+
+    pub fn initialize(writer: W, sample_rate: u32) -> io::Result<Self> {
+        let mut audio: WavAudio<W> = WavAudio {
+            writer,
+            sample_rate,
+            samples_written: 0,
+        };
+
+        audio.write_header()?;
+
+        Ok(audio)
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+
+    // Mono 16-bit PCM. The sizes are only known at the end, so the header is
+    // written with no samples first and again by finish.
+    fn write_header(&mut self) -> io::Result<()> {
+        let data_size: u32 = self.samples_written * 2;
+
+        self.writer.seek(SeekFrom::Start(0))?;
+        self.writer.write_all(b"RIFF")?;
+        self.writer.write_all(&(WavAudio::<W>::HEADER_SIZE - 8 + data_size).to_le_bytes())?;
+        self.writer.write_all(b"WAVEfmt ")?;
+        self.writer.write_all(&16u32.to_le_bytes())?;
+        self.writer.write_all(&1u16.to_le_bytes())?;
+        self.writer.write_all(&1u16.to_le_bytes())?;
+        self.writer.write_all(&self.sample_rate.to_le_bytes())?;
+        self.writer.write_all(&(self.sample_rate * 2).to_le_bytes())?;
+        self.writer.write_all(&2u16.to_le_bytes())?;
+        self.writer.write_all(&16u16.to_le_bytes())?;
+        self.writer.write_all(b"data")?;
+        self.writer.write_all(&data_size.to_le_bytes())?;
+        self.writer.seek(SeekFrom::End(0))?;
+
+        Ok(())
+    }
+
+    fn write_samples(&mut self, samples: &[f32]) -> io::Result<()> {
+        for sample in samples {
+            let value: i16 = (sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16;
+
+            self.writer.write_all(&value.to_le_bytes())?;
+        }
+
+        self.samples_written += samples.len() as u32;
+
+        Ok(())
+    }
+
+    fn write_ending(&mut self) -> io::Result<()> {
+        self.write_header()?;
+        self.writer.flush()
+    }
+}
+
+impl<W: Write + Seek> Audio for WavAudio<W> {
+    fn play(&mut self, samples: &[f32]) -> Result<(), EmulatorError> {
+        self.write_samples(samples)
+            .map_err(|error| EmulatorError::AudioOutput { message: error.to_string() })
+    }
+
+    fn finish(&mut self) -> Result<(), EmulatorError> {
+        self.write_ending()
+            .map_err(|error| EmulatorError::AudioOutput { message: error.to_string() })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn it_should_generate_silence_without_the_sound_timer() {
+        let mut tone_generator: ToneGenerator = ToneGenerator::initialize(44100);
+        let cpu: Cpu = Cpu::initialize();
+
+        assert_eq!(vec![0.0; 735], tone_generator.generate(&cpu));
+    }
+
+    #[test]
+    fn it_should_generate_a_square_wave_while_the_sound_timer_runs() {
+        let mut tone_generator: ToneGenerator = ToneGenerator::initialize(4400);
+        let mut cpu: Cpu = Cpu::initialize();
+
+        cpu.set_sound_timer(0x1);
+
+        let samples: Vec<f32> = tone_generator.generate(&cpu);
+
+        assert_eq!(73, samples.len());
+        assert_eq!([0.25; 5], samples[0..5]);
+        assert_eq!([-0.25; 5], samples[5..10]);
+        assert_eq!([0.25; 5], samples[10..15]);
+    }
+
+    #[test]
+    fn it_should_keep_the_wave_phase_between_frames() {
+        let mut tone_generator: ToneGenerator = ToneGenerator::initialize(4400);
+        let mut cpu: Cpu = Cpu::initialize();
+
+        cpu.set_sound_timer(0x2);
+
+        tone_generator.generate(&cpu);
+
+        // 73 samples are 7 periods of 10 samples and 3 more on the high half.
+        assert_eq!([0.25, 0.25, -0.25], tone_generator.generate(&cpu)[0..3]);
+    }
+
+    #[test]
+    fn it_should_play_the_audio_pattern_at_the_pitch_rate() {
+        let mut tone_generator: ToneGenerator = ToneGenerator::initialize(1000);
+        let mut cpu: Cpu = Cpu::initialize();
+        let mut pattern: [u8; Cpu::AUDIO_PATTERN_SIZE] = [0x0; Cpu::AUDIO_PATTERN_SIZE];
+
+        pattern[0x0] = 0b10000000;
+        pattern[0x2] = 0b10000000;
+
+        cpu.set_sound_timer(0x1);
+        cpu.set_audio_pattern(pattern);
+        cpu.set_pitch(112);
+
+        // A pitch of 112 doubles the rate to 8000 bits per second, so every
+        // sample moves a whole byte forward in the pattern.
+        let samples: Vec<f32> = tone_generator.generate(&cpu);
+
+        assert_eq!(16, samples.len());
+        assert_eq!([0.25, -0.25, 0.25, -0.25], samples[0..4]);
+    }
+
+    #[test]
+    fn it_should_loop_the_audio_pattern_between_frames() {
+        let mut tone_generator: ToneGenerator = ToneGenerator::initialize(500);
+        let mut cpu: Cpu = Cpu::initialize();
+        let mut pattern: [u8; Cpu::AUDIO_PATTERN_SIZE] = [0x0; Cpu::AUDIO_PATTERN_SIZE];
+
+        pattern[0x0] = 0b10000000;
+        pattern[0xF] = 0b10000000;
+
+        cpu.set_sound_timer(0x3);
+        cpu.set_audio_pattern(pattern);
+
+        // At the default pitch every sample moves a byte forward and every
+        // frame plays half of the pattern.
+        assert_eq!(vec![0.25, -0.25, -0.25, -0.25, -0.25, -0.25, -0.25, -0.25], tone_generator.generate(&cpu));
+        assert_eq!(vec![-0.25, -0.25, -0.25, -0.25, -0.25, -0.25, -0.25, 0.25], tone_generator.generate(&cpu));
+        assert_eq!(0.25, tone_generator.generate(&cpu)[0]);
+    }
+
+    #[test]
+    fn it_should_write_a_wav_header() {
+        let audio: WavAudio<Cursor<Vec<u8>>> = WavAudio::initialize(Cursor::new(Vec::new()), 44100).unwrap();
+
+        let bytes: Vec<u8> = audio.into_inner().into_inner();
+
+        assert_eq!(44, bytes.len());
+        assert_eq!(b"RIFF", &bytes[0..4]);
+        assert_eq!(36u32.to_le_bytes(), bytes[4..8]);
+        assert_eq!(b"WAVEfmt ", &bytes[8..16]);
+        assert_eq!(44100u32.to_le_bytes(), bytes[24..28]);
+        assert_eq!(b"data", &bytes[36..40]);
+        assert_eq!(0u32.to_le_bytes(), bytes[40..44]);
+    }
+
+    #[test]
+    fn it_should_write_the_samples_to_the_wav_file() {
+        let mut audio: WavAudio<Cursor<Vec<u8>>> = WavAudio::initialize(Cursor::new(Vec::new()), 44100).unwrap();
+
+        audio.play(&[1.0, -1.0]).unwrap();
+        audio.play(&[0.0]).unwrap();
+        audio.finish().unwrap();
+
+        let bytes: Vec<u8> = audio.into_inner().into_inner();
+
+        assert_eq!(50, bytes.len());
+        assert_eq!(42u32.to_le_bytes(), bytes[4..8]);
+        assert_eq!(6u32.to_le_bytes(), bytes[40..44]);
+        assert_eq!(i16::MAX.to_le_bytes(), bytes[44..46]);
+        assert_eq!((-i16::MAX).to_le_bytes(), bytes[46..48]);
+        assert_eq!([0x0, 0x0], bytes[48..50]);
+    }
+
+    #[test]
+    fn it_should_fail_to_play_when_the_samples_cannot_be_written() {
+        // Only the header fits, like on a full disk.
+        let mut bytes: [u8; 44] = [0x0; 44];
+        let mut audio: WavAudio<Cursor<&mut [u8]>> = WavAudio::initialize(Cursor::new(&mut bytes[..]), 44100).unwrap();
+
+        assert!(matches!(audio.play(&[1.0]), Err(EmulatorError::AudioOutput { .. })));
+    }
+}
@@ -0,0 +1,95 @@
+use chip8rs::assembler;
+use std::error::Error;
+use std::path::Path;
+use std::{env, fs, process};
+
+#[derive(Debug, PartialEq)]
+struct Options {
+    source_path: String,
+    output: String,
+}
+
+impl Options {
+    fn parse(args: Vec<String>) -> Result<Self, String> {
+        let mut source_path: Option<String> = None;
+        let mut output: Option<String> = None;
+
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--output" => {
+                    output = Some(args.next().ok_or("The option --output requires a path.")?);
+                },
+                _ if arg.starts_with("--") => return Err(format!("Unknown option {}.", arg)),
+                _ => source_path = Some(arg),
+            }
+        }
+
+        let source_path: String = source_path.ok_or("The location to the Octo source is required.")?;
+
+        // The ROM goes next to the source unless told otherwise.
+        let output: String = output.unwrap_or_else(|| {
+            Path::new(&source_path).with_extension("ch8").to_string_lossy().into_owned()
+        });
+
+        Ok(Options { source_path, output })
+    }
+}
+
+fn main() {
+    if let Err(error) = run(env::args().skip(1).collect()) {
+        eprintln!("{}", error);
+
+        process::exit(1);
+    }
+}
+
+fn run(args: Vec<String>) -> Result<(), Box<dyn Error>> {
+    let options: Options = Options::parse(args)?;
+
+    let source: String = fs::read_to_string(&options.source_path)
+        .map_err(|error| format!("The source {} could not be read: {}", options.source_path, error))?;
+
+    let program: Vec<u8> = assembler::assemble(&source)
+        .map_err(|error| format!("{}: {}", options.source_path, error))?;
+
+    fs::write(&options.output, &program)
+        .map_err(|error| format!("The ROM could not be written to {}: {}", options.output, error))?;
+
+    println!("Assembled {} bytes into {}.", program.len(), options.output);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn arguments(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn it_should_write_the_rom_next_to_the_source() {
+        assert_eq!(
+            Ok(Options { source_path: String::from("games/Pong.8o"), output: String::from("games/Pong.ch8") }),
+            Options::parse(arguments(&["games/Pong.8o"]))
+        );
+    }
+
+    #[test]
+    fn it_should_parse_the_output_path() {
+        let options: Options = Options::parse(arguments(&["--output", "Game.ch8", "Pong.8o"])).unwrap();
+
+        assert_eq!("Game.ch8", options.output);
+    }
+
+    #[test]
+    fn it_should_fail_without_a_source_path() {
+        assert_eq!(
+            Err(String::from("The location to the Octo source is required.")),
+            Options::parse(arguments(&["--output", "Game.ch8"]))
+        );
+    }
+}
@@ -0,0 +1,110 @@
+use chip8rs::analysis::Analysis;
+use chip8rs::disassembler::{self, Line, Syntax};
+use chip8rs::memory::Memory;
+use std::error::Error;
+use std::{env, fs, process};
+
+#[derive(Debug, PartialEq)]
+struct Options {
+    rom_path: String,
+    syntax: Syntax,
+    analyze: bool,
+}
+
+impl Options {
+    fn parse(args: Vec<String>) -> Result<Self, String> {
+        let mut rom_path: Option<String> = None;
+        let mut syntax: Syntax = Syntax::Cowgod;
+        let mut analyze: bool = false;
+
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--syntax" => {
+                    syntax = args.next()
+                        .ok_or("The option --syntax requires a syntax.")?
+                        .parse()?;
+                },
+                "--analyze" => analyze = true,
+                _ if arg.starts_with("--") => return Err(format!("Unknown option {}.", arg)),
+                _ => rom_path = Some(arg),
+            }
+        }
+
+        Ok(Options {
+            rom_path: rom_path.ok_or("The location to the chip8 ROM is required.")?,
+            syntax,
+            analyze,
+        })
+    }
+}
+
+fn main() {
+    if let Err(error) = run(env::args().skip(1).collect()) {
+        eprintln!("{}", error);
+
+        process::exit(1);
+    }
+}
+
+fn run(args: Vec<String>) -> Result<(), Box<dyn Error>> {
+    let options: Options = Options::parse(args)?;
+
+    let program: Vec<u8> = fs::read(&options.rom_path)
+        .map_err(|error| format!("The ROM {} could not be read: {}", options.rom_path, error))?;
+
+    // The analysis is meant to be assembled again, so it's always Octo.
+    if options.analyze {
+        print!("{}", Analysis::initialize(program, Memory::PROGRAM_START).listing());
+
+        return Ok(());
+    }
+
+    let lines: Vec<Line> = disassembler::disassemble(&program, Memory::PROGRAM_START, options.syntax);
+
+    for line in lines {
+        println!("{}", line);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn arguments(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn it_should_parse_the_rom_path() {
+        assert_eq!(
+            Ok(Options { rom_path: String::from("Pong.ch8"), syntax: Syntax::Cowgod, analyze: false }),
+            Options::parse(arguments(&["Pong.ch8"]))
+        );
+    }
+
+    #[test]
+    fn it_should_parse_the_syntax() {
+        let options: Options = Options::parse(arguments(&["--syntax", "octo", "Pong.ch8"])).unwrap();
+
+        assert_eq!(Syntax::Octo, options.syntax);
+    }
+
+    #[test]
+    fn it_should_parse_the_analyze_flag() {
+        let options: Options = Options::parse(arguments(&["--analyze", "Pong.ch8"])).unwrap();
+
+        assert!(options.analyze);
+    }
+
+    #[test]
+    fn it_should_fail_without_a_rom_path() {
+        assert_eq!(
+            Err(String::from("The location to the chip8 ROM is required.")),
+            Options::parse(arguments(&["--syntax", "octo"]))
+        );
+    }
+}
@@ -1,9 +1,12 @@
+use crate::error::EmulatorError;
+use crate::framebuffer::Framebuffer;
 use crate::instruction::Instruction;
+use crate::keypad::Keypad;
 use crate::memory::Memory;
-use mockall_double::double;
-
-#[double]
-use crate::interface::Interface;
+use crate::quirks::Quirks;
+use crate::random::Random;
+use crate::state::{StateReader, StateWriter};
+use crate::watchpoint::{self, Trigger, Watchpoint};
 
 #[derive(Debug)]
 pub struct Cpu {
@@ -14,10 +17,22 @@ pub struct Cpu {
     program_counter: usize,
     stack_empty: bool,
     stack_pointer: usize,
-    stack: [u16; 0xF],
+    stack: [u16; Cpu::STACK_SIZE],
+    halted: bool,
+    rpl_flags: [u8; Cpu::RPL_FLAGS],
+    audio_pattern: Option<[u8; Cpu::AUDIO_PATTERN_SIZE]>,
+    pitch: u8,
+    random: Random,
+    watchpoints: Vec<Watchpoint>,
+    trigger: Option<Trigger>,
 }
 
 impl Cpu {
+    pub const STACK_SIZE: usize = 16;
+    pub const RPL_FLAGS: usize = 16;
+    pub const AUDIO_PATTERN_SIZE: usize = 16;
+    pub const DEFAULT_PITCH: u8 = 64;
+
     pub fn initialize() -> Self {
         Cpu {
             v_registers: [0x0; 16],
@@ -27,21 +42,110 @@ impl Cpu {
             program_counter: 0x200,
             stack_empty: true,
             stack_pointer: 0x0,
-            stack: [0x0; 0xF],
+            stack: [0x0; Cpu::STACK_SIZE],
+            halted: false,
+            rpl_flags: [0x0; Cpu::RPL_FLAGS],
+            audio_pattern: None,
+            pitch: Cpu::DEFAULT_PITCH,
+            random: Random::initialize(Random::DEFAULT_SEED),
+            watchpoints: Vec::new(),
+            trigger: None,
+        }
+    }
+
+    pub fn save_state(&self, writer: &mut StateWriter) {
+        writer.write_bytes(&self.v_registers);
+        writer.write_u16(self.i_register);
+        writer.write_u8(self.delay_timer);
+        writer.write_u8(self.sound_timer);
+        writer.write_u32(self.program_counter as u32);
+        writer.write_bool(self.stack_empty);
+        writer.write_u8(self.stack_pointer as u8);
+
+        for value in self.stack {
+            writer.write_u16(value);
+        }
+
+        writer.write_bool(self.halted);
+        writer.write_bytes(&self.rpl_flags);
+
+        match self.audio_pattern {
+            Some(pattern) => {
+                writer.write_bool(true);
+                writer.write_bytes(&pattern);
+            },
+            None => writer.write_bool(false),
+        }
+
+        writer.write_u8(self.pitch);
+        writer.write_u64(self.random.get_state());
+    }
+
+    pub fn load_state(reader: &mut StateReader) -> Result<Self, EmulatorError> {
+        let mut cpu: Cpu = Cpu::initialize();
+
+        cpu.v_registers = reader.read_array()?;
+        cpu.i_register = reader.read_u16()?;
+        cpu.delay_timer = reader.read_u8()?;
+        cpu.sound_timer = reader.read_u8()?;
+        cpu.program_counter = reader.read_u32()? as usize;
+        cpu.stack_empty = reader.read_bool()?;
+        cpu.stack_pointer = reader.read_u8()? as usize;
+
+        if cpu.stack_pointer >= Cpu::STACK_SIZE {
+            return Err(EmulatorError::InvalidState);
         }
+
+        for value in cpu.stack.iter_mut() {
+            *value = reader.read_u16()?;
+        }
+
+        cpu.halted = reader.read_bool()?;
+        cpu.rpl_flags = reader.read_array()?;
+
+        if reader.read_bool()? {
+            cpu.audio_pattern = Some(reader.read_array()?);
+        }
+
+        cpu.pitch = reader.read_u8()?;
+        cpu.random.set_state(reader.read_u64()?);
+
+        Ok(cpu)
     }
 
-    pub fn fetch_and_decode(&mut self, memory: &mut Memory, interface: &mut Interface) {
-        let first_byte: u8 = memory.get(self.program_counter);
+    pub fn fetch_and_decode(
+        &mut self,
+        memory: &mut Memory,
+        framebuffer: &mut Framebuffer,
+        keypad: &Keypad,
+        quirks: &Quirks,
+    ) -> Result<(), EmulatorError> {
+        let address: usize = self.program_counter;
+
+        let mut instruction: Instruction = match memory.get_decoded(address) {
+            Some(instruction) => {
+                self.increase_program_counter(0x2);
+
+                instruction
+            },
+            None => {
+                let first_byte: u8 = memory.get(self.program_counter)?;
+
+                self.increase_program_counter(0x1);
 
-        self.increase_program_counter(0x1);
+                let second_byte: u8 = memory.get(self.program_counter)?;
 
-        let second_byte: u8 = memory.get(self.program_counter);
+                self.increase_program_counter(0x1);
 
-        self.increase_program_counter(0x1);
+                let instruction: Instruction = Instruction::initialize(first_byte, second_byte);
 
-        Instruction::initialize(first_byte, second_byte)
-            .interpret(self, memory, interface);
+                memory.set_decoded(address, instruction);
+
+                instruction
+            },
+        };
+
+        instruction.interpret(self, memory, framebuffer, keypad, quirks)
     }
 
     pub fn decrease_timers_on_tick(&mut self) {
@@ -51,8 +155,6 @@ impl Cpu {
 
         if self.sound_timer > 0 {
             self.sound_timer -= 1;
-        } else {
-            // deal with sounds
         }
     }
 
@@ -72,9 +174,9 @@ impl Cpu {
         self.program_counter
     }
 
-    pub fn stack_push(&mut self, value: u16) {
-        if self.stack_pointer == 0xF {
-            panic!("The CPU has a stack overflow.");
+    pub fn stack_push(&mut self, value: u16) -> Result<(), EmulatorError> {
+        if !self.stack_empty && self.stack_pointer == Cpu::STACK_SIZE - 1 {
+            return Err(EmulatorError::StackOverflow);
         }
 
         if self.stack_empty {
@@ -84,11 +186,13 @@ impl Cpu {
         }
 
         self.stack[self.stack_pointer] = value;
+
+        Ok(())
     }
 
-    pub fn stack_pop(&mut self) -> u16 {
-        if self.stack_empty == true {
-            panic!("The CPU has a stack underflow.");
+    pub fn stack_pop(&mut self) -> Result<u16, EmulatorError> {
+        if self.stack_empty {
+            return Err(EmulatorError::StackUnderflow);
         }
 
         let stack_value: u16 = self.stack[self.stack_pointer];
@@ -101,11 +205,23 @@ impl Cpu {
             self.stack_pointer -= 1;
         }
 
-        stack_value
+        Ok(stack_value)
+    }
+
+    // The addresses on the stack, from the oldest call to the latest.
+    pub fn get_stack(&self) -> &[u16] {
+        match self.stack_empty {
+            true => &[],
+            false => &self.stack[..=self.stack_pointer],
+        }
     }
 
     pub fn set_v_register(&mut self, register: usize, value: u8) {
         self.v_registers[register] = value;
+
+        if !self.watchpoints.is_empty() {
+            self.watch(Trigger::VRegister { register, value });
+        }
     }
 
     pub fn get_v_register(&self, register: usize) -> u8 {
@@ -114,6 +230,10 @@ impl Cpu {
 
     pub fn set_i_register(&mut self, value: u16) {
         self.i_register = value;
+
+        if !self.watchpoints.is_empty() {
+            self.watch(Trigger::IRegister { value });
+        }
     }
 
     pub fn get_i_register(&self) -> u16 {
@@ -135,6 +255,72 @@ impl Cpu {
     pub fn get_sound_timer(&self) -> u8 {
         self.sound_timer
     }
+
+    pub fn set_audio_pattern(&mut self, pattern: [u8; Cpu::AUDIO_PATTERN_SIZE]) {
+        self.audio_pattern = Some(pattern);
+    }
+
+    pub fn get_audio_pattern(&self) -> Option<[u8; Cpu::AUDIO_PATTERN_SIZE]> {
+        self.audio_pattern
+    }
+
+    pub fn set_pitch(&mut self, value: u8) {
+        self.pitch = value;
+    }
+
+    pub fn get_pitch(&self) -> u8 {
+        self.pitch
+    }
+
+    pub fn set_rpl_flag(&mut self, flag: usize, value: u8) {
+        self.rpl_flags[flag] = value;
+    }
+
+    pub fn get_rpl_flag(&self, flag: usize) -> u8 {
+        self.rpl_flags[flag]
+    }
+
+    pub fn set_rpl_flags(&mut self, flags: [u8; Cpu::RPL_FLAGS]) {
+        self.rpl_flags = flags;
+    }
+
+    pub fn get_rpl_flags(&self) -> [u8; Cpu::RPL_FLAGS] {
+        self.rpl_flags
+    }
+
+    pub fn next_random_byte(&mut self) -> u8 {
+        self.random.next_byte()
+    }
+
+    pub fn set_random_state(&mut self, state: u64) {
+        self.random.set_state(state);
+    }
+
+    pub fn get_random_state(&self) -> u64 {
+        self.random.get_state()
+    }
+
+    pub fn set_watchpoints(&mut self, watchpoints: Vec<Watchpoint>) {
+        self.watchpoints = watchpoints;
+    }
+
+    pub fn take_trigger(&mut self) -> Option<Trigger> {
+        self.trigger.take()
+    }
+
+    fn watch(&mut self, trigger: Trigger) {
+        if let Some(trigger) = watchpoint::check(&self.watchpoints, trigger) {
+            self.trigger = Some(trigger);
+        }
+    }
+
+    pub fn halt(&mut self) {
+        self.halted = true;
+    }
+
+    pub fn is_halted(&self) -> bool {
+        self.halted
+    }
 }
 
 #[cfg(test)]
@@ -151,7 +337,11 @@ mod tests {
         assert_eq!(0x0, cpu.sound_timer);
         assert_eq!(0x200, cpu.program_counter);
         assert_eq!(0x0, cpu.stack_pointer);
-        assert_eq!([0x0; 0xF], cpu.stack);
+        assert_eq!([0x0; 16], cpu.stack);
+        assert!(!cpu.halted);
+        assert_eq!([0x0; 16], cpu.rpl_flags);
+        assert_eq!(None, cpu.audio_pattern);
+        assert_eq!(64, cpu.pitch);
     }
 
     #[test]
@@ -206,72 +396,85 @@ mod tests {
     }
 
     #[test]
-    #[should_panic(expected = "The CPU has a stack overflow.")]
     fn it_should_fail_to_push_to_the_stack_if_it_is_overflowing() {
         let mut cpu: Cpu = Cpu::initialize();
 
-        cpu.stack_pointer = 0xF;
+        for value in 0..16 {
+            cpu.stack_push(value).unwrap();
+        }
 
-        cpu.stack_push(0xFE);
+        assert_eq!(0xF, cpu.stack_pointer);
+        assert_eq!(Err(EmulatorError::StackOverflow), cpu.stack_push(0xFE));
     }
 
     #[test]
     fn it_should_push_to_an_empty_stack() {
         let mut cpu: Cpu = Cpu::initialize();
 
-        cpu.stack_push(0xCA);
+        cpu.stack_push(0xCA).unwrap();
         
         assert_eq!(0xCA, cpu.stack[0x0]);
         assert_eq!(0x0, cpu.stack_pointer);
-        assert_eq!(false, cpu.stack_empty);
+        assert!(!cpu.stack_empty);
     }
 
     #[test]
     fn it_should_push_to_an_initiated_stack() {
         let mut cpu: Cpu = Cpu::initialize();
 
-        cpu.stack_push(0xCA);
-        cpu.stack_push(0xFE);
+        cpu.stack_push(0xCA).unwrap();
+        cpu.stack_push(0xFE).unwrap();
         
         assert_eq!(0xCA, cpu.stack[0x0]);
         assert_eq!(0xFE, cpu.stack[0x1]);
         assert_eq!(0x1, cpu.stack_pointer);
-        assert_eq!(false, cpu.stack_empty);
+        assert!(!cpu.stack_empty);
+    }
+
+    #[test]
+    fn it_should_get_the_stack() {
+        let mut cpu: Cpu = Cpu::initialize();
+
+        assert!(cpu.get_stack().is_empty());
+
+        cpu.stack_push(0xCA).unwrap();
+        cpu.stack_push(0xFE).unwrap();
+
+        assert_eq!(&[0xCA, 0xFE], cpu.get_stack());
     }
 
     #[test]
-    #[should_panic(expected = "The CPU has a stack underflow.")]
     fn it_should_fail_to_pop_from_an_empty_stack() {
         let mut cpu: Cpu = Cpu::initialize();
 
-        cpu.stack_pop();
+        assert_eq!(Err(EmulatorError::StackUnderflow), cpu.stack_pop());
     }
 
     #[test]
     fn it_should_pop_from_the_last_position() {
         let mut cpu: Cpu = Cpu::initialize();
 
-        cpu.stack_push(0xCA);
+        cpu.stack_push(0xCA).unwrap();
 
-        let value: u16 = cpu.stack_pop();
+        let value: u16 = cpu.stack_pop().unwrap();
         
         assert_eq!(0xCA, value);
         assert_eq!(0x0, cpu.stack_pointer);
-        assert_eq!(true, cpu.stack_empty);
+        assert!(cpu.stack_empty);
     }
 
     #[test]
     fn it_should_pop_from_another_position() {
         let mut cpu: Cpu = Cpu::initialize();
 
-        cpu.stack_push(0xCA);
-        cpu.stack_push(0xFE);
+        cpu.stack_push(0xCA).unwrap();
+        cpu.stack_push(0xFE).unwrap();
 
-        let value: u16 = cpu.stack_pop();
+        let value: u16 = cpu.stack_pop().unwrap();
         
         assert_eq!(0xFE, value);
         assert_eq!(0x0, cpu.stack_pointer);
-        assert_eq!(false, cpu.stack_empty);
+        assert!(!cpu.stack_empty);
     }
 
     #[test]
@@ -345,4 +548,83 @@ mod tests {
 
         assert_eq!(0xFE, cpu.get_sound_timer());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn it_should_save_and_load_the_state() {
+        let mut cpu: Cpu = Cpu::initialize();
+
+        cpu.set_v_register(0x3, 0xCA);
+        cpu.set_i_register(0x3FE);
+        cpu.set_delay_timer(0x10);
+        cpu.set_sound_timer(0x20);
+        cpu.set_program_counter(0x2AC);
+        cpu.stack_push(0x204).unwrap();
+        cpu.stack_push(0x208).unwrap();
+        cpu.set_rpl_flag(0x7, 0xFE);
+        cpu.set_audio_pattern([0xF0; Cpu::AUDIO_PATTERN_SIZE]);
+        cpu.set_pitch(0x70);
+        cpu.set_random_state(0xCAFE);
+
+        let mut writer: StateWriter = StateWriter::initialize();
+
+        cpu.save_state(&mut writer);
+
+        let bytes: Vec<u8> = writer.into_bytes();
+        let mut reader: StateReader = StateReader::initialize(&bytes).unwrap();
+        let mut restored: Cpu = Cpu::load_state(&mut reader).unwrap();
+
+        reader.finish().unwrap();
+
+        assert_eq!(0xCA, restored.get_v_register(0x3));
+        assert_eq!(0x3FE, restored.get_i_register());
+        assert_eq!(0x10, restored.get_delay_timer());
+        assert_eq!(0x20, restored.get_sound_timer());
+        assert_eq!(0x2AC, restored.get_program_counter());
+        assert_eq!(0xFE, restored.get_rpl_flag(0x7));
+        assert_eq!(Some([0xF0; Cpu::AUDIO_PATTERN_SIZE]), restored.get_audio_pattern());
+        assert_eq!(0x70, restored.get_pitch());
+        assert_eq!(cpu.next_random_byte(), restored.next_random_byte());
+        assert_eq!(Ok(0x208), restored.stack_pop());
+        assert_eq!(Ok(0x204), restored.stack_pop());
+        assert_eq!(Err(EmulatorError::StackUnderflow), restored.stack_pop());
+    }
+
+    #[test]
+    fn it_should_fail_to_load_a_state_with_the_stack_pointer_out_of_range() {
+        let mut writer: StateWriter = StateWriter::initialize();
+
+        Cpu::initialize().save_state(&mut writer);
+
+        let mut bytes: Vec<u8> = writer.into_bytes();
+
+        // The stack pointer comes after the header, the registers, I, the timers,
+        // the program counter and the stack empty flag.
+        bytes[5 + 16 + 2 + 2 + 4 + 1] = 0x10;
+
+        let mut reader: StateReader = StateReader::initialize(&bytes).unwrap();
+
+        assert!(matches!(Cpu::load_state(&mut reader), Err(EmulatorError::InvalidState)));
+    }
+
+    #[test]
+    fn it_should_set_off_the_register_watchpoints() {
+        let mut cpu: Cpu = Cpu::initialize();
+
+        cpu.set_watchpoints(vec![
+            Watchpoint::VRegister { register: 0x3, value: 0x10 },
+            Watchpoint::IRegister { value: 0x300 },
+        ]);
+        cpu.set_v_register(0x3, 0xF);
+        cpu.set_i_register(0x2FF);
+
+        assert_eq!(None, cpu.take_trigger());
+
+        cpu.set_v_register(0x3, 0x10);
+
+        assert_eq!(Some(Trigger::VRegister { register: 0x3, value: 0x10 }), cpu.take_trigger());
+
+        cpu.set_i_register(0x300);
+
+        assert_eq!(Some(Trigger::IRegister { value: 0x300 }), cpu.take_trigger());
+    }
+}
@@ -0,0 +1,483 @@
+use crate::cpu::Cpu;
+use crate::disassembler::{self, Line, Syntax};
+use crate::error::EmulatorError;
+use crate::machine::Machine;
+use crate::watchpoint::Watchpoint;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Command {
+    Break(usize),
+    Delete(usize),
+    Breakpoints,
+    Watch(Watchpoint),
+    Unwatch(usize),
+    Watchpoints,
+    Step,
+    Next,
+    Finish,
+    Continue,
+    Registers,
+    Screen,
+    Help,
+    Quit,
+}
+
+impl FromStr for Command {
+    type Err = String;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let words: Vec<&str> = line.split_whitespace().collect();
+
+        match words.as_slice() {
+            ["break" | "b", address] => Ok(Command::Break(parse_address(address)?)),
+            ["delete" | "d", address] => Ok(Command::Delete(parse_address(address)?)),
+            [name @ ("break" | "b" | "delete" | "d")] => Err(format!("The command {} requires an address.", name)),
+            ["breakpoints" | "i"] => Ok(Command::Breakpoints),
+            ["watch" | "w", arguments @ ..] => Ok(Command::Watch(parse_watchpoint(arguments)?)),
+            ["rwatch", arguments @ ..] => Ok(Command::Watch(parse_read_watchpoint(arguments)?)),
+            ["unwatch", index] => index.parse()
+                .map(Command::Unwatch)
+                .map_err(|_| format!("The watchpoint {} is not a number from the list.", index)),
+            ["watchpoints"] => Ok(Command::Watchpoints),
+            ["step" | "s"] => Ok(Command::Step),
+            ["next" | "n"] => Ok(Command::Next),
+            ["finish" | "f"] => Ok(Command::Finish),
+            ["continue" | "c"] => Ok(Command::Continue),
+            ["registers" | "r"] => Ok(Command::Registers),
+            ["screen"] => Ok(Command::Screen),
+            ["help" | "h"] => Ok(Command::Help),
+            ["quit" | "q"] => Ok(Command::Quit),
+            _ => Err(format!("Unknown command {}, type help to list the commands.", line.trim())),
+        }
+    }
+}
+
+// watch <address> [<end>], watch V<x> <value> or watch I <value>.
+fn parse_watchpoint(arguments: &[&str]) -> Result<Watchpoint, String> {
+    match arguments {
+        [register, value] if register.len() == 2 && register.to_uppercase().starts_with('V') => {
+            let register: usize = parse_address(&register[1..])?;
+            let value: usize = parse_address(value)?;
+
+            match u8::try_from(value) {
+                Ok(value) => Ok(Watchpoint::VRegister { register, value }),
+                Err(_) => Err(format!("The value {:#X} does not fit in a V register.", value)),
+            }
+        },
+        ["i" | "I", value] => {
+            let value: usize = parse_address(value)?;
+
+            match u16::try_from(value) {
+                Ok(value) => Ok(Watchpoint::IRegister { value }),
+                Err(_) => Err(format!("The value {:#X} does not fit in the I register.", value)),
+            }
+        },
+        _ => {
+            let (start, end): (usize, usize) = parse_range(arguments, "watch")?;
+
+            Ok(Watchpoint::Write { start, end })
+        },
+    }
+}
+
+fn parse_read_watchpoint(arguments: &[&str]) -> Result<Watchpoint, String> {
+    let (start, end): (usize, usize) = parse_range(arguments, "rwatch")?;
+
+    Ok(Watchpoint::Read { start, end })
+}
+
+fn parse_range(arguments: &[&str], name: &str) -> Result<(usize, usize), String> {
+    match arguments {
+        [address] => parse_address(address).map(|address| (address, address)),
+        [start, end] => match (parse_address(start)?, parse_address(end)?) {
+            (start, end) if start <= end => Ok((start, end)),
+            _ => Err(format!("The range {} {} ends before it starts.", start, end)),
+        },
+        _ => Err(format!("The command {} requires an address or a range of addresses.", name)),
+    }
+}
+
+// Addresses are always hexadecimal, with or without the 0x prefix.
+fn parse_address(address: &str) -> Result<usize, String> {
+    let digits: &str = address.trim_start_matches("0x").trim_start_matches("0X");
+
+    usize::from_str_radix(digits, 16).map_err(|_| format!("The address {} is not hexadecimal.", address))
+}
+
+pub struct Debugger {
+    frames: usize,
+}
+
+impl Debugger {
+    // How long continuing runs without hitting a breakpoint, as nothing can
+    // interrupt it from the terminal.
+    pub const FRAMES: usize = 600;
+
+    pub const HELP: &'static str = "\
+break <address>   pause when the program counter gets to the address
+delete <address>  remove the breakpoint on the address
+breakpoints       list the breakpoints
+watch <start> [<end>]
+                  pause after the program writes to the addresses
+rwatch <start> [<end>]
+                  pause after the program reads from the addresses
+watch V<x> <value>, watch I <value>
+                  pause after the program sets the register to the value
+watchpoints       list the watchpoints
+unwatch <number>  remove a watchpoint by its number on the list
+step              run the next instruction
+next              run the next instruction, or the whole subroutine it calls
+finish            run until the current subroutine returns
+continue          run until a breakpoint, the end of the program or an idle loop
+registers         show the registers, the timers and the stack
+screen            show the screen
+quit              stop debugging";
+
+    pub fn initialize(frames: usize) -> Self {
+        Debugger { frames }
+    }
+
+    // The machine is kept paused between commands, and every command returns
+    // what should be shown to the user.
+    pub fn execute(&self, machine: &mut Machine, command: Command) -> Result<String, EmulatorError> {
+        machine.pause();
+
+        match command {
+            Command::Break(address) => {
+                machine.add_breakpoint(address);
+
+                Ok(format!("Breakpoint at {:#05X}.", address))
+            },
+            Command::Delete(address) => match machine.remove_breakpoint(address) {
+                true => Ok(format!("Deleted the breakpoint at {:#05X}.", address)),
+                false => Ok(format!("There is no breakpoint at {:#05X}.", address)),
+            },
+            Command::Breakpoints => Ok(Debugger::show_breakpoints(machine)),
+            Command::Watch(watchpoint) => {
+                machine.add_watchpoint(watchpoint);
+
+                Ok(format!("Watchpoint {}: {}.", machine.get_watchpoints().len() - 1, watchpoint))
+            },
+            Command::Unwatch(index) => match machine.remove_watchpoint(index) {
+                Some(watchpoint) => Ok(format!("Deleted the watchpoint {}: {}.", index, watchpoint)),
+                None => Ok(format!("There is no watchpoint {}.", index)),
+            },
+            Command::Watchpoints => Ok(Debugger::show_watchpoints(machine)),
+            Command::Step => {
+                machine.step()?;
+
+                match machine.get_trigger() {
+                    Some(trigger) => Ok(format!("{}\n{}", trigger, Debugger::show_registers(machine))),
+                    None => Ok(Debugger::show_registers(machine)),
+                }
+            },
+            Command::Next => {
+                machine.step_over()?;
+
+                self.run(machine)
+            },
+            Command::Finish => match machine.step_out() {
+                true => self.run(machine),
+                false => Ok(String::from("There is no subroutine to finish.")),
+            },
+            Command::Continue => {
+                machine.resume();
+
+                self.run(machine)
+            },
+            Command::Registers => Ok(Debugger::show_registers(machine)),
+            Command::Screen => Ok(machine.framebuffer().to_string()),
+            Command::Help => Ok(String::from(Debugger::HELP)),
+            Command::Quit => Ok(String::new()),
+        }
+    }
+
+    // Only runs when the command left the machine resumed, which stepping over
+    // an instruction that isn't a call does not.
+    fn run(&self, machine: &mut Machine) -> Result<String, EmulatorError> {
+        if machine.is_paused() {
+            return Ok(Debugger::show_registers(machine));
+        }
+
+        machine.run_until(self.frames, |machine| machine.is_idle())?;
+
+        let program_counter: usize = machine.cpu().get_program_counter();
+
+        let reason: String = if machine.is_halted() {
+            String::from("The program exited.")
+        } else if let Some(trigger) = machine.get_trigger() {
+            trigger.to_string()
+        } else if machine.is_paused() && machine.get_breakpoints().contains(&program_counter) {
+            format!("Breakpoint at {:#05X}.", program_counter)
+        } else if machine.is_paused() {
+            format!("Paused at {:#05X}.", program_counter)
+        } else if machine.is_idle() {
+            format!("The program is idle at {:#05X}.", program_counter)
+        } else {
+            format!("Paused at {:#05X} after {} frames.", program_counter, self.frames)
+        };
+
+        machine.pause();
+
+        Ok(format!("{}\n{}", reason, Debugger::show_registers(machine)))
+    }
+
+    fn show_breakpoints(machine: &Machine) -> String {
+        let breakpoints: Vec<usize> = machine.get_breakpoints();
+
+        if breakpoints.is_empty() {
+            return String::from("There are no breakpoints.");
+        }
+
+        breakpoints.iter()
+            .map(|address| format!("{:#05X}", address))
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    fn show_watchpoints(machine: &Machine) -> String {
+        let watchpoints: &[Watchpoint] = machine.get_watchpoints();
+
+        if watchpoints.is_empty() {
+            return String::from("There are no watchpoints.");
+        }
+
+        watchpoints.iter()
+            .enumerate()
+            .map(|(index, watchpoint)| format!("{}: {}", index, watchpoint))
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    pub fn show_registers(machine: &Machine) -> String {
+        let cpu: &Cpu = machine.cpu();
+        let line: Option<Line> = disassembler::disassemble_at(machine.memory(), cpu.get_program_counter(), Syntax::Cowgod);
+
+        let next: String = match (machine.current_opcode(), line) {
+            (Some(opcode), Some(Line { text, .. })) => format!("{:04X}  {}", opcode, text),
+            _ => String::from("----"),
+        };
+
+        let registers: Vec<String> = (0x0..0x10)
+            .map(|register| format!("V{:X} {:02X}", register, cpu.get_v_register(register)))
+            .collect();
+
+        let stack: Vec<String> = match cpu.get_stack() {
+            [] => vec![String::from("-")],
+            stack => stack.iter().map(|address| format!("{:#05X}", address)).collect(),
+        };
+
+        format!(
+            "PC {:#05X}  I {:#06X}  DT {:02X}  ST {:02X}  Next {}\n{}\n{}\nStack {}",
+            cpu.get_program_counter(),
+            cpu.get_i_register(),
+            cpu.get_delay_timer(),
+            cpu.get_sound_timer(),
+            next,
+            registers[..8].join("  "),
+            registers[8..].join("  "),
+            stack.join(" ")
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+    use super::*;
+
+    const SUBROUTINE: [u8; 12] = [0x22, 0x06, 0x70, 0x10, 0x12, 0x04, 0x71, 0x01, 0x71, 0x01, 0x00, 0xEE];
+
+    fn machine(program: &[u8]) -> Machine {
+        let mut machine: Machine = Machine::initialize();
+
+        machine.load_rom(program.to_vec()).unwrap();
+
+        machine
+    }
+
+    #[test_case("break 0x204", Command::Break(0x204) ; "with a breakpoint")]
+    #[test_case("b 2A0", Command::Break(0x2A0) ; "with a breakpoint without the prefix")]
+    #[test_case("delete 204", Command::Delete(0x204) ; "with a deleted breakpoint")]
+    #[test_case("breakpoints", Command::Breakpoints ; "with the breakpoints")]
+    #[test_case("watch 300", Command::Watch(Watchpoint::Write { start: 0x300, end: 0x300 }) ; "with a write watchpoint")]
+    #[test_case("watch 300 30F", Command::Watch(Watchpoint::Write { start: 0x300, end: 0x30F }) ; "with a write range")]
+    #[test_case("rwatch 0x300 0x30F", Command::Watch(Watchpoint::Read { start: 0x300, end: 0x30F }) ; "with a read range")]
+    #[test_case("watch vA 10", Command::Watch(Watchpoint::VRegister { register: 0xA, value: 0x10 }) ; "with a register watchpoint")]
+    #[test_case("watch I 300", Command::Watch(Watchpoint::IRegister { value: 0x300 }) ; "with an i register watchpoint")]
+    #[test_case("unwatch 2", Command::Unwatch(2) ; "with a deleted watchpoint")]
+    #[test_case("watchpoints", Command::Watchpoints ; "with the watchpoints")]
+    #[test_case("s", Command::Step ; "with a step")]
+    #[test_case("next", Command::Next ; "with a step over")]
+    #[test_case("finish", Command::Finish ; "with a step out")]
+    #[test_case("  c  ", Command::Continue ; "with a continue")]
+    #[test_case("registers", Command::Registers ; "with the registers")]
+    #[test_case("screen", Command::Screen ; "with the screen")]
+    #[test_case("quit", Command::Quit ; "with a quit")]
+    fn it_should_parse_a_command(line: &str, command: Command) {
+        assert_eq!(Ok(command), line.parse());
+    }
+
+    #[test_case("break", "The command break requires an address." ; "without an address")]
+    #[test_case("break 0xZZZ", "The address 0xZZZ is not hexadecimal." ; "with an invalid address")]
+    #[test_case("watch", "The command watch requires an address or a range of addresses." ; "without a watched address")]
+    #[test_case("rwatch 30F 300", "The range 30F 300 ends before it starts." ; "with a reversed range")]
+    #[test_case("watch V1 100", "The value 0x100 does not fit in a V register." ; "with a register value too large")]
+    #[test_case("unwatch first", "The watchpoint first is not a number from the list." ; "with an invalid watchpoint number")]
+    #[test_case("jump 200", "Unknown command jump 200, type help to list the commands." ; "with an unknown command")]
+    #[test_case("step 2", "Unknown command step 2, type help to list the commands." ; "with an unexpected argument")]
+    fn it_should_fail_to_parse_a_command(line: &str, message: &str) {
+        assert_eq!(Err(String::from(message)), line.parse::<Command>());
+    }
+
+    #[test]
+    fn it_should_show_the_registers() {
+        let mut machine: Machine = machine(&SUBROUTINE);
+        let debugger: Debugger = Debugger::initialize(Debugger::FRAMES);
+
+        debugger.execute(&mut machine, Command::Step).unwrap();
+
+        assert_eq!(
+            "PC 0x206  I 0x0000  DT 00  ST 00  Next 7101  ADD V1, 0x01\n\
+             V0 00  V1 00  V2 00  V3 00  V4 00  V5 00  V6 00  V7 00\n\
+             V8 00  V9 00  VA 00  VB 00  VC 00  VD 00  VE 00  VF 00\n\
+             Stack 0x202",
+            debugger.execute(&mut machine, Command::Registers).unwrap()
+        );
+    }
+
+    #[test]
+    fn it_should_continue_until_a_breakpoint() {
+        let mut machine: Machine = machine(&SUBROUTINE);
+        let debugger: Debugger = Debugger::initialize(Debugger::FRAMES);
+
+        debugger.execute(&mut machine, Command::Break(0x208)).unwrap();
+
+        let output: String = debugger.execute(&mut machine, Command::Continue).unwrap();
+
+        assert!(output.starts_with("Breakpoint at 0x208.\nPC 0x208"));
+        assert!(machine.is_paused());
+    }
+
+    #[test]
+    fn it_should_continue_until_the_program_is_idle() {
+        let mut machine: Machine = machine(&SUBROUTINE);
+        let debugger: Debugger = Debugger::initialize(Debugger::FRAMES);
+
+        let output: String = debugger.execute(&mut machine, Command::Continue).unwrap();
+
+        assert!(output.starts_with("The program is idle at 0x204."));
+        assert_eq!(0x10, machine.cpu().get_v_register(0x0));
+    }
+
+    #[test]
+    fn it_should_continue_until_the_program_exits() {
+        let mut machine: Machine = machine(&[0x00, 0xFD]);
+        let debugger: Debugger = Debugger::initialize(Debugger::FRAMES);
+
+        let output: String = debugger.execute(&mut machine, Command::Continue).unwrap();
+
+        assert!(output.starts_with("The program exited."));
+    }
+
+    #[test]
+    fn it_should_stop_continuing_after_the_frames() {
+        let mut machine: Machine = machine(&[0x70, 0x01, 0x12, 0x00]);
+        let debugger: Debugger = Debugger::initialize(2);
+
+        let output: String = debugger.execute(&mut machine, Command::Continue).unwrap();
+
+        assert!(output.starts_with("Paused at 0x200 after 2 frames."));
+        assert!(machine.is_paused());
+    }
+
+    #[test]
+    fn it_should_step_over_a_subroutine() {
+        let mut machine: Machine = machine(&SUBROUTINE);
+        let debugger: Debugger = Debugger::initialize(Debugger::FRAMES);
+
+        assert!(debugger.execute(&mut machine, Command::Next).unwrap().starts_with("Paused at 0x202."));
+        assert_eq!(0x2, machine.cpu().get_v_register(0x1));
+    }
+
+    #[test]
+    fn it_should_step_out_of_a_subroutine() {
+        let mut machine: Machine = machine(&SUBROUTINE);
+        let debugger: Debugger = Debugger::initialize(Debugger::FRAMES);
+
+        debugger.execute(&mut machine, Command::Step).unwrap();
+        debugger.execute(&mut machine, Command::Step).unwrap();
+
+        assert!(debugger.execute(&mut machine, Command::Finish).unwrap().starts_with("Paused at 0x202."));
+        assert_eq!(0x2, machine.cpu().get_v_register(0x1));
+        assert_eq!(
+            "There is no subroutine to finish.",
+            debugger.execute(&mut machine, Command::Finish).unwrap()
+        );
+    }
+
+    #[test]
+    fn it_should_list_and_delete_breakpoints() {
+        let mut machine: Machine = machine(&SUBROUTINE);
+        let debugger: Debugger = Debugger::initialize(Debugger::FRAMES);
+
+        assert_eq!("There are no breakpoints.", debugger.execute(&mut machine, Command::Breakpoints).unwrap());
+
+        debugger.execute(&mut machine, Command::Break(0x206)).unwrap();
+        debugger.execute(&mut machine, Command::Break(0x200)).unwrap();
+
+        assert_eq!("0x200\n0x206", debugger.execute(&mut machine, Command::Breakpoints).unwrap());
+        assert_eq!(
+            "Deleted the breakpoint at 0x200.",
+            debugger.execute(&mut machine, Command::Delete(0x200)).unwrap()
+        );
+        assert_eq!(
+            "There is no breakpoint at 0x200.",
+            debugger.execute(&mut machine, Command::Delete(0x200)).unwrap()
+        );
+    }
+
+    #[test]
+    fn it_should_continue_until_a_watchpoint() {
+        let mut machine: Machine = machine(&[0xA3, 0x00, 0x60, 0xCA, 0xF0, 0x55, 0x12, 0x06]);
+        let debugger: Debugger = Debugger::initialize(Debugger::FRAMES);
+
+        assert_eq!(
+            "Watchpoint 0: write 0x300-0x30F.",
+            debugger.execute(&mut machine, "watch 300 30F".parse().unwrap()).unwrap()
+        );
+
+        let output: String = debugger.execute(&mut machine, Command::Continue).unwrap();
+
+        assert!(output.starts_with("Wrote 0xCA to 0x300.\nPC 0x206"));
+    }
+
+    #[test]
+    fn it_should_show_a_watchpoint_set_off_by_a_step() {
+        let mut machine: Machine = machine(&[0xA3, 0x00]);
+        let debugger: Debugger = Debugger::initialize(Debugger::FRAMES);
+
+        debugger.execute(&mut machine, Command::Watch(Watchpoint::IRegister { value: 0x300 })).unwrap();
+
+        assert!(debugger.execute(&mut machine, Command::Step).unwrap().starts_with("I was set to 0x300.\nPC 0x202"));
+    }
+
+    #[test]
+    fn it_should_list_and_delete_watchpoints() {
+        let mut machine: Machine = machine(&SUBROUTINE);
+        let debugger: Debugger = Debugger::initialize(Debugger::FRAMES);
+
+        assert_eq!("There are no watchpoints.", debugger.execute(&mut machine, Command::Watchpoints).unwrap());
+
+        debugger.execute(&mut machine, Command::Watch(Watchpoint::Read { start: 0x300, end: 0x300 })).unwrap();
+        debugger.execute(&mut machine, Command::Watch(Watchpoint::VRegister { register: 0x1, value: 0x2 })).unwrap();
+
+        assert_eq!("0: read 0x300\n1: V1 = 0x02", debugger.execute(&mut machine, Command::Watchpoints).unwrap());
+        assert_eq!(
+            "Deleted the watchpoint 0: read 0x300.",
+            debugger.execute(&mut machine, Command::Unwatch(0)).unwrap()
+        );
+        assert_eq!("There is no watchpoint 1.", debugger.execute(&mut machine, Command::Unwatch(1)).unwrap());
+    }
+}
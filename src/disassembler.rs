@@ -0,0 +1,343 @@
+use crate::memory::Memory;
+use crate::op::{self, Op};
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Syntax {
+    Cowgod,
+    Octo,
+}
+
+impl FromStr for Syntax {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "cowgod" => Ok(Syntax::Cowgod),
+            "octo" => Ok(Syntax::Octo),
+            _ => Err(format!("Unknown syntax {}, expected cowgod or octo.", name)),
+        }
+    }
+}
+
+// One instruction, or the bytes that don't make one, with where they were.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Line {
+    pub address: usize,
+    pub bytes: Vec<u8>,
+    pub text: String,
+}
+
+impl Line {
+    pub fn len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+}
+
+impl fmt::Display for Line {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        let bytes: Vec<String> = self.bytes
+            .chunks(2)
+            .map(|word| word.iter().map(|byte| format!("{:02X}", byte)).collect())
+            .collect();
+
+        write!(formatter, "{:#05X}  {:<9}  {}", self.address, bytes.join(" "), self.text)
+    }
+}
+
+// Disassembles the instruction at the start of the bytes, which is four bytes
+// long for the XO-CHIP F000 nnnn. Whatever doesn't decode is shown as data.
+pub fn disassemble_line(bytes: &[u8], address: usize, syntax: Syntax) -> Line {
+    let opcode: Option<u16> = match bytes {
+        [first_byte, second_byte, ..] => Some(u16::from(*first_byte) << 8 | u16::from(*second_byte)),
+        _ => None,
+    };
+
+    let long_word: Option<u16> = match bytes {
+        [0xF0, 0x00, first_byte, second_byte, ..] => Some(u16::from(*first_byte) << 8 | u16::from(*second_byte)),
+        _ => None,
+    };
+
+    let (length, text): (usize, Option<String>) = match (opcode, long_word) {
+        (Some(_), Some(word)) => (4, Some(format_long_load(word, syntax))),
+        (Some(0xF000), None) => (bytes.len().min(2), None),
+        (Some(opcode), None) => (2, format_opcode(opcode, syntax)),
+        (None, _) => (bytes.len().min(1), None),
+    };
+
+    let bytes: Vec<u8> = bytes[..length].to_vec();
+    let text: String = text.unwrap_or_else(|| format_data(&bytes, syntax));
+
+    Line { address, bytes, text }
+}
+
+// A linear sweep from the start of the program to its end.
+pub fn disassemble(program: &[u8], origin: usize, syntax: Syntax) -> Vec<Line> {
+    let mut lines: Vec<Line> = Vec::new();
+    let mut offset: usize = 0;
+
+    while offset < program.len() {
+        let line: Line = disassemble_line(&program[offset..], origin + offset, syntax);
+
+        offset += line.len();
+        lines.push(line);
+    }
+
+    lines
+}
+
+// Reads without setting the watchpoints off, so the debugger can show the
+// instruction at the program counter.
+pub fn disassemble_at(memory: &Memory, address: usize, syntax: Syntax) -> Option<Line> {
+    let bytes: Vec<u8> = (address..address + 4)
+        .map_while(|location| memory.peek(location).ok())
+        .collect();
+
+    match bytes.is_empty() {
+        true => None,
+        false => Some(disassemble_line(&bytes, address, syntax)),
+    }
+}
+
+pub fn format_data(bytes: &[u8], syntax: Syntax) -> String {
+    let bytes: Vec<String> = bytes.iter().map(|byte| format!("{:#04X}", byte)).collect();
+
+    match syntax {
+        Syntax::Cowgod => format!("DB {}", bytes.join(", ")),
+        Syntax::Octo => bytes.join(" "),
+    }
+}
+
+fn format_long_load(word: u16, syntax: Syntax) -> String {
+    match syntax {
+        Syntax::Cowgod => format!("LD I, {:#06X}", word),
+        Syntax::Octo => format!("i := long {:#06X}", word),
+    }
+}
+
+pub fn format_opcode(opcode: u16, syntax: Syntax) -> Option<String> {
+    match syntax {
+        Syntax::Cowgod => format_cowgod(opcode),
+        Syntax::Octo => format_octo(opcode),
+    }
+}
+
+fn format_cowgod(opcode: u16) -> Option<String> {
+    let text: String = match op::decode(opcode) {
+        Op::Clear => String::from("CLS"),
+        Op::Return => String::from("RET"),
+        Op::ScrollDown { n } => format!("SCD {:#03X}", n),
+        Op::ScrollRight => String::from("SCR"),
+        Op::ScrollLeft => String::from("SCL"),
+        Op::Exit => String::from("EXIT"),
+        Op::LowResolution => String::from("LOW"),
+        Op::HighResolution => String::from("HIGH"),
+        Op::Jump { nnn } => format!("JP {:#05X}", nnn),
+        Op::Call { nnn } => format!("CALL {:#05X}", nnn),
+        Op::SkipIfEqualImm { x, kk } => format!("SE V{:X}, {:#04X}", x, kk),
+        Op::SkipIfNotEqualImm { x, kk } => format!("SNE V{:X}, {:#04X}", x, kk),
+        Op::SkipIfEqual { x, y } => format!("SE V{:X}, V{:X}", x, y),
+        Op::SaveRange { x, y } => format!("SAVE V{:X} - V{:X}", x, y),
+        Op::LoadRange { x, y } => format!("LOAD V{:X} - V{:X}", x, y),
+        Op::LoadImm { x, kk } => format!("LD V{:X}, {:#04X}", x, kk),
+        Op::AddImm { x, kk } => format!("ADD V{:X}, {:#04X}", x, kk),
+        Op::Move { x, y } => format!("LD V{:X}, V{:X}", x, y),
+        Op::Or { x, y } => format!("OR V{:X}, V{:X}", x, y),
+        Op::And { x, y } => format!("AND V{:X}, V{:X}", x, y),
+        Op::Xor { x, y } => format!("XOR V{:X}, V{:X}", x, y),
+        Op::Add { x, y } => format!("ADD V{:X}, V{:X}", x, y),
+        Op::Sub { x, y } => format!("SUB V{:X}, V{:X}", x, y),
+        Op::ShiftRight { x, y } => format!("SHR V{:X}, V{:X}", x, y),
+        Op::SubReverse { x, y } => format!("SUBN V{:X}, V{:X}", x, y),
+        Op::ShiftLeft { x, y } => format!("SHL V{:X}, V{:X}", x, y),
+        Op::SkipIfNotEqual { x, y } => format!("SNE V{:X}, V{:X}", x, y),
+        Op::LoadI { nnn } => format!("LD I, {:#05X}", nnn),
+        Op::JumpOffset { nnn, .. } => format!("JP V0, {:#05X}", nnn),
+        Op::Random { x, kk } => format!("RND V{:X}, {:#04X}", x, kk),
+        Op::Draw { x, y, n } => format!("DRW V{:X}, V{:X}, {:#03X}", x, y, n),
+        Op::SkipIfKey { x } => format!("SKP V{:X}", x),
+        Op::SkipIfNotKey { x } => format!("SKNP V{:X}", x),
+        Op::SelectPlanes { n } => format!("PLANE {:#03X}", n),
+        Op::LoadAudio => String::from("AUDIO"),
+        Op::GetDelay { x } => format!("LD V{:X}, DT", x),
+        Op::WaitKey { x } => format!("LD V{:X}, K", x),
+        Op::SetDelay { x } => format!("LD DT, V{:X}", x),
+        Op::SetSound { x } => format!("LD ST, V{:X}", x),
+        Op::AddI { x } => format!("ADD I, V{:X}", x),
+        Op::Font { x } => format!("LD F, V{:X}", x),
+        Op::BigFont { x } => format!("LD HF, V{:X}", x),
+        Op::Bcd { x } => format!("LD B, V{:X}", x),
+        Op::SetPitch { x } => format!("PITCH V{:X}", x),
+        Op::Store { x } => format!("LD [I], V{:X}", x),
+        Op::Load { x } => format!("LD V{:X}, [I]", x),
+        Op::StoreFlags { x } => format!("LD R, V{:X}", x),
+        Op::LoadFlags { x } => format!("LD V{:X}, R", x),
+        // F000 needs the word after it, see format_long_load.
+        Op::LoadLongI | Op::Unknown { .. } => return None,
+    };
+
+    Some(text)
+}
+
+// The skips are written the way Octo reads them, as the condition for running
+// the next instruction.
+fn format_octo(opcode: u16) -> Option<String> {
+    let text: String = match op::decode(opcode) {
+        Op::Clear => String::from("clear"),
+        Op::Return => String::from("return"),
+        Op::ScrollDown { n } => format!("scroll-down {}", n),
+        Op::ScrollRight => String::from("scroll-right"),
+        Op::ScrollLeft => String::from("scroll-left"),
+        Op::Exit => String::from("exit"),
+        Op::LowResolution => String::from("lores"),
+        Op::HighResolution => String::from("hires"),
+        Op::Jump { nnn } => format!("jump {:#05X}", nnn),
+        Op::Call { nnn } => format!(":call {:#05X}", nnn),
+        Op::SkipIfEqualImm { x, kk } => format!("if v{:x} != {:#04X} then", x, kk),
+        Op::SkipIfNotEqualImm { x, kk } => format!("if v{:x} == {:#04X} then", x, kk),
+        Op::SkipIfEqual { x, y } => format!("if v{:x} != v{:x} then", x, y),
+        Op::SaveRange { x, y } => format!("save v{:x} - v{:x}", x, y),
+        Op::LoadRange { x, y } => format!("load v{:x} - v{:x}", x, y),
+        Op::LoadImm { x, kk } => format!("v{:x} := {:#04X}", x, kk),
+        Op::AddImm { x, kk } => format!("v{:x} += {:#04X}", x, kk),
+        Op::Move { x, y } => format!("v{:x} := v{:x}", x, y),
+        Op::Or { x, y } => format!("v{:x} |= v{:x}", x, y),
+        Op::And { x, y } => format!("v{:x} &= v{:x}", x, y),
+        Op::Xor { x, y } => format!("v{:x} ^= v{:x}", x, y),
+        Op::Add { x, y } => format!("v{:x} += v{:x}", x, y),
+        Op::Sub { x, y } => format!("v{:x} -= v{:x}", x, y),
+        Op::ShiftRight { x, y } => format!("v{:x} >>= v{:x}", x, y),
+        Op::SubReverse { x, y } => format!("v{:x} =- v{:x}", x, y),
+        Op::ShiftLeft { x, y } => format!("v{:x} <<= v{:x}", x, y),
+        Op::SkipIfNotEqual { x, y } => format!("if v{:x} == v{:x} then", x, y),
+        Op::LoadI { nnn } => format!("i := {:#05X}", nnn),
+        Op::JumpOffset { nnn, .. } => format!("jump0 {:#05X}", nnn),
+        Op::Random { x, kk } => format!("v{:x} := random {:#04X}", x, kk),
+        Op::Draw { x, y, n } => format!("sprite v{:x} v{:x} {}", x, y, n),
+        Op::SkipIfKey { x } => format!("if v{:x} -key then", x),
+        Op::SkipIfNotKey { x } => format!("if v{:x} key then", x),
+        Op::SelectPlanes { n } => format!("plane {}", n),
+        Op::LoadAudio => String::from("audio"),
+        Op::GetDelay { x } => format!("v{:x} := delay", x),
+        Op::WaitKey { x } => format!("v{:x} := key", x),
+        Op::SetDelay { x } => format!("delay := v{:x}", x),
+        Op::SetSound { x } => format!("buzzer := v{:x}", x),
+        Op::AddI { x } => format!("i += v{:x}", x),
+        Op::Font { x } => format!("i := hex v{:x}", x),
+        Op::BigFont { x } => format!("i := bighex v{:x}", x),
+        Op::Bcd { x } => format!("bcd v{:x}", x),
+        Op::SetPitch { x } => format!("pitch := v{:x}", x),
+        Op::Store { x } => format!("save v{:x}", x),
+        Op::Load { x } => format!("load v{:x}", x),
+        Op::StoreFlags { x } => format!("saveflags v{:x}", x),
+        Op::LoadFlags { x } => format!("loadflags v{:x}", x),
+        Op::LoadLongI | Op::Unknown { .. } => return None,
+    };
+
+    Some(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+    use super::*;
+
+    #[test_case(0x00E0, "CLS", "clear" ; "with 00E0")]
+    #[test_case(0x00C4, "SCD 0x4", "scroll-down 4" ; "with 00Cn")]
+    #[test_case(0x00EE, "RET", "return" ; "with 00EE")]
+    #[test_case(0x00FF, "HIGH", "hires" ; "with 00FF")]
+    #[test_case(0x12A4, "JP 0x2A4", "jump 0x2A4" ; "with 1nnn")]
+    #[test_case(0x2206, "CALL 0x206", ":call 0x206" ; "with 2nnn")]
+    #[test_case(0x3A10, "SE VA, 0x10", "if va != 0x10 then" ; "with 3xkk")]
+    #[test_case(0x4A10, "SNE VA, 0x10", "if va == 0x10 then" ; "with 4xkk")]
+    #[test_case(0x5120, "SE V1, V2", "if v1 != v2 then" ; "with 5xy0")]
+    #[test_case(0x5122, "SAVE V1 - V2", "save v1 - v2" ; "with 5xy2")]
+    #[test_case(0x6A02, "LD VA, 0x02", "va := 0x02" ; "with 6xkk")]
+    #[test_case(0x7101, "ADD V1, 0x01", "v1 += 0x01" ; "with 7xkk")]
+    #[test_case(0x8126, "SHR V1, V2", "v1 >>= v2" ; "with 8xy6")]
+    #[test_case(0x8127, "SUBN V1, V2", "v1 =- v2" ; "with 8xy7")]
+    #[test_case(0x9120, "SNE V1, V2", "if v1 == v2 then" ; "with 9xy0")]
+    #[test_case(0xA300, "LD I, 0x300", "i := 0x300" ; "with Annn")]
+    #[test_case(0xB300, "JP V0, 0x300", "jump0 0x300" ; "with Bnnn")]
+    #[test_case(0xC30F, "RND V3, 0x0F", "v3 := random 0x0F" ; "with Cxkk")]
+    #[test_case(0xD125, "DRW V1, V2, 0x5", "sprite v1 v2 5" ; "with Dxyn")]
+    #[test_case(0xE59E, "SKP V5", "if v5 -key then" ; "with Ex9E")]
+    #[test_case(0xE5A1, "SKNP V5", "if v5 key then" ; "with ExA1")]
+    #[test_case(0xF201, "PLANE 0x2", "plane 2" ; "with Fn01")]
+    #[test_case(0xF002, "AUDIO", "audio" ; "with F002")]
+    #[test_case(0xF50A, "LD V5, K", "v5 := key" ; "with Fx0A")]
+    #[test_case(0xF518, "LD ST, V5", "buzzer := v5" ; "with Fx18")]
+    #[test_case(0xF530, "LD HF, V5", "i := bighex v5" ; "with Fx30")]
+    #[test_case(0xF53A, "PITCH V5", "pitch := v5" ; "with Fx3A")]
+    #[test_case(0xF555, "LD [I], V5", "save v5" ; "with Fx55")]
+    #[test_case(0xF585, "LD V5, R", "loadflags v5" ; "with Fx85")]
+    fn it_should_format_an_opcode(opcode: u16, cowgod: &str, octo: &str) {
+        assert_eq!(Some(String::from(cowgod)), format_opcode(opcode, Syntax::Cowgod));
+        assert_eq!(Some(String::from(octo)), format_opcode(opcode, Syntax::Octo));
+    }
+
+    #[test_case(0x0123 ; "with a machine code routine")]
+    #[test_case(0x5121 ; "with an unknown 5xyn")]
+    #[test_case(0x812F ; "with an unknown 8xyn")]
+    #[test_case(0xE1FF ; "with an unknown Exkk")]
+    #[test_case(0xF1FF ; "with an unknown Fxkk")]
+    #[test_case(0xF102 ; "with an audio pattern on a register")]
+    fn it_should_not_format_an_unknown_opcode(opcode: u16) {
+        assert_eq!(None, format_opcode(opcode, Syntax::Cowgod));
+        assert_eq!(None, format_opcode(opcode, Syntax::Octo));
+    }
+
+    #[test]
+    fn it_should_disassemble_a_long_load() {
+        let line: Line = disassemble_line(&[0xF0, 0x00, 0x03, 0x00], 0x200, Syntax::Octo);
+
+        assert_eq!(4, line.len());
+        assert_eq!("i := long 0x0300", line.text);
+    }
+
+    #[test_case(Syntax::Cowgod, "DB 0x01, 0x23" ; "with the cowgod syntax")]
+    #[test_case(Syntax::Octo, "0x01 0x23" ; "with the octo syntax")]
+    fn it_should_disassemble_unknown_opcodes_as_data(syntax: Syntax, text: &str) {
+        assert_eq!(text, disassemble_line(&[0x01, 0x23], 0x200, syntax).text);
+    }
+
+    #[test]
+    fn it_should_disassemble_a_program() {
+        let lines: Vec<String> = disassemble(&[0x6A, 0x02, 0xF0, 0x00, 0x03, 0x00, 0x12, 0x06, 0xFF], 0x200, Syntax::Cowgod)
+            .iter()
+            .map(|line| line.to_string())
+            .collect();
+
+        assert_eq!(vec![
+            "0x200  6A02       LD VA, 0x02",
+            "0x202  F000 0300  LD I, 0x0300",
+            "0x206  1206       JP 0x206",
+            "0x208  FF         DB 0xFF",
+        ], lines);
+    }
+
+    #[test]
+    fn it_should_disassemble_from_memory() {
+        let mut memory: Memory = Memory::initialize();
+
+        memory.store_program(vec![0x00, 0xE0]).unwrap();
+
+        assert_eq!(
+            Some(String::from("CLS")),
+            disassemble_at(&memory, 0x200, Syntax::Cowgod).map(|line| line.text)
+        );
+        assert_eq!(None, disassemble_at(&memory, memory.get_size(), Syntax::Cowgod));
+    }
+
+    #[test_case("cowgod", Ok(Syntax::Cowgod) ; "with cowgod")]
+    #[test_case("octo", Ok(Syntax::Octo) ; "with octo")]
+    #[test_case("intel", Err(String::from("Unknown syntax intel, expected cowgod or octo.")) ; "with an unknown syntax")]
+    fn it_should_parse_a_syntax(name: &str, expected: Result<Syntax, String>) {
+        assert_eq!(expected, name.parse::<Syntax>());
+    }
+}
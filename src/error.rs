@@ -0,0 +1,106 @@
+use std::error::Error;
+use std::fmt;
+
+#[derive(Debug, PartialEq)]
+pub enum EmulatorError {
+    StackOverflow,
+    StackUnderflow,
+    MemoryFault { address: usize },
+    UnknownOpcode { opcode: u16, program_counter: usize },
+    RomTooLarge { size: usize, capacity: usize },
+    InvalidState,
+    UnsupportedStateVersion { version: u8 },
+    InvalidSource { line: usize, message: String },
+    AudioOutput { message: String },
+    DisplayOutput { message: String },
+    BreakWithoutDebugger,
+}
+
+impl fmt::Display for EmulatorError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EmulatorError::StackOverflow => write!(formatter, "The CPU has a stack overflow."),
+            EmulatorError::StackUnderflow => write!(formatter, "The CPU has a stack underflow."),
+            EmulatorError::MemoryFault { address } => {
+                write!(formatter, "The memory address {:#05X} is out of range.", address)
+            },
+            EmulatorError::UnknownOpcode { opcode, program_counter } => {
+                write!(formatter, "The opcode {:04X} at {:#05X} is unknown.", opcode, program_counter)
+            },
+            EmulatorError::RomTooLarge { size, capacity } => {
+                write!(formatter, "The ROM has {} bytes, but only {} bytes fit in memory.", size, capacity)
+            },
+            EmulatorError::InvalidState => write!(formatter, "The save state is corrupted."),
+            EmulatorError::UnsupportedStateVersion { version } => {
+                write!(formatter, "The save state has the unsupported version {}.", version)
+            },
+            EmulatorError::InvalidSource { line, message } => {
+                write!(formatter, "The source has an error on line {}: {}", line, message)
+            },
+            EmulatorError::AudioOutput { message } => {
+                write!(formatter, "The audio could not be written: {}", message)
+            },
+            EmulatorError::DisplayOutput { message } => {
+                write!(formatter, "The pixels on screen could not be updated: {}", message)
+            },
+            EmulatorError::BreakWithoutDebugger => {
+                write!(formatter, "The break policy needs a debugger to resume the paused machine.")
+            },
+        }
+    }
+}
+
+impl Error for EmulatorError {}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+    use super::*;
+
+    #[test_case(EmulatorError::StackOverflow, "The CPU has a stack overflow." ; "with a stack overflow")]
+    #[test_case(EmulatorError::StackUnderflow, "The CPU has a stack underflow." ; "with a stack underflow")]
+    #[test_case(
+        EmulatorError::MemoryFault { address: 0x1000 },
+        "The memory address 0x1000 is out of range." ;
+        "with a memory fault"
+    )]
+    #[test_case(
+        EmulatorError::UnknownOpcode { opcode: 0xE0FF, program_counter: 0x2AC },
+        "The opcode E0FF at 0x2AC is unknown." ;
+        "with an unknown opcode"
+    )]
+    #[test_case(
+        EmulatorError::RomTooLarge { size: 4000, capacity: 3584 },
+        "The ROM has 4000 bytes, but only 3584 bytes fit in memory." ;
+        "with a rom too large"
+    )]
+    #[test_case(EmulatorError::InvalidState, "The save state is corrupted." ; "with an invalid state")]
+    #[test_case(
+        EmulatorError::UnsupportedStateVersion { version: 7 },
+        "The save state has the unsupported version 7." ;
+        "with an unsupported state version"
+    )]
+    #[test_case(
+        EmulatorError::InvalidSource { line: 3, message: String::from("The label main is defined twice.") },
+        "The source has an error on line 3: The label main is defined twice." ;
+        "with an invalid source"
+    )]
+    #[test_case(
+        EmulatorError::AudioOutput { message: String::from("No space left on device") },
+        "The audio could not be written: No space left on device" ;
+        "with an audio output failure"
+    )]
+    #[test_case(
+        EmulatorError::DisplayOutput { message: String::from("Update failed") },
+        "The pixels on screen could not be updated: Update failed" ;
+        "with a display output failure"
+    )]
+    #[test_case(
+        EmulatorError::BreakWithoutDebugger,
+        "The break policy needs a debugger to resume the paused machine." ;
+        "with a break without a debugger"
+    )]
+    fn it_should_describe_the_error(error: EmulatorError, message: &str) {
+        assert_eq!(message, error.to_string());
+    }
+}
@@ -0,0 +1,118 @@
+use crate::cpu::Cpu;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+pub struct FlagStore {
+    directory: PathBuf,
+}
+
+impl FlagStore {
+    pub fn initialize(directory: PathBuf) -> Self {
+        FlagStore { directory }
+    }
+
+    pub fn load(&self, rom_hash: u64) -> io::Result<Option<[u8; Cpu::RPL_FLAGS]>> {
+        let bytes: Vec<u8> = match fs::read(self.get_path(rom_hash)) {
+            Ok(bytes) => bytes,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(error) => return Err(error),
+        };
+
+        let flags: [u8; Cpu::RPL_FLAGS] = bytes.try_into().map_err(|_| io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("The flags file for the ROM {:016x} is corrupted.", rom_hash)
+        ))?;
+
+        Ok(Some(flags))
+    }
+
+    // Flags matching the stored ones (or all zero, without a file) are left
+    // alone, so ROMs that never run Fx75 don't get a file.
+    pub fn save(&self, rom_hash: u64, flags: [u8; Cpu::RPL_FLAGS]) -> io::Result<()> {
+        let stored: [u8; Cpu::RPL_FLAGS] = self.load(rom_hash).ok().flatten().unwrap_or([0x0; Cpu::RPL_FLAGS]);
+
+        if stored == flags {
+            return Ok(());
+        }
+
+        fs::create_dir_all(&self.directory)?;
+
+        fs::write(self.get_path(rom_hash), flags)
+    }
+
+    fn get_path(&self, rom_hash: u64) -> PathBuf {
+        self.directory.join(format!("{:016x}.flags", rom_hash))
+    }
+}
+
+// 64-bit FNV-1a, which is enough to tell ROMs apart without pulling a
+// cryptographic hash in.
+pub fn hash_rom(program: &[u8]) -> u64 {
+    program.iter().fold(0xCBF29CE484222325, |hash: u64, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x100000001B3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+    use super::*;
+    use std::env;
+
+    fn directory(name: &str) -> PathBuf {
+        let directory: PathBuf = env::temp_dir().join(format!("chip8rs-{}-{}", name, std::process::id()));
+
+        let _ = fs::remove_dir_all(&directory);
+
+        directory
+    }
+
+    #[test_case(b"", 0xCBF29CE484222325 ; "with an empty program")]
+    #[test_case(b"a", 0xAF63DC4C8601EC8C ; "with a single byte")]
+    fn it_should_hash_a_rom(program: &[u8], hash: u64) {
+        assert_eq!(hash, hash_rom(program));
+    }
+
+    #[test]
+    fn it_should_save_and_load_the_flags() {
+        let store: FlagStore = FlagStore::initialize(directory("save"));
+        let mut flags: [u8; Cpu::RPL_FLAGS] = [0x0; Cpu::RPL_FLAGS];
+
+        flags[0x0] = 0xCA;
+        flags[0xF] = 0xFE;
+
+        store.save(0x1234, flags).unwrap();
+
+        assert_eq!(Some(flags), store.load(0x1234).unwrap());
+        assert_eq!(None, store.load(0x5678).unwrap());
+    }
+
+    #[test]
+    fn it_should_not_write_flags_that_did_not_change() {
+        let directory: PathBuf = directory("unchanged");
+        let store: FlagStore = FlagStore::initialize(directory.clone());
+
+        store.save(0x1234, [0x0; Cpu::RPL_FLAGS]).unwrap();
+
+        assert!(!directory.exists());
+    }
+
+    #[test]
+    fn it_should_load_nothing_without_a_flags_file() {
+        let store: FlagStore = FlagStore::initialize(directory("missing"));
+
+        assert_eq!(None, store.load(0x1234).unwrap());
+    }
+
+    #[test]
+    fn it_should_fail_to_load_a_corrupted_flags_file() {
+        let directory: PathBuf = directory("corrupted");
+        let store: FlagStore = FlagStore::initialize(directory.clone());
+
+        fs::create_dir_all(&directory).unwrap();
+        fs::write(directory.join(format!("{:016x}.flags", 0x1234)), [0x1, 0x2]).unwrap();
+
+        assert_eq!(io::ErrorKind::InvalidData, store.load(0x1234).unwrap_err().kind());
+    }
+}
@@ -0,0 +1,436 @@
+use crate::error::EmulatorError;
+use crate::state::{StateReader, StateWriter};
+use std::fmt;
+
+pub struct Framebuffer {
+    planes: Vec<Vec<bool>>,
+    selected_planes: u8,
+    width: usize,
+    height: usize,
+}
+
+impl Framebuffer {
+    pub const WIDTH: usize = 64;
+    pub const HEIGHT: usize = 32;
+    pub const HIGH_RESOLUTION_WIDTH: usize = 128;
+    pub const HIGH_RESOLUTION_HEIGHT: usize = 64;
+    pub const PLANES: usize = 4;
+
+    pub fn initialize() -> Self {
+        Framebuffer {
+            planes: vec![vec![false; Framebuffer::WIDTH * Framebuffer::HEIGHT]; Framebuffer::PLANES],
+            selected_planes: 0b0001,
+            width: Framebuffer::WIDTH,
+            height: Framebuffer::HEIGHT,
+        }
+    }
+
+    pub fn save_state(&self, writer: &mut StateWriter) {
+        writer.write_bool(self.is_high_resolution());
+        writer.write_u8(self.selected_planes);
+        writer.write_bytes(&self.get_colours());
+    }
+
+    pub fn load_state(reader: &mut StateReader) -> Result<Self, EmulatorError> {
+        let mut framebuffer: Framebuffer = Framebuffer::initialize();
+
+        framebuffer.set_high_resolution(reader.read_bool()?);
+        framebuffer.selected_planes = reader.read_u8()?;
+
+        let colours: &[u8] = reader.read_bytes(framebuffer.width * framebuffer.height)?;
+
+        if framebuffer.selected_planes > 0xF || colours.iter().any(|colour| *colour > 0xF) {
+            return Err(EmulatorError::InvalidState);
+        }
+
+        for (index, colour) in colours.iter().enumerate() {
+            for (plane, pixels) in framebuffer.planes.iter_mut().enumerate() {
+                pixels[index] = colour >> plane & 0b1 == 0b1;
+            }
+        }
+
+        Ok(framebuffer)
+    }
+
+    pub fn clear(&mut self) {
+        for plane in 0..Framebuffer::PLANES {
+            if self.is_plane_selected(plane) {
+                self.planes[plane] = vec![false; self.width * self.height];
+            }
+        }
+    }
+
+    pub fn set_high_resolution(&mut self, enabled: bool) {
+        (self.width, self.height) = match enabled {
+            true => (Framebuffer::HIGH_RESOLUTION_WIDTH, Framebuffer::HIGH_RESOLUTION_HEIGHT),
+            false => (Framebuffer::WIDTH, Framebuffer::HEIGHT),
+        };
+
+        self.planes = vec![vec![false; self.width * self.height]; Framebuffer::PLANES];
+    }
+
+    pub fn is_high_resolution(&self) -> bool {
+        self.width == Framebuffer::HIGH_RESOLUTION_WIDTH
+    }
+
+    pub fn get_width(&self) -> usize {
+        self.width
+    }
+
+    pub fn get_height(&self) -> usize {
+        self.height
+    }
+
+    pub fn select_planes(&mut self, planes: u8) {
+        self.selected_planes = planes & 0xF;
+    }
+
+    pub fn get_selected_planes(&self) -> u8 {
+        self.selected_planes
+    }
+
+    pub fn is_plane_selected(&self, plane: usize) -> bool {
+        self.selected_planes >> plane & 0b1 == 0b1
+    }
+
+    pub fn draw_pixel(&mut self, state: bool, x: usize, y: usize) -> bool {
+        self.draw_plane_pixel(0, state, x, y)
+    }
+
+    pub fn draw_plane_pixel(&mut self, plane: usize, state: bool, x: usize, y: usize) -> bool {
+        let index: usize = x + (y * self.width);
+
+        let old_state: bool = self.planes[plane][index];
+
+        self.planes[plane][index] = old_state != state;
+
+        old_state && state
+    }
+
+    pub fn get_pixel(&self, x: usize, y: usize) -> bool {
+        self.planes[0][x + (y * self.width)]
+    }
+
+    pub fn get_pixels(&self) -> &[bool] {
+        &self.planes[0]
+    }
+
+    pub fn get_plane_pixels(&self, plane: usize) -> &[bool] {
+        &self.planes[plane]
+    }
+
+    // Each plane is one bit of the colour, so two planes give four colours
+    // and four planes give sixteen.
+    pub fn get_colours(&self) -> Vec<u8> {
+        (0..self.width * self.height)
+            .map(|index| self.get_colour(index))
+            .collect()
+    }
+
+    fn get_colour(&self, index: usize) -> u8 {
+        (0..Framebuffer::PLANES).fold(0, |colour, plane| {
+            colour | (u8::from(self.planes[plane][index]) << plane)
+        })
+    }
+
+    pub fn scroll_down(&mut self, rows: usize) {
+        self.scroll(0, rows as isize);
+    }
+
+    pub fn scroll_left(&mut self, columns: usize) {
+        self.scroll(-(columns as isize), 0);
+    }
+
+    pub fn scroll_right(&mut self, columns: usize) {
+        self.scroll(columns as isize, 0);
+    }
+
+    fn scroll(&mut self, horizontal_offset: isize, vertical_offset: isize) {
+        for plane in 0..Framebuffer::PLANES {
+            if !self.is_plane_selected(plane) {
+                continue;
+            }
+
+            let mut pixels: Vec<bool> = vec![false; self.width * self.height];
+
+            for y in 0..self.height {
+                for x in 0..self.width {
+                    let source_x: isize = x as isize - horizontal_offset;
+                    let source_y: isize = y as isize - vertical_offset;
+
+                    if source_x < 0 || source_y < 0 || source_x >= self.width as isize || source_y >= self.height as isize {
+                        continue;
+                    }
+
+                    pixels[x + (y * self.width)] = self.planes[plane][source_x as usize + (source_y as usize * self.width)];
+                }
+            }
+
+            self.planes[plane] = pixels;
+        }
+    }
+}
+
+impl fmt::Display for Framebuffer {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        for row in self.get_colours().chunks(self.width) {
+            for colour in row {
+                match colour {
+                    0 => write!(formatter, ".")?,
+                    1 => write!(formatter, "#")?,
+                    _ => write!(formatter, "{:X}", colour)?,
+                }
+            }
+
+            writeln!(formatter)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_should_initialize_the_framebuffer() {
+        let framebuffer: Framebuffer = Framebuffer::initialize();
+
+        assert_eq!(vec![vec![false; 64 * 32]; 4], framebuffer.planes);
+        assert_eq!(0b0001, framebuffer.selected_planes);
+    }
+
+    #[test]
+    fn it_should_clear_the_framebuffer() {
+        let mut framebuffer: Framebuffer = Framebuffer::initialize();
+
+        framebuffer.planes[0][0x5] = true;
+
+        framebuffer.clear();
+
+        assert_eq!(vec![false; 64 * 32], framebuffer.planes[0]);
+    }
+
+    #[test]
+    fn it_should_draw_a_pixel_without_collision() {
+        let mut framebuffer: Framebuffer = Framebuffer::initialize();
+
+        let collision: bool = framebuffer.draw_pixel(true, 0x3, 0x1);
+
+        assert!(!collision);
+        assert!(framebuffer.planes[0][0x3 + 64]);
+    }
+
+    #[test]
+    fn it_should_draw_a_pixel_with_collision() {
+        let mut framebuffer: Framebuffer = Framebuffer::initialize();
+
+        framebuffer.planes[0][0x3 + 64] = true;
+
+        let collision: bool = framebuffer.draw_pixel(true, 0x3, 0x1);
+
+        assert!(collision);
+        assert!(!framebuffer.planes[0][0x3 + 64]);
+    }
+
+    #[test]
+    fn it_should_keep_a_pixel_when_drawing_an_unset_bit() {
+        let mut framebuffer: Framebuffer = Framebuffer::initialize();
+
+        framebuffer.planes[0][0x3 + 64] = true;
+
+        let collision: bool = framebuffer.draw_pixel(false, 0x3, 0x1);
+
+        assert!(!collision);
+        assert!(framebuffer.planes[0][0x3 + 64]);
+    }
+
+    #[test]
+    fn it_should_get_a_pixel() {
+        let mut framebuffer: Framebuffer = Framebuffer::initialize();
+
+        framebuffer.planes[0][0x3 + 64] = true;
+
+        assert!(framebuffer.get_pixel(0x3, 0x1));
+        assert!(!framebuffer.get_pixel(0x4, 0x1));
+    }
+
+    #[test]
+    fn it_should_format_the_framebuffer_as_text() {
+        let mut framebuffer: Framebuffer = Framebuffer::initialize();
+
+        framebuffer.planes[0][0x0] = true;
+        framebuffer.planes[0][0x3 + 64] = true;
+
+        let text: String = framebuffer.to_string();
+        let rows: Vec<&str> = text.lines().collect();
+
+        assert_eq!(32, rows.len());
+        assert_eq!(format!("#{}", ".".repeat(63)), rows[0]);
+        assert_eq!(format!("...#{}", ".".repeat(60)), rows[1]);
+        assert_eq!(".".repeat(64), rows[2]);
+    }
+
+    #[test]
+    fn it_should_switch_to_high_resolution_and_back() {
+        let mut framebuffer: Framebuffer = Framebuffer::initialize();
+
+        framebuffer.planes[0][0x5] = true;
+
+        framebuffer.set_high_resolution(true);
+
+        assert!(framebuffer.is_high_resolution());
+        assert_eq!(128, framebuffer.get_width());
+        assert_eq!(64, framebuffer.get_height());
+        assert_eq!(vec![false; 128 * 64], framebuffer.planes[0]);
+
+        framebuffer.set_high_resolution(false);
+
+        assert!(!framebuffer.is_high_resolution());
+        assert_eq!(vec![false; 64 * 32], framebuffer.planes[0]);
+    }
+
+    #[test]
+    fn it_should_draw_a_pixel_in_high_resolution() {
+        let mut framebuffer: Framebuffer = Framebuffer::initialize();
+
+        framebuffer.set_high_resolution(true);
+        framebuffer.draw_pixel(true, 0x7F, 0x3F);
+
+        assert!(framebuffer.planes[0][0x7F + (0x3F * 128)]);
+        assert!(framebuffer.get_pixel(0x7F, 0x3F));
+    }
+
+    #[test]
+    fn it_should_scroll_down() {
+        let mut framebuffer: Framebuffer = Framebuffer::initialize();
+
+        framebuffer.planes[0][0x3] = true;
+        framebuffer.planes[0][0x3 + (31 * 64)] = true;
+
+        framebuffer.scroll_down(2);
+
+        assert!(!framebuffer.get_pixel(0x3, 0));
+        assert!(framebuffer.get_pixel(0x3, 2));
+        assert_eq!(1, framebuffer.planes[0].iter().filter(|pixel| **pixel).count());
+    }
+
+    #[test]
+    fn it_should_scroll_left() {
+        let mut framebuffer: Framebuffer = Framebuffer::initialize();
+
+        framebuffer.planes[0][0x2] = true;
+        framebuffer.planes[0][0x6 + 64] = true;
+
+        framebuffer.scroll_left(4);
+
+        assert!(framebuffer.get_pixel(0x2, 1));
+        assert_eq!(1, framebuffer.planes[0].iter().filter(|pixel| **pixel).count());
+    }
+
+    #[test]
+    fn it_should_scroll_right() {
+        let mut framebuffer: Framebuffer = Framebuffer::initialize();
+
+        framebuffer.planes[0][0x2] = true;
+        framebuffer.planes[0][62 + 64] = true;
+
+        framebuffer.scroll_right(4);
+
+        assert!(framebuffer.get_pixel(0x6, 0));
+        assert_eq!(1, framebuffer.planes[0].iter().filter(|pixel| **pixel).count());
+    }
+
+    #[test]
+    fn it_should_select_planes() {
+        let mut framebuffer: Framebuffer = Framebuffer::initialize();
+
+        framebuffer.select_planes(0b0110);
+
+        assert_eq!(0b0110, framebuffer.get_selected_planes());
+        assert!(!framebuffer.is_plane_selected(0));
+        assert!(framebuffer.is_plane_selected(1));
+        assert!(framebuffer.is_plane_selected(2));
+        assert!(!framebuffer.is_plane_selected(3));
+    }
+
+    #[test]
+    fn it_should_clear_only_the_selected_planes() {
+        let mut framebuffer: Framebuffer = Framebuffer::initialize();
+
+        framebuffer.planes[0][0x5] = true;
+        framebuffer.planes[1][0x5] = true;
+
+        framebuffer.select_planes(0b0010);
+        framebuffer.clear();
+
+        assert!(framebuffer.planes[0][0x5]);
+        assert!(!framebuffer.planes[1][0x5]);
+    }
+
+    #[test]
+    fn it_should_scroll_only_the_selected_planes() {
+        let mut framebuffer: Framebuffer = Framebuffer::initialize();
+
+        framebuffer.planes[0][0x5] = true;
+        framebuffer.planes[1][0x5] = true;
+
+        framebuffer.select_planes(0b0010);
+        framebuffer.scroll_down(1);
+
+        assert!(framebuffer.planes[0][0x5]);
+        assert!(!framebuffer.planes[1][0x5]);
+        assert!(framebuffer.planes[1][0x5 + 64]);
+    }
+
+    #[test]
+    fn it_should_draw_a_pixel_on_a_plane() {
+        let mut framebuffer: Framebuffer = Framebuffer::initialize();
+
+        framebuffer.planes[2][0x3 + 64] = true;
+
+        assert!(framebuffer.draw_plane_pixel(2, true, 0x3, 0x1));
+        assert!(!framebuffer.draw_plane_pixel(3, true, 0x3, 0x1));
+        assert!(!framebuffer.get_plane_pixels(2)[0x3 + 64]);
+        assert!(framebuffer.get_plane_pixels(3)[0x3 + 64]);
+        assert!(!framebuffer.get_pixel(0x3, 0x1));
+    }
+
+    #[test]
+    fn it_should_combine_the_planes_into_colours() {
+        let mut framebuffer: Framebuffer = Framebuffer::initialize();
+
+        framebuffer.planes[0][0x0] = true;
+        framebuffer.planes[1][0x1] = true;
+        framebuffer.planes[0][0x2] = true;
+        framebuffer.planes[1][0x2] = true;
+        framebuffer.planes[3][0x3] = true;
+
+        assert_eq!([0x1, 0x2, 0x3, 0x8, 0x0], framebuffer.get_colours()[0x0..0x5]);
+        assert!(framebuffer.to_string().starts_with("#238."));
+    }
+
+    #[test]
+    fn it_should_save_and_load_the_state() {
+        let mut framebuffer: Framebuffer = Framebuffer::initialize();
+
+        framebuffer.set_high_resolution(true);
+        framebuffer.draw_plane_pixel(0, true, 127, 63);
+        framebuffer.draw_plane_pixel(2, true, 127, 63);
+        framebuffer.draw_plane_pixel(3, true, 0, 0);
+        framebuffer.select_planes(0b1010);
+
+        let mut writer: StateWriter = StateWriter::initialize();
+
+        framebuffer.save_state(&mut writer);
+
+        let bytes: Vec<u8> = writer.into_bytes();
+        let mut reader: StateReader = StateReader::initialize(&bytes).unwrap();
+        let restored: Framebuffer = Framebuffer::load_state(&mut reader).unwrap();
+
+        assert!(restored.is_high_resolution());
+        assert_eq!(0b1010, restored.get_selected_planes());
+        assert_eq!(framebuffer.get_colours(), restored.get_colours());
+    }
+}
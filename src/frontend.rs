@@ -0,0 +1,25 @@
+use crate::error::EmulatorError;
+use crate::framebuffer::Framebuffer;
+
+pub trait Display {
+    fn is_open(&self) -> bool;
+
+    fn refresh(&mut self, framebuffer: &Framebuffer) -> Result<(), EmulatorError>;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Hotkey {
+    SaveState(usize),
+    LoadState(usize),
+    Rewind,
+}
+
+pub trait Input {
+    fn is_pressed(&self, key_code: usize) -> bool;
+
+    fn get_hotkey(&self) -> Option<Hotkey>;
+}
+
+pub trait Frontend: Display + Input {}
+
+impl<T: Display + Input> Frontend for T {}
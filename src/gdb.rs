@@ -0,0 +1,748 @@
+use crate::machine::Machine;
+use crate::watchpoint::{Trigger, Watchpoint};
+use std::io::{self, Read, Write};
+use std::net::TcpStream;
+
+// The registers in the order of the g and G packets: V0 to VF, then I and
+// the program counter as 16-bit little endian values, the stack pointer
+// (as the number of calls on the stack) and the two timers.
+const REGISTERS: [(&str, usize); 21] = [
+    ("v0", 8), ("v1", 8), ("v2", 8), ("v3", 8), ("v4", 8), ("v5", 8), ("v6", 8), ("v7", 8),
+    ("v8", 8), ("v9", 8), ("va", 8), ("vb", 8), ("vc", 8), ("vd", 8), ("ve", 8), ("vf", 8),
+    ("i", 16), ("pc", 16), ("sp", 8), ("dt", 8), ("st", 8),
+];
+
+const I_REGISTER: usize = 16;
+const PROGRAM_COUNTER: usize = 17;
+const STACK_POINTER: usize = 18;
+const DELAY_TIMER: usize = 19;
+const SOUND_TIMER: usize = 20;
+
+#[derive(Debug, PartialEq)]
+pub enum Response {
+    Reply(String),
+    Close(Option<String>),
+}
+
+pub struct GdbStub {
+    machine: Machine,
+    last_stop: String,
+}
+
+impl GdbStub {
+    pub const SIGINT: u8 = 2;
+    pub const SIGILL: u8 = 4;
+    pub const SIGTRAP: u8 = 5;
+
+    pub fn initialize(mut machine: Machine) -> Self {
+        machine.pause();
+
+        GdbStub {
+            machine,
+            last_stop: format!("S{:02x}", GdbStub::SIGTRAP),
+        }
+    }
+
+    // Serves a single debugger until it detaches, kills the program or hangs up.
+    pub fn serve(&mut self, stream: TcpStream) -> io::Result<()> {
+        let mut connection: Connection = Connection { stream };
+
+        while let Some(packet) = connection.read_packet()? {
+            let response: Response = self.respond(&packet, &mut || connection.is_interrupted());
+
+            match response {
+                Response::Reply(reply) => connection.write_packet(&reply)?,
+                Response::Close(reply) => {
+                    if let Some(reply) = reply {
+                        connection.write_packet(&reply)?;
+                    }
+
+                    break;
+                },
+            }
+        }
+
+        Ok(())
+    }
+
+    // Anything the stub doesn't support gets an empty reply, which is how
+    // the protocol says so.
+    pub fn respond(&mut self, packet: &str, interrupted: &mut dyn FnMut() -> bool) -> Response {
+        let command: &str = packet.get(..1).unwrap_or("");
+        let arguments: &str = packet.get(1..).unwrap_or("");
+
+        let reply: Option<String> = match command {
+            "?" => Some(self.last_stop.clone()),
+            "g" => Some(self.read_registers()),
+            "G" => self.write_registers(arguments),
+            "p" => self.read_register(arguments),
+            "P" => self.write_register(arguments),
+            "m" => self.read_memory(arguments),
+            "M" => self.write_memory(arguments),
+            "c" => self.jump(arguments).map(|_| self.resume(interrupted)),
+            "s" => self.jump(arguments).map(|_| self.step()),
+            "Z" => self.insert_breakpoint(arguments),
+            "z" => self.remove_breakpoint(arguments),
+            "H" => Some(String::from("OK")),
+            "k" => return Response::Close(None),
+            "D" => return Response::Close(Some(String::from("OK"))),
+            "q" => Some(self.query(arguments)),
+            _ => Some(String::new()),
+        };
+
+        Response::Reply(reply.unwrap_or_else(|| String::from("E01")))
+    }
+
+    pub fn machine(&self) -> &Machine {
+        &self.machine
+    }
+
+    fn query(&self, query: &str) -> String {
+        if query.starts_with("Supported") {
+            return String::from("PacketSize=1000;qXfer:features:read+");
+        }
+
+        if let Some(range) = query.strip_prefix("Xfer:features:read:target.xml:") {
+            return parse_pair(range, ',')
+                .and_then(|(offset, length)| read_chunk(&target_description(), offset, length))
+                .unwrap_or_else(|| String::from("E01"));
+        }
+
+        match query {
+            "Attached" => String::from("1"),
+            "C" => String::from("QC1"),
+            "fThreadInfo" => String::from("m1"),
+            "sThreadInfo" => String::from("l"),
+            _ => String::new(),
+        }
+    }
+
+    fn read_registers(&self) -> String {
+        (0..REGISTERS.len())
+            .map(|register| encode_hex(&self.get_register(register)))
+            .collect()
+    }
+
+    fn write_registers(&mut self, values: &str) -> Option<String> {
+        let mut bytes: Vec<u8> = decode_hex(values)?;
+
+        for (register, (_, bits)) in REGISTERS.iter().enumerate() {
+            let size: usize = bits / 8;
+
+            if bytes.len() < size {
+                return None;
+            }
+
+            let value: Vec<u8> = bytes.drain(..size).collect();
+
+            // The stack pointer only follows the calls, so writing it is ignored.
+            if register != STACK_POINTER {
+                self.set_register(register, &value);
+            }
+        }
+
+        Some(String::from("OK"))
+    }
+
+    fn read_register(&self, register: &str) -> Option<String> {
+        let register: usize = usize::from_str_radix(register, 16).ok()?;
+
+        match register < REGISTERS.len() {
+            true => Some(encode_hex(&self.get_register(register))),
+            false => None,
+        }
+    }
+
+    fn write_register(&mut self, assignment: &str) -> Option<String> {
+        let (register, value): (&str, &str) = assignment.split_once('=')?;
+        let register: usize = usize::from_str_radix(register, 16).ok()?;
+        let value: Vec<u8> = decode_hex(value)?;
+
+        if register >= REGISTERS.len() || register == STACK_POINTER || value.len() != REGISTERS[register].1 / 8 {
+            return None;
+        }
+
+        self.set_register(register, &value);
+
+        Some(String::from("OK"))
+    }
+
+    fn get_register(&self, register: usize) -> Vec<u8> {
+        let cpu = self.machine.cpu();
+
+        match register {
+            I_REGISTER => cpu.get_i_register().to_le_bytes().to_vec(),
+            PROGRAM_COUNTER => (cpu.get_program_counter() as u16).to_le_bytes().to_vec(),
+            STACK_POINTER => vec![cpu.get_stack().len() as u8],
+            DELAY_TIMER => vec![cpu.get_delay_timer()],
+            SOUND_TIMER => vec![cpu.get_sound_timer()],
+            _ => vec![cpu.get_v_register(register)],
+        }
+    }
+
+    fn set_register(&mut self, register: usize, value: &[u8]) {
+        let cpu = self.machine.cpu_mut();
+
+        match register {
+            I_REGISTER => cpu.set_i_register(u16::from_le_bytes([value[0], value[1]])),
+            PROGRAM_COUNTER => cpu.set_program_counter(usize::from(u16::from_le_bytes([value[0], value[1]]))),
+            STACK_POINTER => {},
+            DELAY_TIMER => cpu.set_delay_timer(value[0]),
+            SOUND_TIMER => cpu.set_sound_timer(value[0]),
+            _ => cpu.set_v_register(register, value[0]),
+        }
+
+        // Changes made by the debugger shouldn't set the watchpoints off.
+        cpu.take_trigger();
+    }
+
+    fn read_memory(&self, range: &str) -> Option<String> {
+        let (address, length): (usize, usize) = parse_pair(range, ',')?;
+
+        let bytes: Vec<u8> = (address..address.checked_add(length)?)
+            .map(|location| self.machine.memory().peek(location).ok())
+            .collect::<Option<Vec<u8>>>()?;
+
+        Some(encode_hex(&bytes))
+    }
+
+    fn write_memory(&mut self, arguments: &str) -> Option<String> {
+        let (range, data): (&str, &str) = arguments.split_once(':')?;
+        let (address, length): (usize, usize) = parse_pair(range, ',')?;
+        let bytes: Vec<u8> = decode_hex(data)?;
+
+        if bytes.len() != length || address.checked_add(length)? > self.machine.memory().get_size() {
+            return None;
+        }
+
+        let memory = self.machine.memory_mut();
+
+        for (index, byte) in bytes.into_iter().enumerate() {
+            memory.set(address + index, byte).ok()?;
+        }
+
+        memory.take_trigger();
+
+        Some(String::from("OK"))
+    }
+
+    // c and s can take the address to go on from.
+    fn jump(&mut self, address: &str) -> Option<()> {
+        if !address.is_empty() {
+            let address: usize = usize::from_str_radix(address, 16).ok()?;
+
+            self.machine.cpu_mut().set_program_counter(address);
+        }
+
+        Some(())
+    }
+
+    fn step(&mut self) -> String {
+        let reply: String = match self.machine.step() {
+            Ok(()) => self.get_stop_reply(),
+            Err(_) => format!("S{:02x}", GdbStub::SIGILL),
+        };
+
+        self.machine.pause();
+        self.last_stop = reply.clone();
+
+        reply
+    }
+
+    // The machine runs frame by frame, without pacing, until it stops by
+    // itself or the debugger interrupts it.
+    fn resume(&mut self, interrupted: &mut dyn FnMut() -> bool) -> String {
+        self.machine.resume();
+
+        let reply: String = loop {
+            if self.machine.run_frame().is_err() {
+                break format!("S{:02x}", GdbStub::SIGILL);
+            }
+
+            if self.machine.is_halted() || self.machine.is_paused() {
+                break self.get_stop_reply();
+            }
+
+            if interrupted() {
+                break format!("S{:02x}", GdbStub::SIGINT);
+            }
+        };
+
+        self.machine.pause();
+        self.last_stop = reply.clone();
+
+        reply
+    }
+
+    fn get_stop_reply(&self) -> String {
+        if self.machine.is_halted() {
+            return String::from("W00");
+        }
+
+        match self.machine.get_trigger() {
+            Some(Trigger::Write { address, .. }) => format!("T{:02x}watch:{:x};", GdbStub::SIGTRAP, address),
+            Some(Trigger::Read { address }) => format!("T{:02x}rwatch:{:x};", GdbStub::SIGTRAP, address),
+            _ => format!("S{:02x}", GdbStub::SIGTRAP),
+        }
+    }
+
+    // Z0 and Z1 are breakpoints, Z2, Z3 and Z4 are write, read and access
+    // watchpoints over the given amount of bytes.
+    fn insert_breakpoint(&mut self, arguments: &str) -> Option<String> {
+        let (kind, address, length): (char, usize, usize) = parse_breakpoint(arguments)?;
+
+        match kind {
+            '0' | '1' => self.machine.add_breakpoint(address),
+            '2' | '3' | '4' => {
+                for watchpoint in get_watchpoints(kind, address, length)? {
+                    self.machine.add_watchpoint(watchpoint);
+                }
+            },
+            _ => return Some(String::new()),
+        }
+
+        Some(String::from("OK"))
+    }
+
+    fn remove_breakpoint(&mut self, arguments: &str) -> Option<String> {
+        let (kind, address, length): (char, usize, usize) = parse_breakpoint(arguments)?;
+
+        match kind {
+            '0' | '1' => {
+                self.machine.remove_breakpoint(address);
+            },
+            '2' | '3' | '4' => {
+                for watchpoint in get_watchpoints(kind, address, length)? {
+                    let index: Option<usize> = self.machine.get_watchpoints()
+                        .iter()
+                        .position(|existing| *existing == watchpoint);
+
+                    if let Some(index) = index {
+                        self.machine.remove_watchpoint(index);
+                    }
+                }
+            },
+            _ => return Some(String::new()),
+        }
+
+        Some(String::from("OK"))
+    }
+}
+
+struct Connection {
+    stream: TcpStream,
+}
+
+impl Connection {
+    // Skips the acknowledgements, and asks for the packets with a wrong
+    // checksum again.
+    fn read_packet(&mut self) -> io::Result<Option<String>> {
+        loop {
+            match self.read_byte()? {
+                None => return Ok(None),
+                Some(b'$') => {},
+                Some(_) => continue,
+            }
+
+            let mut data: Vec<u8> = Vec::new();
+
+            loop {
+                match self.read_byte()? {
+                    None => return Ok(None),
+                    Some(b'#') => break,
+                    Some(byte) => data.push(byte),
+                }
+            }
+
+            let checksum: Option<u8> = match (self.read_byte()?, self.read_byte()?) {
+                (Some(high), Some(low)) => decode_hex(&String::from_utf8_lossy(&[high, low])).map(|bytes| bytes[0]),
+                _ => return Ok(None),
+            };
+
+            if checksum == Some(calculate_checksum(&data)) {
+                self.stream.write_all(b"+")?;
+
+                return Ok(Some(String::from_utf8_lossy(&data).into_owned()));
+            }
+
+            self.stream.write_all(b"-")?;
+        }
+    }
+
+    fn write_packet(&mut self, data: &str) -> io::Result<()> {
+        let packet: String = format!("${}#{:02x}", data, calculate_checksum(data.as_bytes()));
+
+        self.stream.write_all(packet.as_bytes())?;
+        self.stream.flush()
+    }
+
+    fn read_byte(&mut self) -> io::Result<Option<u8>> {
+        let mut byte: [u8; 1] = [0x0];
+
+        match self.stream.read(&mut byte)? {
+            0 => Ok(None),
+            _ => Ok(Some(byte[0])),
+        }
+    }
+
+    // The debugger sends a lone 0x03 byte to interrupt the program, and
+    // hanging up stops it as well.
+    fn is_interrupted(&mut self) -> bool {
+        if self.stream.set_nonblocking(true).is_err() {
+            return true;
+        }
+
+        let mut byte: [u8; 1] = [0x0];
+
+        let interrupted: bool = match self.stream.read(&mut byte) {
+            Ok(0) => true,
+            Ok(_) => byte[0] == 0x03,
+            Err(error) => error.kind() != io::ErrorKind::WouldBlock,
+        };
+
+        self.stream.set_nonblocking(false).is_err() || interrupted
+    }
+}
+
+fn get_watchpoints(kind: char, address: usize, length: usize) -> Option<Vec<Watchpoint>> {
+    let end: usize = address.checked_add(length.max(1) - 1)?;
+
+    Some(match kind {
+        '2' => vec![Watchpoint::Write { start: address, end }],
+        '3' => vec![Watchpoint::Read { start: address, end }],
+        _ => vec![Watchpoint::Write { start: address, end }, Watchpoint::Read { start: address, end }],
+    })
+}
+
+fn parse_breakpoint(arguments: &str) -> Option<(char, usize, usize)> {
+    let mut parts = arguments.split(',');
+
+    let kind: char = parts.next()?.chars().next()?;
+    let address: usize = usize::from_str_radix(parts.next()?, 16).ok()?;
+    let length: usize = usize::from_str_radix(parts.next()?, 16).ok()?;
+
+    Some((kind, address, length))
+}
+
+fn parse_pair(arguments: &str, separator: char) -> Option<(usize, usize)> {
+    let (first, second): (&str, &str) = arguments.split_once(separator)?;
+
+    Some((usize::from_str_radix(first, 16).ok()?, usize::from_str_radix(second, 16).ok()?))
+}
+
+fn read_chunk(document: &str, offset: usize, length: usize) -> Option<String> {
+    let start: usize = offset.min(document.len());
+    let end: usize = offset.checked_add(length)?.min(document.len());
+
+    Some(match end == document.len() {
+        true => format!("l{}", &document[start..end]),
+        false => format!("m{}", &document[start..end]),
+    })
+}
+
+fn target_description() -> String {
+    let registers: String = REGISTERS.iter()
+        .map(|(name, size)| match *name {
+            "pc" => format!("<reg name=\"{}\" bitsize=\"{}\" type=\"code_ptr\"/>", name, size),
+            "i" => format!("<reg name=\"{}\" bitsize=\"{}\" type=\"data_ptr\"/>", name, size),
+            _ => format!("<reg name=\"{}\" bitsize=\"{}\"/>", name, size),
+        })
+        .collect();
+
+    format!(
+        "<?xml version=\"1.0\"?><!DOCTYPE target SYSTEM \"gdb-target.dtd\"><target><feature name=\"org.chip8rs.core\">{}</feature></target>",
+        registers
+    )
+}
+
+fn calculate_checksum(data: &[u8]) -> u8 {
+    data.iter().fold(0, |checksum: u8, byte| checksum.wrapping_add(*byte))
+}
+
+fn encode_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn decode_hex(text: &str) -> Option<Vec<u8>> {
+    if text.len() & 0x1 == 0x1 {
+        return None;
+    }
+
+    (0..text.len())
+        .step_by(2)
+        .map(|index| u8::from_str_radix(text.get(index..index + 2)?, 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+    use super::*;
+    use std::net::TcpListener;
+    use std::thread;
+
+    const SUBROUTINE: [u8; 12] = [0x22, 0x06, 0x70, 0x10, 0x12, 0x04, 0x71, 0x01, 0x71, 0x01, 0x00, 0xEE];
+
+    fn stub(program: &[u8]) -> GdbStub {
+        let mut machine: Machine = Machine::initialize();
+
+        machine.load_rom(program.to_vec()).unwrap();
+
+        GdbStub::initialize(machine)
+    }
+
+    fn respond(stub: &mut GdbStub, packet: &str) -> Response {
+        stub.respond(packet, &mut || false)
+    }
+
+    fn reply(text: &str) -> Response {
+        Response::Reply(String::from(text))
+    }
+
+    #[test]
+    fn it_should_read_the_registers() {
+        let mut stub: GdbStub = stub(&SUBROUTINE);
+
+        respond(&mut stub, "s");
+
+        // The V registers, then I, the program counter, the stack pointer and the timers.
+        assert_eq!(
+            reply(&format!("{}{}", "00".repeat(16), "00000602010000")),
+            respond(&mut stub, "g")
+        );
+    }
+
+    #[test]
+    fn it_should_write_the_registers() {
+        let mut stub: GdbStub = stub(&SUBROUTINE);
+
+        assert_eq!(
+            reply("OK"),
+            respond(&mut stub, "G0102030405060708090a0b0c0d0e0f10000306020f0a0b")
+        );
+
+        let cpu = stub.machine().cpu();
+
+        assert_eq!(0x1, cpu.get_v_register(0x0));
+        assert_eq!(0x10, cpu.get_v_register(0xF));
+        assert_eq!(0x300, cpu.get_i_register());
+        assert_eq!(0x206, cpu.get_program_counter());
+        assert_eq!(0x0, cpu.get_stack().len());
+        assert_eq!(0xA, cpu.get_delay_timer());
+        assert_eq!(0xB, cpu.get_sound_timer());
+    }
+
+    #[test_case("p0", "00" ; "with a v register")]
+    #[test_case("p11", "0002" ; "with the program counter")]
+    #[test_case("p15", "E01" ; "with an unknown register")]
+    fn it_should_read_a_register(packet: &str, expected: &str) {
+        let mut stub: GdbStub = stub(&SUBROUTINE);
+
+        assert_eq!(reply(expected), respond(&mut stub, packet));
+    }
+
+    #[test]
+    fn it_should_write_a_register() {
+        let mut stub: GdbStub = stub(&SUBROUTINE);
+
+        assert_eq!(reply("OK"), respond(&mut stub, "P3=ca"));
+        assert_eq!(reply("OK"), respond(&mut stub, "P10=0003"));
+        assert_eq!(reply("E01"), respond(&mut stub, "P12=01"));
+        assert_eq!(0xCA, stub.machine().cpu().get_v_register(0x3));
+        assert_eq!(0x300, stub.machine().cpu().get_i_register());
+    }
+
+    #[test]
+    fn it_should_read_and_write_the_memory() {
+        let mut stub: GdbStub = stub(&SUBROUTINE);
+
+        assert_eq!(reply("22067010"), respond(&mut stub, "m200,4"));
+        assert_eq!(reply("OK"), respond(&mut stub, "M300,2:cafe"));
+        assert_eq!(reply("cafe"), respond(&mut stub, "m300,2"));
+        assert_eq!(reply("E01"), respond(&mut stub, "mfff,2"));
+        assert_eq!(reply("E01"), respond(&mut stub, "Mfff,2:cafe"));
+    }
+
+    #[test_case("mffffffffffffffff,2" ; "with a memory read")]
+    #[test_case("Mffffffffffffffff,2:cafe" ; "with a memory write")]
+    #[test_case("Z2,ffffffffffffffff,2" ; "with a watchpoint")]
+    #[test_case("qXfer:features:read:target.xml:ffffffffffffffff,2" ; "with the target description")]
+    fn it_should_fail_when_the_range_overflows(packet: &str) {
+        let mut stub: GdbStub = stub(&SUBROUTINE);
+
+        assert_eq!(reply("E01"), respond(&mut stub, packet));
+    }
+
+    #[test]
+    fn it_should_continue_until_a_breakpoint() {
+        let mut stub: GdbStub = stub(&SUBROUTINE);
+
+        assert_eq!(reply("OK"), respond(&mut stub, "Z0,208,2"));
+        assert_eq!(reply("S05"), respond(&mut stub, "c"));
+        assert_eq!(0x208, stub.machine().cpu().get_program_counter());
+        assert_eq!(reply("OK"), respond(&mut stub, "z0,208,2"));
+        assert_eq!(reply("S05"), respond(&mut stub, "?"));
+    }
+
+    #[test]
+    fn it_should_continue_until_a_watchpoint() {
+        let mut stub: GdbStub = stub(&[0xA3, 0x00, 0x60, 0xCA, 0xF0, 0x55, 0x12, 0x06]);
+
+        assert_eq!(reply("OK"), respond(&mut stub, "Z2,300,1"));
+        assert_eq!(reply("T05watch:300;"), respond(&mut stub, "c"));
+        assert_eq!(reply("OK"), respond(&mut stub, "z2,300,1"));
+        assert!(stub.machine().get_watchpoints().is_empty());
+    }
+
+    #[test]
+    fn it_should_continue_until_interrupted() {
+        let mut stub: GdbStub = stub(&SUBROUTINE);
+
+        assert_eq!(reply("S02"), stub.respond("c", &mut || true));
+        assert!(stub.machine().is_paused());
+    }
+
+    #[test]
+    fn it_should_report_the_end_of_the_program() {
+        let mut stub: GdbStub = stub(&[0x00, 0xFD]);
+
+        assert_eq!(reply("W00"), respond(&mut stub, "c"));
+    }
+
+    #[test]
+    fn it_should_report_an_error_of_the_program() {
+        let mut stub: GdbStub = stub(&[0x00, 0xEE]);
+
+        assert_eq!(reply("S04"), respond(&mut stub, "s"));
+    }
+
+    #[test]
+    fn it_should_step_from_an_address() {
+        let mut stub: GdbStub = stub(&SUBROUTINE);
+
+        assert_eq!(reply("S05"), respond(&mut stub, "s206"));
+        assert_eq!(0x208, stub.machine().cpu().get_program_counter());
+        assert_eq!(0x1, stub.machine().cpu().get_v_register(0x1));
+    }
+
+    #[test]
+    fn it_should_describe_the_target() {
+        let mut stub: GdbStub = stub(&SUBROUTINE);
+
+        assert_eq!(reply("m<?xml"), respond(&mut stub, "qXfer:features:read:target.xml:0,5"));
+
+        match respond(&mut stub, "qXfer:features:read:target.xml:0,1000") {
+            Response::Reply(document) => {
+                assert!(document.starts_with("l<?xml"));
+                assert!(document.contains("<reg name=\"pc\" bitsize=\"16\" type=\"code_ptr\"/>"));
+            },
+            response => panic!("Unexpected response {:?}", response),
+        }
+    }
+
+    #[test_case("qSupported:multiprocess+", "PacketSize=1000;qXfer:features:read+" ; "with the supported features")]
+    #[test_case("qAttached", "1" ; "with the attached query")]
+    #[test_case("Hg0", "OK" ; "with a thread selection")]
+    #[test_case("vMustReplyEmpty", "" ; "with an unsupported packet")]
+    fn it_should_answer_a_packet(packet: &str, expected: &str) {
+        let mut stub: GdbStub = stub(&SUBROUTINE);
+
+        assert_eq!(reply(expected), respond(&mut stub, packet));
+    }
+
+    #[test]
+    fn it_should_close_on_detach_and_kill() {
+        let mut stub: GdbStub = stub(&SUBROUTINE);
+
+        assert_eq!(Response::Close(Some(String::from("OK"))), respond(&mut stub, "D"));
+        assert_eq!(Response::Close(None), respond(&mut stub, "k"));
+    }
+
+    fn send(client: &mut TcpStream, data: &str) -> String {
+        let packet: String = format!("${}#{:02x}", data, calculate_checksum(data.as_bytes()));
+
+        client.write_all(packet.as_bytes()).unwrap();
+
+        receive(client)
+    }
+
+    // Reads the acknowledgement and the reply, and acknowledges it back.
+    fn receive(client: &mut TcpStream) -> String {
+        let mut received: Vec<u8> = Vec::new();
+        let mut byte: [u8; 1] = [0x0];
+
+        while !received.ends_with(b"#") {
+            client.read_exact(&mut byte).unwrap();
+            received.push(byte[0]);
+        }
+
+        let mut checksum: [u8; 2] = [0x0; 2];
+
+        client.read_exact(&mut checksum).unwrap();
+        client.write_all(b"+").unwrap();
+
+        let text: String = String::from_utf8(received).unwrap();
+        let start: usize = text.find('$').unwrap();
+
+        assert_eq!("+", &text[..start]);
+
+        text[start + 1..text.len() - 1].to_string()
+    }
+
+    #[test]
+    fn it_should_serve_a_debugger_over_tcp() {
+        let listener: TcpListener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+
+        let server = thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut stub: GdbStub = stub(&SUBROUTINE);
+
+            stub.serve(stream).unwrap();
+
+            stub.machine().cpu().get_v_register(0x1)
+        });
+
+        let mut client: TcpStream = TcpStream::connect(address).unwrap();
+
+        // A wrong checksum gets the packet asked for again.
+        client.write_all(b"$?#00").unwrap();
+
+        let mut nack: [u8; 1] = [0x0];
+
+        client.read_exact(&mut nack).unwrap();
+
+        assert_eq!(b"-", &nack);
+        assert_eq!("S05", send(&mut client, "?"));
+        assert_eq!("OK", send(&mut client, "Z0,20a,2"));
+        assert_eq!("S05", send(&mut client, "c"));
+        assert_eq!("0a02", send(&mut client, "p11"));
+        assert_eq!("S05", send(&mut client, "s"));
+        assert_eq!("OK", send(&mut client, "D"));
+        assert_eq!(0x2, server.join().unwrap());
+    }
+
+    #[test]
+    fn it_should_be_interrupted_over_tcp() {
+        let listener: TcpListener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+
+        let server = thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut stub: GdbStub = stub(&[0x12, 0x00]);
+
+            stub.serve(stream).unwrap();
+        });
+
+        let mut client: TcpStream = TcpStream::connect(address).unwrap();
+        let packet: String = format!("$c#{:02x}", calculate_checksum(b"c"));
+
+        client.write_all(packet.as_bytes()).unwrap();
+        client.write_all(&[0x03]).unwrap();
+
+        assert_eq!("S02", receive(&mut client));
+        assert_eq!("OK", send(&mut client, "D"));
+
+        server.join().unwrap();
+    }
+}
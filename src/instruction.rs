@@ -1,148 +1,265 @@
 use std::ops::{Div, Sub};
 
 use crate::cpu::Cpu;
+use crate::error::EmulatorError;
+use crate::framebuffer::Framebuffer;
+use crate::keypad::Keypad;
 use crate::memory::Memory;
-use mockall_double::double;
-use rand::Rng;
-
-#[double]
-use crate::interface::Interface;
+use crate::op::{self, Op};
+use crate::quirks::{IndexIncrement, Quirks};
 
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Instruction {
     opcode: u16,
+    op: Op,
 }
 
 impl Instruction {
     pub fn initialize(first_byte: u8, second_byte: u8) -> Self {
+        let opcode: u16 = u16::from(first_byte) << 8 | u16::from(second_byte);
+
         Instruction {
-            opcode: u16::from(first_byte) << 8 | u16::from(second_byte),
+            opcode,
+            op: op::decode(opcode),
         }
     }
 
-    pub fn interpret(&mut self, cpu: &mut Cpu, memory: &mut Memory, interface: &mut Interface) {
-        match self.opcode & 0xF000 {
-            0x0000 => match self.opcode {
-                0x00E0 => self.clear_display(interface),
-                0x00EE => self.return_from_subroutine(cpu),
-                _ => (),
-            },
-            0x1000 => self.jump_to_address(cpu),
-            0x2000 => self.call_subroutine_at_address(cpu),
-            0x3000 => self.skip_next_instruction_if_argument_equals_v_register_value(cpu),
-            0x4000 => self.skip_next_instruction_if_argument_differs_v_register_value(cpu),
-            0x5000 => self.skip_next_instruction_if_v_registers_values_are_the_same(cpu),
-            0x6000 => self.put_argument_value_on_v_register(cpu),
-            0x7000 => self.add_argument_value_to_v_register(cpu),
-            0x8000 => match self.opcode & 0xF00F {
-                0x8000 => self.put_v_register_value_on_other_v_register(cpu),
-                0x8001 => self.put_value_of_bitwise_or_operation_between_v_registers_on_first_passed_register(cpu),
-                0x8002 => self.put_value_of_bitwise_and_operation_between_v_registers_on_first_passed_register(cpu),
-                0x8003 => self.put_value_of_bitwise_xor_operation_between_v_registers_on_first_passed_register(cpu),
-                0x8004 => self.put_value_of_sum_operation_between_v_registers_on_first_passed_register(cpu),
-                0x8005 => self.put_value_of_subtraction_operation_between_v_registers_on_first_passed_register(cpu),
-                0x8006 => self.put_value_of_bitwise_shift_right_operation_between_v_registers_on_first_passed_register(cpu),
-                0x8007 => self.put_value_of_inverted_subtraction_operation_between_v_registers_on_first_passed_register(cpu),
-                0x800E => self.put_value_of_bitwise_shift_left_operation_between_v_registers_on_first_passed_register(cpu),
-                _ => (),
-            },
-            0x9000 => self.skip_next_instruction_if_v_registers_values_are_different(cpu),
-            0xA000 => self.put_argument_value_on_i_register(cpu),
-            0xB000 => self.jump_to_argument_value_plus_v0(cpu),
-            0xC000 => self.put_value_of_bitwise_or_operation_between_argument_and_random_byte_on_passed_v_register(cpu),
-            0xD000 => self.draw_byte_sprite_starting_at_location_i_on_register_stored_location(cpu, memory, interface),
-            0xE000 => match self.opcode & 0xF0FF {
-                0xE09E => self.skip_next_instruction_if_key_with_v_register_value_is_pressed(interface, cpu),
-                0xE0A1 => self.skip_next_instruction_if_key_with_v_register_value_is_not_pressed(interface, cpu),
-                _ => (),
-            }
-            0xF000 => match self.opcode & 0xF0FF {
-                0xF007 => self.put_delay_timer_value_on_v_register(cpu),
-                0xF00A => self.wait_for_key_press_and_store_value_on_v_register(cpu, interface),
-                0xF015 => self.put_v_register_value_on_delay_timer(cpu),
-                0xF018 => self.put_v_register_value_on_sound_timer(cpu),
-                0xF01E => self.add_v_register_value_to_i_register_value(cpu),
-                0xF029 => self.put_location_of_sprite_for_v_register_digit_on_i_register(cpu),
-                0xF033 => self.put_bcd_representation_of_v_register_in_memory_locations_starting_on_i_register_location(cpu, memory),
-                0xF055 => self.put_values_of_v_registers_from_v0_to_passed_v_register_in_memory_starting_on_i_register_location(cpu, memory),
-                0xF065 => self.put_values_on_v_registers_from_v0_to_passed_v_register_from_memory_starting_on_i_register_location(cpu, memory),
-                _ => (),
-            },
-            _ => (),
+    pub fn get_op(&self) -> Op {
+        self.op
+    }
+
+    pub fn interpret(
+        &mut self,
+        cpu: &mut Cpu,
+        memory: &mut Memory,
+        framebuffer: &mut Framebuffer,
+        keypad: &Keypad,
+        quirks: &Quirks,
+    ) -> Result<(), EmulatorError> {
+        if (self.op.is_xo_chip() && !quirks.xo_chip) || (self.op.is_super_chip() && !quirks.super_chip) {
+            return Err(self.unknown_opcode(cpu));
+        }
+
+        match self.op {
+            Op::Clear => self.clear_display(framebuffer),
+            Op::Return => self.return_from_subroutine(cpu)?,
+            Op::ScrollDown { n } => self.scroll_display_down(framebuffer, n),
+            Op::ScrollRight => self.scroll_display_right(framebuffer),
+            Op::ScrollLeft => self.scroll_display_left(framebuffer),
+            Op::Exit => self.exit_interpreter(cpu),
+            Op::LowResolution => self.disable_high_resolution(framebuffer),
+            Op::HighResolution => self.enable_high_resolution(framebuffer),
+            Op::Jump { nnn } => self.jump_to_address(cpu, nnn),
+            Op::Call { nnn } => self.call_subroutine_at_address(cpu, nnn)?,
+            Op::SkipIfEqualImm { x, kk } => self.skip_next_instruction_if_argument_equals_v_register_value(cpu, memory, quirks, x, kk),
+            Op::SkipIfNotEqualImm { x, kk } => self.skip_next_instruction_if_argument_differs_v_register_value(cpu, memory, quirks, x, kk),
+            Op::SkipIfEqual { x, y } => self.skip_next_instruction_if_v_registers_values_are_the_same(cpu, memory, quirks, x, y),
+            Op::SaveRange { x, y } => self.put_values_of_v_registers_from_first_to_second_passed_register_in_memory_starting_on_i_register_location(cpu, memory, x, y)?,
+            Op::LoadRange { x, y } => self.put_values_on_v_registers_from_first_to_second_passed_register_from_memory_starting_on_i_register_location(cpu, memory, x, y)?,
+            Op::LoadImm { x, kk } => self.put_argument_value_on_v_register(cpu, x, kk),
+            Op::AddImm { x, kk } => self.add_argument_value_to_v_register(cpu, x, kk),
+            Op::Move { x, y } => self.put_v_register_value_on_other_v_register(cpu, x, y),
+            Op::Or { x, y } => self.put_value_of_bitwise_or_operation_between_v_registers_on_first_passed_register(cpu, quirks, x, y),
+            Op::And { x, y } => self.put_value_of_bitwise_and_operation_between_v_registers_on_first_passed_register(cpu, quirks, x, y),
+            Op::Xor { x, y } => self.put_value_of_bitwise_xor_operation_between_v_registers_on_first_passed_register(cpu, quirks, x, y),
+            Op::Add { x, y } => self.put_value_of_sum_operation_between_v_registers_on_first_passed_register(cpu, x, y),
+            Op::Sub { x, y } => self.put_value_of_subtraction_operation_between_v_registers_on_first_passed_register(cpu, x, y),
+            Op::ShiftRight { x, y } => self.put_value_of_bitwise_shift_right_operation_between_v_registers_on_first_passed_register(cpu, quirks, x, y),
+            Op::SubReverse { x, y } => self.put_value_of_inverted_subtraction_operation_between_v_registers_on_first_passed_register(cpu, x, y),
+            Op::ShiftLeft { x, y } => self.put_value_of_bitwise_shift_left_operation_between_v_registers_on_first_passed_register(cpu, quirks, x, y),
+            Op::SkipIfNotEqual { x, y } => self.skip_next_instruction_if_v_registers_values_are_different(cpu, memory, quirks, x, y),
+            Op::LoadI { nnn } => self.put_argument_value_on_i_register(cpu, nnn),
+            Op::JumpOffset { x, nnn } => self.jump_to_argument_value_plus_v_register(cpu, quirks, x, nnn),
+            Op::Random { x, kk } => self.put_value_of_bitwise_and_operation_between_argument_and_random_byte_on_passed_v_register(cpu, x, kk),
+            Op::Draw { x, y, n } => self.draw_byte_sprite_starting_at_location_i_on_register_stored_location(cpu, memory, framebuffer, quirks, x, y, n)?,
+            Op::SkipIfKey { x } => self.skip_next_instruction_if_key_with_v_register_value_is_pressed(keypad, cpu, memory, quirks, x),
+            Op::SkipIfNotKey { x } => self.skip_next_instruction_if_key_with_v_register_value_is_not_pressed(keypad, cpu, memory, quirks, x),
+            Op::LoadLongI => self.put_next_word_on_i_register(cpu, memory)?,
+            Op::SelectPlanes { n } => self.select_drawing_planes(framebuffer, n),
+            Op::LoadAudio => self.put_audio_pattern_from_memory_starting_on_i_register_location(cpu, memory)?,
+            Op::GetDelay { x } => self.put_delay_timer_value_on_v_register(cpu, x),
+            Op::WaitKey { x } => self.wait_for_key_press_and_store_value_on_v_register(cpu, keypad, x),
+            Op::SetDelay { x } => self.put_v_register_value_on_delay_timer(cpu, x),
+            Op::SetSound { x } => self.put_v_register_value_on_sound_timer(cpu, x),
+            Op::AddI { x } => self.add_v_register_value_to_i_register_value(cpu, x),
+            Op::Font { x } => self.put_location_of_sprite_for_v_register_digit_on_i_register(cpu, x),
+            Op::BigFont { x } => self.put_location_of_big_sprite_for_v_register_digit_on_i_register(cpu, x),
+            Op::SetPitch { x } => self.put_v_register_value_on_pitch(cpu, x),
+            Op::Bcd { x } => self.put_bcd_representation_of_v_register_in_memory_locations_starting_on_i_register_location(cpu, memory, x)?,
+            Op::Store { x } => self.put_values_of_v_registers_from_v0_to_passed_v_register_in_memory_starting_on_i_register_location(cpu, memory, quirks, x)?,
+            Op::Load { x } => self.put_values_on_v_registers_from_v0_to_passed_v_register_from_memory_starting_on_i_register_location(cpu, memory, quirks, x)?,
+            Op::StoreFlags { x } => self.put_values_of_v_registers_from_v0_to_passed_v_register_in_rpl_flags(cpu, quirks, x)?,
+            Op::LoadFlags { x } => self.put_values_on_v_registers_from_v0_to_passed_v_register_from_rpl_flags(cpu, quirks, x)?,
+            Op::Unknown { .. } => return Err(self.unknown_opcode(cpu)),
         }
+
+        Ok(())
     }
 
-    fn clear_display(&self, interface: &mut Interface) {
-        interface.clear();
+    fn unknown_opcode(&self, cpu: &Cpu) -> EmulatorError {
+        EmulatorError::UnknownOpcode {
+            opcode: self.opcode,
+            program_counter: cpu.get_program_counter().saturating_sub(2),
+        }
+    }
 
-        interface.refresh();
+    fn skip_next_instruction(&self, cpu: &mut Cpu, memory: &Memory, quirks: &Quirks) {
+        let program_counter: usize = cpu.get_program_counter();
+
+        // On XO-CHIP, F000 nnnn is four bytes long and has to be skipped whole.
+        let is_long_instruction: bool = quirks.xo_chip && matches!(
+            (memory.peek(program_counter), memory.peek(program_counter + 1)),
+            (Ok(0xF0), Ok(0x00))
+        );
+
+        cpu.increase_program_counter(if is_long_instruction { 0x4 } else { 0x2 });
     }
 
-    fn return_from_subroutine(&self, cpu: &mut Cpu) {
-        let top_value_from_stack: usize = cpu.stack_pop() as usize;
+    fn clear_display(&self, framebuffer: &mut Framebuffer) {
+        framebuffer.clear();
+    }
 
-        cpu.set_program_counter(top_value_from_stack);
+    fn scroll_display_down(&self, framebuffer: &mut Framebuffer, rows: u8) {
+        framebuffer.scroll_down(rows as usize);
+    }
+
+    fn scroll_display_right(&self, framebuffer: &mut Framebuffer) {
+        framebuffer.scroll_right(4);
     }
 
-    fn jump_to_address(&self, cpu: &mut Cpu) {
-        let address = usize::from(self.opcode & 0x0FFF);
+    fn scroll_display_left(&self, framebuffer: &mut Framebuffer) {
+        framebuffer.scroll_left(4);
+    }
 
-        cpu.set_program_counter(address);
+    fn exit_interpreter(&self, cpu: &mut Cpu) {
+        cpu.halt();
+    }
+
+    fn disable_high_resolution(&self, framebuffer: &mut Framebuffer) {
+        framebuffer.set_high_resolution(false);
+    }
+
+    fn enable_high_resolution(&self, framebuffer: &mut Framebuffer) {
+        framebuffer.set_high_resolution(true);
+    }
+
+    fn return_from_subroutine(&self, cpu: &mut Cpu) -> Result<(), EmulatorError> {
+        let top_value_from_stack: usize = cpu.stack_pop()? as usize;
+
+        cpu.set_program_counter(top_value_from_stack);
+
+        Ok(())
+    }
+
+    fn jump_to_address(&self, cpu: &mut Cpu, address: u16) {
+        cpu.set_program_counter(usize::from(address));
     }
 
-    fn call_subroutine_at_address(&self, cpu: &mut Cpu) {
+    fn call_subroutine_at_address(&self, cpu: &mut Cpu, address: u16) -> Result<(), EmulatorError> {
         cpu.stack_push(
             cpu.get_program_counter() as u16,
-        );
+        )?;
 
-        let address = usize::from(self.opcode & 0x0FFF);
+        cpu.set_program_counter(usize::from(address));
 
-        cpu.set_program_counter(address);
+        Ok(())
     }
 
-    fn skip_next_instruction_if_argument_equals_v_register_value(&self, cpu: &mut Cpu) {
-        let argument_value: u8 = (self.opcode as u8) & 0x00FF;
-        let register_number: usize = (self.opcode as usize) >> 8 & 0x000F;
-
+    fn skip_next_instruction_if_argument_equals_v_register_value(
+        &self,
+        cpu: &mut Cpu,
+        memory: &Memory,
+        quirks: &Quirks,
+        register_number: usize,
+        argument_value: u8,
+    ) {
         let register_value: u8 = cpu.get_v_register(register_number);
 
         if argument_value == register_value {
-            cpu.increase_program_counter(0x2);
+            self.skip_next_instruction(cpu, memory, quirks);
         }
     }
 
-    fn skip_next_instruction_if_argument_differs_v_register_value(&self, cpu: &mut Cpu) {
-        let argument_value: u8 = (self.opcode as u8) & 0x00FF;
-        let register_number: usize = (self.opcode as usize) >> 8 & 0x000F;
-
+    fn skip_next_instruction_if_argument_differs_v_register_value(
+        &self,
+        cpu: &mut Cpu,
+        memory: &Memory,
+        quirks: &Quirks,
+        register_number: usize,
+        argument_value: u8,
+    ) {
         let register_value: u8 = cpu.get_v_register(register_number);
 
         if argument_value != register_value {
-            cpu.increase_program_counter(0x2);
+            self.skip_next_instruction(cpu, memory, quirks);
         }
     }
 
-    fn skip_next_instruction_if_v_registers_values_are_the_same(&self, cpu: &mut Cpu) {
-        let first_register_number: usize = (self.opcode as usize) >> 8 & 0x000F;
-        let second_register_number: usize = (self.opcode as usize) >> 4 & 0x000F;
-
+    fn skip_next_instruction_if_v_registers_values_are_the_same(
+        &self,
+        cpu: &mut Cpu,
+        memory: &Memory,
+        quirks: &Quirks,
+        first_register_number: usize,
+        second_register_number: usize,
+    ) {
         let first_register_value: u8 = cpu.get_v_register(first_register_number);
         let second_register_value: u8 = cpu.get_v_register(second_register_number);
 
         if first_register_value == second_register_value {
-            cpu.increase_program_counter(0x2);
+            self.skip_next_instruction(cpu, memory, quirks);
+        }
+    }
+
+    fn put_values_of_v_registers_from_first_to_second_passed_register_in_memory_starting_on_i_register_location(
+        &self,
+        cpu: &mut Cpu,
+        memory: &mut Memory,
+        first_register_number: usize,
+        second_register_number: usize,
+    ) -> Result<(), EmulatorError> {
+        let memory_location: usize = cpu.get_i_register() as usize;
+
+        for (offset, register_number) in self.get_register_range(first_register_number, second_register_number).into_iter().enumerate() {
+            let register_value: u8 = cpu.get_v_register(register_number);
+
+            memory.set(memory_location + offset, register_value)?;
+        }
+
+        Ok(())
+    }
+
+    fn put_values_on_v_registers_from_first_to_second_passed_register_from_memory_starting_on_i_register_location(
+        &self,
+        cpu: &mut Cpu,
+        memory: &mut Memory,
+        first_register_number: usize,
+        second_register_number: usize,
+    ) -> Result<(), EmulatorError> {
+        let memory_location: usize = cpu.get_i_register() as usize;
+
+        for (offset, register_number) in self.get_register_range(first_register_number, second_register_number).into_iter().enumerate() {
+            let memory_value: u8 = memory.get(memory_location + offset)?;
+
+            cpu.set_v_register(register_number, memory_value);
         }
+
+        Ok(())
     }
 
-    fn put_argument_value_on_v_register(&self, cpu: &mut Cpu) {
-        let argument_value: u8 = (self.opcode as u8) & 0x00FF;
-        let register_number: usize = (self.opcode as usize) >> 8 & 0x000F;
+    // Vx to Vy, going backwards when y is lower than x.
+    fn get_register_range(&self, first_register_number: usize, second_register_number: usize) -> Vec<usize> {
+        match first_register_number <= second_register_number {
+            true => (first_register_number..=second_register_number).collect(),
+            false => (second_register_number..=first_register_number).rev().collect(),
+        }
+    }
 
+    fn put_argument_value_on_v_register(&self, cpu: &mut Cpu, register_number: usize, argument_value: u8) {
         cpu.set_v_register(register_number, argument_value);
     }
 
-    fn add_argument_value_to_v_register(&self, cpu: &mut Cpu) {
-        let argument_value: u8 = (self.opcode as u8) & 0x00FF;
-        let register_number: usize = (self.opcode as usize) >> 8 & 0x000F;
+    fn add_argument_value_to_v_register(&self, cpu: &mut Cpu, register_number: usize, argument_value: u8) {
         let register_value: u8 = cpu.get_v_register(register_number);
 
         cpu.set_v_register(
@@ -151,20 +268,20 @@ impl Instruction {
         );
     }
 
-    fn put_v_register_value_on_other_v_register(&self, cpu: &mut Cpu) {
-        let first_register_number: usize = (self.opcode as usize) >> 8 & 0x000F;
-        let second_register_number: usize = (self.opcode as usize) >> 4 & 0x000F;
-
+    fn put_v_register_value_on_other_v_register(&self, cpu: &mut Cpu, first_register_number: usize, second_register_number: usize) {
         cpu.set_v_register(
             first_register_number, 
             cpu.get_v_register(second_register_number)
         );
     }
 
-    fn put_value_of_bitwise_or_operation_between_v_registers_on_first_passed_register(&self, cpu: &mut Cpu) {
-        let first_register_number: usize = (self.opcode as usize) >> 8 & 0x000F;
-        let second_register_number: usize = (self.opcode as usize) >> 4 & 0x000F;
-
+    fn put_value_of_bitwise_or_operation_between_v_registers_on_first_passed_register(
+        &self,
+        cpu: &mut Cpu,
+        quirks: &Quirks,
+        first_register_number: usize,
+        second_register_number: usize,
+    ) {
         let first_register_value: u8 = cpu.get_v_register(first_register_number);
         let second_register_value: u8 = cpu.get_v_register(second_register_number);
 
@@ -172,12 +289,19 @@ impl Instruction {
             first_register_number, 
             first_register_value | second_register_value
         );
-    }
 
-    fn put_value_of_bitwise_and_operation_between_v_registers_on_first_passed_register(&self, cpu: &mut Cpu) {
-        let first_register_number: usize = (self.opcode as usize) >> 8 & 0x000F;
-        let second_register_number: usize = (self.opcode as usize) >> 4 & 0x000F;
+        if quirks.reset_vf_on_logic {
+            cpu.set_v_register(0xF, 0x0);
+        }
+    }
 
+    fn put_value_of_bitwise_and_operation_between_v_registers_on_first_passed_register(
+        &self,
+        cpu: &mut Cpu,
+        quirks: &Quirks,
+        first_register_number: usize,
+        second_register_number: usize,
+    ) {
         let first_register_value: u8 = cpu.get_v_register(first_register_number);
         let second_register_value: u8 = cpu.get_v_register(second_register_number);
 
@@ -185,12 +309,19 @@ impl Instruction {
             first_register_number, 
             first_register_value & second_register_value
         );
-    }
 
-    fn put_value_of_bitwise_xor_operation_between_v_registers_on_first_passed_register(&self, cpu: &mut Cpu) {
-        let first_register_number: usize = (self.opcode as usize) >> 8 & 0x000F;
-        let second_register_number: usize = (self.opcode as usize) >> 4 & 0x000F;
+        if quirks.reset_vf_on_logic {
+            cpu.set_v_register(0xF, 0x0);
+        }
+    }
 
+    fn put_value_of_bitwise_xor_operation_between_v_registers_on_first_passed_register(
+        &self,
+        cpu: &mut Cpu,
+        quirks: &Quirks,
+        first_register_number: usize,
+        second_register_number: usize,
+    ) {
         let first_register_value: u8 = cpu.get_v_register(first_register_number);
         let second_register_value: u8 = cpu.get_v_register(second_register_number);
 
@@ -198,12 +329,18 @@ impl Instruction {
             first_register_number, 
             first_register_value ^ second_register_value
         );
-    }
 
-    fn put_value_of_sum_operation_between_v_registers_on_first_passed_register(&self, cpu: &mut Cpu) {
-        let first_register_number: usize = (self.opcode as usize) >> 8 & 0x000F;
-        let second_register_number: usize = (self.opcode as usize) >> 4 & 0x000F;
+        if quirks.reset_vf_on_logic {
+            cpu.set_v_register(0xF, 0x0);
+        }
+    }
 
+    fn put_value_of_sum_operation_between_v_registers_on_first_passed_register(
+        &self,
+        cpu: &mut Cpu,
+        first_register_number: usize,
+        second_register_number: usize,
+    ) {
         let first_register_value: u8 = cpu.get_v_register(first_register_number);
         let second_register_value: u8 = cpu.get_v_register(second_register_number);
 
@@ -213,10 +350,12 @@ impl Instruction {
         cpu.set_v_register(first_register_number, value);
     }
 
-    fn put_value_of_subtraction_operation_between_v_registers_on_first_passed_register(&self, cpu: &mut Cpu) {
-        let first_register_number: usize = (self.opcode as usize) >> 8 & 0x000F;
-        let second_register_number: usize = (self.opcode as usize) >> 4 & 0x000F;
-
+    fn put_value_of_subtraction_operation_between_v_registers_on_first_passed_register(
+        &self,
+        cpu: &mut Cpu,
+        first_register_number: usize,
+        second_register_number: usize,
+    ) {
         let first_register_value: u8 = cpu.get_v_register(first_register_number);
         let second_register_value: u8 = cpu.get_v_register(second_register_number);
 
@@ -226,21 +365,28 @@ impl Instruction {
         cpu.set_v_register(first_register_number, value);
     }
 
-    fn put_value_of_bitwise_shift_right_operation_between_v_registers_on_first_passed_register(&self, cpu: &mut Cpu) {
-        let first_register_number: usize = (self.opcode as usize) >> 8 & 0x000F;
-
-        let first_register_value: u8 = cpu.get_v_register(first_register_number);
-
-        let value: u8 = first_register_value >> 1;
+    fn put_value_of_bitwise_shift_right_operation_between_v_registers_on_first_passed_register(
+        &self,
+        cpu: &mut Cpu,
+        quirks: &Quirks,
+        first_register_number: usize,
+        second_register_number: usize,
+    ) {
+        let shifted_register_value: u8 = match quirks.shift_vx_in_place {
+            true => cpu.get_v_register(first_register_number),
+            false => cpu.get_v_register(second_register_number),
+        };
 
-        cpu.set_v_register(0xF, value & 0x1);
-        cpu.set_v_register(first_register_number, value);
+        cpu.set_v_register(first_register_number, shifted_register_value >> 1);
+        cpu.set_v_register(0xF, shifted_register_value & 0x1);
     }
 
-    fn put_value_of_inverted_subtraction_operation_between_v_registers_on_first_passed_register(&self, cpu: &mut Cpu) {
-        let first_register_number: usize = (self.opcode as usize) >> 8 & 0x000F;
-        let second_register_number: usize = (self.opcode as usize) >> 4 & 0x000F;
-
+    fn put_value_of_inverted_subtraction_operation_between_v_registers_on_first_passed_register(
+        &self,
+        cpu: &mut Cpu,
+        first_register_number: usize,
+        second_register_number: usize,
+    ) {
         let first_register_value: u8 = cpu.get_v_register(first_register_number);
         let second_register_value: u8 = cpu.get_v_register(second_register_number);
 
@@ -250,157 +396,229 @@ impl Instruction {
         cpu.set_v_register(first_register_number, value);
     }
 
-    fn put_value_of_bitwise_shift_left_operation_between_v_registers_on_first_passed_register(&self, cpu: &mut Cpu) {
-        let first_register_number: usize = (self.opcode as usize) >> 8 & 0x000F;
-
-        let first_register_value: u8 = cpu.get_v_register(first_register_number);
-
-        let value: u8 = first_register_value << 1;
+    fn put_value_of_bitwise_shift_left_operation_between_v_registers_on_first_passed_register(
+        &self,
+        cpu: &mut Cpu,
+        quirks: &Quirks,
+        first_register_number: usize,
+        second_register_number: usize,
+    ) {
+        let shifted_register_value: u8 = match quirks.shift_vx_in_place {
+            true => cpu.get_v_register(first_register_number),
+            false => cpu.get_v_register(second_register_number),
+        };
 
-        cpu.set_v_register(0xF, (value >> 7) & 0x1);
-        cpu.set_v_register(first_register_number, value);
+        cpu.set_v_register(first_register_number, shifted_register_value << 1);
+        cpu.set_v_register(0xF, (shifted_register_value >> 7) & 0x1);
     }
 
-    fn skip_next_instruction_if_v_registers_values_are_different(&self, cpu: &mut Cpu) {
-        let first_register_number: usize = (self.opcode as usize) >> 8 & 0x000F;
-        let second_register_number: usize = (self.opcode as usize) >> 4 & 0x000F;
-
+    fn skip_next_instruction_if_v_registers_values_are_different(
+        &self,
+        cpu: &mut Cpu,
+        memory: &Memory,
+        quirks: &Quirks,
+        first_register_number: usize,
+        second_register_number: usize,
+    ) {
         let first_register_value: u8 = cpu.get_v_register(first_register_number);
         let second_register_value: u8 = cpu.get_v_register(second_register_number);
 
         if first_register_value != second_register_value {
-            cpu.increase_program_counter(0x2);
+            self.skip_next_instruction(cpu, memory, quirks);
         }
     }
 
-    fn put_argument_value_on_i_register(&self, cpu: &mut Cpu) {
-        let argument: u16 = self.opcode & 0xFFF;
-
+    fn put_argument_value_on_i_register(&self, cpu: &mut Cpu, argument: u16) {
         cpu.set_i_register(argument);
     }
 
-    fn jump_to_argument_value_plus_v0(&self, cpu: &mut Cpu) {
-        let argument: u16 = self.opcode & 0xFFF;
-        let address: usize = (argument as usize) + (cpu.get_v_register(0x0) as usize);
+    fn jump_to_argument_value_plus_v_register(&self, cpu: &mut Cpu, quirks: &Quirks, register_number: usize, argument: u16) {
+        let register_number: usize = match quirks.jump_with_vx {
+            true => register_number,
+            false => 0x0,
+        };
+        let address: usize = (argument as usize) + (cpu.get_v_register(register_number) as usize);
 
         cpu.set_program_counter(address);
     }
 
-    fn put_value_of_bitwise_or_operation_between_argument_and_random_byte_on_passed_v_register(&self, cpu: &mut Cpu) {
-        let register_number: usize = ((self.opcode as usize) >> 8) & 0xF;
-        let argument: u8 = (self.opcode as u8) & 0xFF;
-        let random_byte: u8 = rand::thread_rng().gen_range(0x0..0xFF);
+    fn put_value_of_bitwise_and_operation_between_argument_and_random_byte_on_passed_v_register(
+        &self,
+        cpu: &mut Cpu,
+        register_number: usize,
+        argument: u8,
+    ) {
+        let random_byte: u8 = cpu.next_random_byte();
 
         cpu.set_v_register(
             register_number,
-            argument | random_byte
+            argument & random_byte
         );
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn draw_byte_sprite_starting_at_location_i_on_register_stored_location(
         &self, 
         cpu: &mut Cpu,
         memory: &mut Memory,
-        interface: &mut Interface,
-    ) {
+        framebuffer: &mut Framebuffer,
+        quirks: &Quirks,
+        first_register_number: usize,
+        second_register_number: usize,
+        number_of_sprite_bytes: u8,
+    ) -> Result<(), EmulatorError> {
         cpu.set_v_register(0xF, 0x0);
 
-        let first_register_number: usize = (self.opcode as usize) >> 8 & 0x000F;
-        let second_register_number: usize = (self.opcode as usize) >> 4 & 0x000F;
-        let number_of_sprite_bytes: u8 = (self.opcode as u8) & 0x000F;
+        // Dxy0 draws a 16x16 sprite, with two bytes on each row.
+        let (sprite_width, sprite_height): (usize, usize) = match number_of_sprite_bytes {
+            0x0 => (16, 16),
+            _ => (8, number_of_sprite_bytes as usize),
+        };
 
         let mut memory_position: usize = cpu.get_i_register() as usize;
 
-        let initial_width_position: usize = (cpu.get_v_register(first_register_number) as usize) % Interface::WIDTH;
-        let initial_height_position: usize = (cpu.get_v_register(second_register_number) as usize) % Interface::HEIGHT;
-        
-        let mut current_width_position: usize = initial_width_position;
-        let mut current_height_position: usize = initial_height_position;
+        let width: usize = framebuffer.get_width();
+        let height: usize = framebuffer.get_height();
 
-        for _ in 0..number_of_sprite_bytes {
-            let byte: u8 = memory.get(memory_position);
+        let initial_width_position: usize = (cpu.get_v_register(first_register_number) as usize) % width;
+        let initial_height_position: usize = (cpu.get_v_register(second_register_number) as usize) % height;
 
-            for shift_size in (0..8).rev() {
-                if current_width_position >= Interface::WIDTH || current_height_position >= Interface::HEIGHT {
-                    continue;
-                }
+        // On XO-CHIP, each selected plane takes its own copy of the sprite,
+        // one after the other in memory.
+        for plane in 0..Framebuffer::PLANES {
+            if !framebuffer.is_plane_selected(plane) {
+                continue;
+            }
 
-                let has_flag = interface.draw_pixel(
-                    (byte >> shift_size & 0b1) == 0b1, 
-                    current_width_position, 
-                    current_height_position
-                );
+            for row in 0..sprite_height {
+                let mut bits: u16 = u16::from(memory.get(memory_position)?) << 8;
 
-                current_width_position += 1;
+                if sprite_width == 16 {
+                    bits |= u16::from(memory.get(memory_position + 1)?);
+                }
 
-                if has_flag {
-                    cpu.set_v_register(0xF, 0x1);
+                for column in 0..sprite_width {
+                    let mut current_width_position: usize = initial_width_position + column;
+                    let mut current_height_position: usize = initial_height_position + row;
+
+                    if quirks.wrap_sprites {
+                        current_width_position %= width;
+                        current_height_position %= height;
+                    }
+
+                    if current_width_position >= width || current_height_position >= height {
+                        continue;
+                    }
+
+                    let has_flag = framebuffer.draw_plane_pixel(
+                        plane,
+                        (bits >> (15 - column) & 0b1) == 0b1, 
+                        current_width_position, 
+                        current_height_position
+                    );
+
+                    if has_flag {
+                        cpu.set_v_register(0xF, 0x1);
+                    }
                 }
-            }
 
-            memory_position += 1;
-            current_height_position += 1;
-            current_width_position = initial_width_position;
+                memory_position += sprite_width / 8;
+            }
         }
 
-        interface.refresh();
+        Ok(())
     }
 
-    fn skip_next_instruction_if_key_with_v_register_value_is_pressed(&self, interface: &mut Interface, cpu: &mut Cpu) {
-        let register_number: usize = (self.opcode as usize) >> 8 & 0xF;
+    fn skip_next_instruction_if_key_with_v_register_value_is_pressed(
+        &self,
+        keypad: &Keypad,
+        cpu: &mut Cpu,
+        memory: &Memory,
+        quirks: &Quirks,
+        register_number: usize,
+    ) {
         let key_index: usize = cpu.get_v_register(register_number) as usize;
 
-        if interface.is_pressed(key_index) {
-            cpu.increase_program_counter(2);
+        if keypad.is_pressed(key_index) {
+            self.skip_next_instruction(cpu, memory, quirks);
         }
     }
 
-    fn skip_next_instruction_if_key_with_v_register_value_is_not_pressed(&self, interface: &mut Interface, cpu: &mut Cpu) {
-        let register_number: usize = (self.opcode as usize) >> 8 & 0xF;
+    fn skip_next_instruction_if_key_with_v_register_value_is_not_pressed(
+        &self,
+        keypad: &Keypad,
+        cpu: &mut Cpu,
+        memory: &Memory,
+        quirks: &Quirks,
+        register_number: usize,
+    ) {
         let key_index: usize = cpu.get_v_register(register_number) as usize;
 
-        if interface.is_not_pressed(key_index) {
-            cpu.increase_program_counter(2);
+        if keypad.is_not_pressed(key_index) {
+            self.skip_next_instruction(cpu, memory, quirks);
         }
     }
 
-    fn put_delay_timer_value_on_v_register(&self, cpu: &mut Cpu) {
-        let register_number: usize = (self.opcode as usize) >> 8 & 0xF;
+    fn put_next_word_on_i_register(&self, cpu: &mut Cpu, memory: &Memory) -> Result<(), EmulatorError> {
+        let program_counter: usize = cpu.get_program_counter();
+
+        let first_byte: u8 = memory.get(program_counter)?;
+        let second_byte: u8 = memory.get(program_counter + 1)?;
+
+        cpu.set_i_register(u16::from(first_byte) << 8 | u16::from(second_byte));
+        cpu.increase_program_counter(0x2);
+
+        Ok(())
+    }
+
+    fn select_drawing_planes(&self, framebuffer: &mut Framebuffer, planes: u8) {
+        framebuffer.select_planes(planes);
+    }
+
+    fn put_audio_pattern_from_memory_starting_on_i_register_location(
+        &self,
+        cpu: &mut Cpu,
+        memory: &Memory,
+    ) -> Result<(), EmulatorError> {
+        let memory_location: usize = cpu.get_i_register() as usize;
+        let mut pattern: [u8; Cpu::AUDIO_PATTERN_SIZE] = [0x0; Cpu::AUDIO_PATTERN_SIZE];
+
+        for (index, byte) in pattern.iter_mut().enumerate() {
+            *byte = memory.get(memory_location + index)?;
+        }
 
+        cpu.set_audio_pattern(pattern);
+
+        Ok(())
+    }
+
+    fn put_delay_timer_value_on_v_register(&self, cpu: &mut Cpu, register_number: usize) {
         cpu.set_v_register(
             register_number, 
             cpu.get_delay_timer()
         );
     }
 
-    fn wait_for_key_press_and_store_value_on_v_register(&self, cpu: &mut Cpu, interface: &mut Interface) {
-        let register_number: usize = (self.opcode as usize) >> 8 & 0xF;
-
-        match interface.get_pressed_key() {
+    fn wait_for_key_press_and_store_value_on_v_register(&self, cpu: &mut Cpu, keypad: &Keypad, register_number: usize) {
+        match keypad.get_pressed_key() {
             Some(key) => cpu.set_v_register(register_number, key as u8),
             None => cpu.decrease_program_counter(2),
         }
     }
 
-    fn put_v_register_value_on_delay_timer(&self, cpu: &mut Cpu) {
-        let register_number: usize = (self.opcode as usize) >> 8 & 0xF;
-
+    fn put_v_register_value_on_delay_timer(&self, cpu: &mut Cpu, register_number: usize) {
         cpu.set_delay_timer(
             cpu.get_v_register(register_number)
         );
     }
 
-    fn put_v_register_value_on_sound_timer(&self, cpu: &mut Cpu) {
-        let register_number: usize = (self.opcode as usize) >> 8 & 0xF;
-
+    fn put_v_register_value_on_sound_timer(&self, cpu: &mut Cpu, register_number: usize) {
         cpu.set_sound_timer(
             cpu.get_v_register(register_number)
         );
     }
 
-    fn add_v_register_value_to_i_register_value(&self, cpu: &mut Cpu) {
-        let register_number: usize = (self.opcode as usize) >> 8 & 0xF;
-
+    fn add_v_register_value_to_i_register_value(&self, cpu: &mut Cpu, register_number: usize) {
         let register_value: u16 = cpu.get_v_register(register_number) as u16;
 
         cpu.set_i_register(
@@ -408,21 +626,32 @@ impl Instruction {
         );
     }
 
-    fn put_location_of_sprite_for_v_register_digit_on_i_register(&self, cpu: &mut Cpu) {
-        let register_number: u16 = self.opcode >> 8 & 0xF;
+    fn put_location_of_sprite_for_v_register_digit_on_i_register(&self, cpu: &mut Cpu, register_number: usize) {
+        let digit: u16 = u16::from(cpu.get_v_register(register_number) & 0xF);
+
+        cpu.set_i_register(
+            (Memory::FONT_START as u16) + (digit * 5)
+        );
+    }
+
+    fn put_location_of_big_sprite_for_v_register_digit_on_i_register(&self, cpu: &mut Cpu, register_number: usize) {
+        let digit: u16 = u16::from(cpu.get_v_register(register_number) & 0xF);
 
         cpu.set_i_register(
-            0x50 + (register_number * 5)
+            (Memory::BIG_FONT_START as u16) + (digit * 10)
         );
     }
 
+    fn put_v_register_value_on_pitch(&self, cpu: &mut Cpu, register_number: usize) {
+        cpu.set_pitch(cpu.get_v_register(register_number));
+    }
+
     fn put_bcd_representation_of_v_register_in_memory_locations_starting_on_i_register_location(
         &self, 
         cpu: &mut Cpu,
         memory: &mut Memory,
-    ) {
-        let register_number: usize = (self.opcode as usize) >> 8 & 0xF;
-
+        register_number: usize,
+    ) -> Result<(), EmulatorError> {
         let register_value: u8 = cpu.get_v_register(register_number);
 
         let mut memory_location: usize = cpu.get_i_register() as usize;
@@ -433,66 +662,114 @@ impl Instruction {
 
         memory.set(
             memory_location, 
-            hundreds as u8
-        );
+            hundreds
+        )?;
 
         memory_location += 1;
 
         memory.set(
             memory_location, 
-            tens as u8
-        );
+            tens
+        )?;
 
         memory_location += 1;
 
         memory.set(
             memory_location, 
-            ones as u8
-        );
+            ones
+        )
     }
 
     fn put_values_of_v_registers_from_v0_to_passed_v_register_in_memory_starting_on_i_register_location(
         &self,
         cpu: &mut Cpu,
         memory: &mut Memory,
-    ) {
-        let final_register_number: usize = (self.opcode as usize) >> 8 & 0xF;
-
-        let mut memory_location: usize = cpu.get_i_register() as usize;
+        quirks: &Quirks,
+        final_register_number: usize,
+    ) -> Result<(), EmulatorError> {
+        let memory_location: usize = cpu.get_i_register() as usize;
 
         for register_number in 0..=final_register_number {
             let register_value: u8 = cpu.get_v_register(register_number);
 
-            memory.set(memory_location, register_value);
-
-            memory_location += 1;
+            memory.set(memory_location + register_number, register_value)?;
         }
+
+        self.increase_i_register_after_load_or_store(cpu, final_register_number, quirks);
+
+        Ok(())
     }
 
     fn put_values_on_v_registers_from_v0_to_passed_v_register_from_memory_starting_on_i_register_location(
         &self,
         cpu: &mut Cpu,
         memory: &mut Memory,
-    ) {
-        let final_register_number: usize = (self.opcode as usize) >> 8 & 0xF;
-
-        let mut memory_location: usize = cpu.get_i_register() as usize;
+        quirks: &Quirks,
+        final_register_number: usize,
+    ) -> Result<(), EmulatorError> {
+        let memory_location: usize = cpu.get_i_register() as usize;
 
         for register_number in 0..=final_register_number {
-            let memory_value: u8 = memory.get(memory_location);
+            let memory_value: u8 = memory.get(memory_location + register_number)?;
 
             cpu.set_v_register(register_number, memory_value);
+        }
+
+        self.increase_i_register_after_load_or_store(cpu, final_register_number, quirks);
+
+        Ok(())
+    }
+
+    fn put_values_of_v_registers_from_v0_to_passed_v_register_in_rpl_flags(
+        &self,
+        cpu: &mut Cpu,
+        quirks: &Quirks,
+        final_register_number: usize,
+    ) -> Result<(), EmulatorError> {
+        if final_register_number >= quirks.rpl_flags {
+            return Err(self.unknown_opcode(cpu));
+        }
 
-            memory_location += 1;
+        for register_number in 0..=final_register_number {
+            cpu.set_rpl_flag(register_number, cpu.get_v_register(register_number));
+        }
+
+        Ok(())
+    }
+
+    fn put_values_on_v_registers_from_v0_to_passed_v_register_from_rpl_flags(
+        &self,
+        cpu: &mut Cpu,
+        quirks: &Quirks,
+        final_register_number: usize,
+    ) -> Result<(), EmulatorError> {
+        if final_register_number >= quirks.rpl_flags {
+            return Err(self.unknown_opcode(cpu));
+        }
+
+        for register_number in 0..=final_register_number {
+            cpu.set_v_register(register_number, cpu.get_rpl_flag(register_number));
         }
+
+        Ok(())
+    }
+
+    fn increase_i_register_after_load_or_store(&self, cpu: &mut Cpu, final_register_number: usize, quirks: &Quirks) {
+        let increment: u16 = match quirks.load_store_increment {
+            IndexIncrement::Unchanged => return,
+            IndexIncrement::ByX => final_register_number as u16,
+            IndexIncrement::ByXPlusOne => (final_register_number as u16) + 1,
+        };
+
+        cpu.set_i_register(cpu.get_i_register().wrapping_add(increment));
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use mockall::predicate::eq;
     use test_case::test_case;
     use super::*;
+    use crate::random::Random;
 
     #[test]
     fn it_should_initialize_the_instruction() {
@@ -506,15 +783,103 @@ mod tests {
         let mut instruction: Instruction = Instruction::initialize(0x00, 0xE0);
         let mut cpu: Cpu = Cpu::initialize();
         let mut memory: Memory = Memory::initialize();
-        let mut interface= Interface::default();
+        let mut framebuffer: Framebuffer = Framebuffer::initialize();
+        let keypad: Keypad = Keypad::initialize();
+        let quirks: Quirks = Quirks::DEFAULT;
+
+        framebuffer.draw_pixel(true, 0x3, 0x1);
+
+        instruction.interpret(&mut cpu, &mut memory, &mut framebuffer, &keypad, &quirks).unwrap();
+
+        assert!(!framebuffer.get_pixel(0x3, 0x1));
+    }
+
+    #[test]
+    fn it_should_clear_only_the_selected_planes() {
+        let mut instruction: Instruction = Instruction::initialize(0x00, 0xE0);
+        let mut cpu: Cpu = Cpu::initialize();
+        let mut memory: Memory = Memory::initialize();
+        let mut framebuffer: Framebuffer = Framebuffer::initialize();
+        let keypad: Keypad = Keypad::initialize();
+        let quirks: Quirks = Quirks::XO_CHIP;
 
-        interface.expect_clear()
-            .returning(|| ());
+        framebuffer.draw_plane_pixel(0, true, 0x3, 0x1);
+        framebuffer.draw_plane_pixel(1, true, 0x3, 0x1);
+        framebuffer.select_planes(0b0010);
 
-        interface.expect_refresh()
-            .returning(|| ());
+        instruction.interpret(&mut cpu, &mut memory, &mut framebuffer, &keypad, &quirks).unwrap();
 
-        instruction.interpret(&mut cpu, &mut memory, &mut interface);
+        assert!(framebuffer.get_plane_pixels(0)[0x3 + 64]);
+        assert!(!framebuffer.get_plane_pixels(1)[0x3 + 64]);
+    }
+
+    #[test]
+    fn it_should_scroll_the_display_down() {
+        let mut instruction: Instruction = Instruction::initialize(0x00, 0xC3);
+        let mut cpu: Cpu = Cpu::initialize();
+        let mut memory: Memory = Memory::initialize();
+        let mut framebuffer: Framebuffer = Framebuffer::initialize();
+        let keypad: Keypad = Keypad::initialize();
+        let quirks: Quirks = Quirks::DEFAULT;
+
+        framebuffer.draw_pixel(true, 0x3, 0x1);
+
+        instruction.interpret(&mut cpu, &mut memory, &mut framebuffer, &keypad, &quirks).unwrap();
+
+        assert!(!framebuffer.get_pixel(0x3, 0x1));
+        assert!(framebuffer.get_pixel(0x3, 0x4));
+    }
+
+    #[test_case(0xFB, 0x7 ; "with scroll right")]
+    #[test_case(0xFC, 0x1 ; "with scroll left")]
+    fn it_should_scroll_the_display_sideways(second_byte: u8, position: usize) {
+        let mut instruction: Instruction = Instruction::initialize(0x00, second_byte);
+        let mut cpu: Cpu = Cpu::initialize();
+        let mut memory: Memory = Memory::initialize();
+        let mut framebuffer: Framebuffer = Framebuffer::initialize();
+        let keypad: Keypad = Keypad::initialize();
+        let quirks: Quirks = Quirks::DEFAULT;
+
+        framebuffer.draw_pixel(true, 0x3, 0x1);
+        framebuffer.draw_pixel(true, 0x5, 0x1);
+
+        instruction.interpret(&mut cpu, &mut memory, &mut framebuffer, &keypad, &quirks).unwrap();
+
+        assert!(framebuffer.get_pixel(position, 0x1));
+        assert!(!framebuffer.get_pixel(0x3, 0x1));
+    }
+
+    #[test]
+    fn it_should_exit_the_interpreter() {
+        let mut instruction: Instruction = Instruction::initialize(0x00, 0xFD);
+        let mut cpu: Cpu = Cpu::initialize();
+        let mut memory: Memory = Memory::initialize();
+        let mut framebuffer: Framebuffer = Framebuffer::initialize();
+        let keypad: Keypad = Keypad::initialize();
+        let quirks: Quirks = Quirks::DEFAULT;
+
+        instruction.interpret(&mut cpu, &mut memory, &mut framebuffer, &keypad, &quirks).unwrap();
+
+        assert!(cpu.is_halted());
+    }
+
+    #[test_case(0xFF, true, 128, 64 ; "with high resolution enabled")]
+    #[test_case(0xFE, false, 64, 32 ; "with high resolution disabled")]
+    fn it_should_switch_the_display_resolution(second_byte: u8, high_resolution: bool, width: usize, height: usize) {
+        let mut instruction: Instruction = Instruction::initialize(0x00, second_byte);
+        let mut cpu: Cpu = Cpu::initialize();
+        let mut memory: Memory = Memory::initialize();
+        let mut framebuffer: Framebuffer = Framebuffer::initialize();
+        let keypad: Keypad = Keypad::initialize();
+        let quirks: Quirks = Quirks::DEFAULT;
+
+        framebuffer.set_high_resolution(!high_resolution);
+
+        instruction.interpret(&mut cpu, &mut memory, &mut framebuffer, &keypad, &quirks).unwrap();
+
+        assert_eq!(high_resolution, framebuffer.is_high_resolution());
+        assert_eq!(width, framebuffer.get_width());
+        assert_eq!(height, framebuffer.get_height());
     }
 
     #[test]
@@ -522,25 +887,67 @@ mod tests {
         let mut instruction: Instruction = Instruction::initialize(0x00, 0xEE);
         let mut cpu: Cpu = Cpu::initialize();
         let mut memory: Memory = Memory::initialize();
-        let mut interface= Interface::default();
+        let mut framebuffer: Framebuffer = Framebuffer::initialize();
+        let keypad: Keypad = Keypad::initialize();
+        let quirks: Quirks = Quirks::DEFAULT;
 
-        cpu.stack_push(0xCAF);
+        cpu.stack_push(0xCAF).unwrap();
 
         assert_eq!(0x200, cpu.get_program_counter());
 
-        instruction.interpret(&mut cpu, &mut memory, &mut interface);
+        instruction.interpret(&mut cpu, &mut memory, &mut framebuffer, &keypad, &quirks).unwrap();
 
         assert_eq!(0xCAF, cpu.get_program_counter());
     }
 
+    #[test]
+    fn it_should_fail_to_return_from_subroutine_with_an_empty_stack() {
+        let mut instruction: Instruction = Instruction::initialize(0x00, 0xEE);
+        let mut cpu: Cpu = Cpu::initialize();
+        let mut memory: Memory = Memory::initialize();
+        let mut framebuffer: Framebuffer = Framebuffer::initialize();
+        let keypad: Keypad = Keypad::initialize();
+        let quirks: Quirks = Quirks::DEFAULT;
+
+        assert_eq!(
+            Err(EmulatorError::StackUnderflow),
+            instruction.interpret(&mut cpu, &mut memory, &mut framebuffer, &keypad, &quirks)
+        );
+    }
+
+    #[test_case(0x01, 0x23 ; "with a machine code routine")]
+    #[test_case(0x80, 0x08 ; "with an unknown arithmetic operation")]
+    #[test_case(0xE1, 0x00 ; "with an unknown key operation")]
+    #[test_case(0xF1, 0x00 ; "with an unknown miscellaneous operation")]
+    fn it_should_fail_to_interpret_an_unknown_opcode(first_byte: u8, second_byte: u8) {
+        let mut instruction: Instruction = Instruction::initialize(first_byte, second_byte);
+        let mut cpu: Cpu = Cpu::initialize();
+        let mut memory: Memory = Memory::initialize();
+        let mut framebuffer: Framebuffer = Framebuffer::initialize();
+        let keypad: Keypad = Keypad::initialize();
+        let quirks: Quirks = Quirks::DEFAULT;
+
+        cpu.set_program_counter(0x2AE);
+
+        assert_eq!(
+            Err(EmulatorError::UnknownOpcode {
+                opcode: u16::from(first_byte) << 8 | u16::from(second_byte),
+                program_counter: 0x2AC,
+            }),
+            instruction.interpret(&mut cpu, &mut memory, &mut framebuffer, &keypad, &quirks)
+        );
+    }
+
     #[test]
     fn it_should_jump_to_address() {
         let mut instruction: Instruction = Instruction::initialize(0x1C, 0xAF);
         let mut cpu: Cpu = Cpu::initialize();
         let mut memory: Memory = Memory::initialize();
-        let mut interface= Interface::default();
+        let mut framebuffer: Framebuffer = Framebuffer::initialize();
+        let keypad: Keypad = Keypad::initialize();
+        let quirks: Quirks = Quirks::DEFAULT;
 
-        instruction.interpret(&mut cpu, &mut memory, &mut interface);
+        instruction.interpret(&mut cpu, &mut memory, &mut framebuffer, &keypad, &quirks).unwrap();
 
         assert_eq!(0xCAF, cpu.get_program_counter());
     }
@@ -550,12 +957,14 @@ mod tests {
         let mut instruction: Instruction = Instruction::initialize(0x2C, 0xAF);
         let mut cpu: Cpu = Cpu::initialize();
         let mut memory: Memory = Memory::initialize();
-        let mut interface= Interface::default();
+        let mut framebuffer: Framebuffer = Framebuffer::initialize();
+        let keypad: Keypad = Keypad::initialize();
+        let quirks: Quirks = Quirks::DEFAULT;
 
-        instruction.interpret(&mut cpu, &mut memory, &mut interface);
+        instruction.interpret(&mut cpu, &mut memory, &mut framebuffer, &keypad, &quirks).unwrap();
 
         assert_eq!(0xCAF, cpu.get_program_counter());
-        assert_eq!(0x200, cpu.stack_pop());
+        assert_eq!(Ok(0x200), cpu.stack_pop());
     }
 
     #[test_case(0xFF, 0xAA, 0x200 ; "with different values")]
@@ -568,11 +977,13 @@ mod tests {
         let mut instruction: Instruction = Instruction::initialize(0x3A, instruction_value);
         let mut cpu: Cpu = Cpu::initialize();
         let mut memory: Memory = Memory::initialize();
-        let mut interface= Interface::default();
+        let mut framebuffer: Framebuffer = Framebuffer::initialize();
+        let keypad: Keypad = Keypad::initialize();
+        let quirks: Quirks = Quirks::DEFAULT;
 
         cpu.set_v_register(0xA, register_value);
 
-        instruction.interpret(&mut cpu, &mut memory, &mut interface);
+        instruction.interpret(&mut cpu, &mut memory, &mut framebuffer, &keypad, &quirks).unwrap();
 
         assert_eq!(program_counter, cpu.get_program_counter());
     }
@@ -587,11 +998,13 @@ mod tests {
         let mut instruction: Instruction = Instruction::initialize(0x4A, instruction_value);
         let mut cpu: Cpu = Cpu::initialize();
         let mut memory: Memory = Memory::initialize();
-        let mut interface= Interface::default();
+        let mut framebuffer: Framebuffer = Framebuffer::initialize();
+        let keypad: Keypad = Keypad::initialize();
+        let quirks: Quirks = Quirks::DEFAULT;
 
         cpu.set_v_register(0xA, register_value);
 
-        instruction.interpret(&mut cpu, &mut memory, &mut interface);
+        instruction.interpret(&mut cpu, &mut memory, &mut framebuffer, &keypad, &quirks).unwrap();
 
         assert_eq!(program_counter, cpu.get_program_counter());
     }
@@ -606,14 +1019,125 @@ mod tests {
         let mut instruction: Instruction = Instruction::initialize(0x5A, 0xB0);
         let mut cpu: Cpu = Cpu::initialize();
         let mut memory: Memory = Memory::initialize();
-        let mut interface= Interface::default();
+        let mut framebuffer: Framebuffer = Framebuffer::initialize();
+        let keypad: Keypad = Keypad::initialize();
+        let quirks: Quirks = Quirks::DEFAULT;
+
+        cpu.set_v_register(0xA, first_register_value);
+        cpu.set_v_register(0xB, second_register_value);
+
+        instruction.interpret(&mut cpu, &mut memory, &mut framebuffer, &keypad, &quirks).unwrap();
+
+        assert_eq!(program_counter, cpu.get_program_counter());
+    }
+
+    #[test_case(0x5A, 0xD2, 0x1A, 0x1B, 0x1C, 0x1D ; "with an ascending range")]
+    #[test_case(0x5D, 0xA2, 0x1D, 0x1C, 0x1B, 0x1A ; "with a descending range")]
+    fn it_should_put_values_of_v_registers_from_first_to_second_passed_register_in_memory_starting_on_i_register_location(
+        first_byte: u8,
+        second_byte: u8,
+        first_value: u8,
+        second_value: u8,
+        third_value: u8,
+        fourth_value: u8,
+    ) {
+        let mut instruction: Instruction = Instruction::initialize(first_byte, second_byte);
+        let mut cpu: Cpu = Cpu::initialize();
+        let mut memory: Memory = Memory::initialize();
+        let mut framebuffer: Framebuffer = Framebuffer::initialize();
+        let keypad: Keypad = Keypad::initialize();
+        let quirks: Quirks = Quirks::XO_CHIP;
+
+        cpu.set_v_register(0x9, 0x19);
+        cpu.set_v_register(0xA, 0x1A);
+        cpu.set_v_register(0xB, 0x1B);
+        cpu.set_v_register(0xC, 0x1C);
+        cpu.set_v_register(0xD, 0x1D);
+        cpu.set_v_register(0xE, 0x1E);
+
+        cpu.set_i_register(0x300);
+
+        instruction.interpret(&mut cpu, &mut memory, &mut framebuffer, &keypad, &quirks).unwrap();
+
+        assert_eq!(Ok(first_value), memory.get(0x300));
+        assert_eq!(Ok(second_value), memory.get(0x301));
+        assert_eq!(Ok(third_value), memory.get(0x302));
+        assert_eq!(Ok(fourth_value), memory.get(0x303));
+        assert_eq!(Ok(0x0), memory.get(0x304));
+        assert_eq!(0x300, cpu.get_i_register());
+    }
+
+    #[test]
+    fn it_should_fail_to_put_values_of_v_registers_range_in_memory_out_of_range() {
+        let mut instruction: Instruction = Instruction::initialize(0x50, 0x32);
+        let mut cpu: Cpu = Cpu::initialize();
+        let mut memory: Memory = Memory::initialize();
+        let mut framebuffer: Framebuffer = Framebuffer::initialize();
+        let keypad: Keypad = Keypad::initialize();
+        let quirks: Quirks = Quirks { xo_chip: true, ..Quirks::DEFAULT };
+
+        memory.set_extended(false);
+        cpu.set_i_register(0xFFE);
+
+        assert_eq!(
+            Err(EmulatorError::MemoryFault { address: 0x1000 }),
+            instruction.interpret(&mut cpu, &mut memory, &mut framebuffer, &keypad, &quirks)
+        );
+    }
+
+    #[test_case(0x5A, 0xD3, 0x1A, 0x1B, 0x1C, 0x1D ; "with an ascending range")]
+    #[test_case(0x5D, 0xA3, 0x1D, 0x1C, 0x1B, 0x1A ; "with a descending range")]
+    fn it_should_put_values_on_v_registers_from_first_to_second_passed_register_from_memory_starting_on_i_register_location(
+        first_byte: u8,
+        second_byte: u8,
+        first_value: u8,
+        second_value: u8,
+        third_value: u8,
+        fourth_value: u8,
+    ) {
+        let mut instruction: Instruction = Instruction::initialize(first_byte, second_byte);
+        let mut cpu: Cpu = Cpu::initialize();
+        let mut memory: Memory = Memory::initialize();
+        let mut framebuffer: Framebuffer = Framebuffer::initialize();
+        let keypad: Keypad = Keypad::initialize();
+        let quirks: Quirks = Quirks::XO_CHIP;
+
+        memory.set(0x300, first_value).unwrap();
+        memory.set(0x301, second_value).unwrap();
+        memory.set(0x302, third_value).unwrap();
+        memory.set(0x303, fourth_value).unwrap();
+        memory.set(0x304, 0xFF).unwrap();
+
+        cpu.set_i_register(0x300);
+
+        instruction.interpret(&mut cpu, &mut memory, &mut framebuffer, &keypad, &quirks).unwrap();
+
+        assert_eq!(0x0, cpu.get_v_register(0x9));
+        assert_eq!(0x1A, cpu.get_v_register(0xA));
+        assert_eq!(0x1B, cpu.get_v_register(0xB));
+        assert_eq!(0x1C, cpu.get_v_register(0xC));
+        assert_eq!(0x1D, cpu.get_v_register(0xD));
+        assert_eq!(0x0, cpu.get_v_register(0xE));
+        assert_eq!(0x300, cpu.get_i_register());
+    }
+
+    #[test_case(0x22 ; "with save")]
+    #[test_case(0x23 ; "with load")]
+    #[test_case(0x21 ; "with an unknown operation")]
+    fn it_should_fail_to_interpret_register_range_operations_without_the_xo_chip_extensions(second_byte: u8) {
+        let mut instruction: Instruction = Instruction::initialize(0x5A, second_byte);
+        let mut cpu: Cpu = Cpu::initialize();
+        let mut memory: Memory = Memory::initialize();
+        let mut framebuffer: Framebuffer = Framebuffer::initialize();
+        let keypad: Keypad = Keypad::initialize();
+        let quirks: Quirks = Quirks::DEFAULT;
 
-        cpu.set_v_register(0xA, first_register_value);
-        cpu.set_v_register(0xB, second_register_value);
-
-        instruction.interpret(&mut cpu, &mut memory, &mut interface);
+        cpu.set_program_counter(0x202);
 
-        assert_eq!(program_counter, cpu.get_program_counter());
+        assert_eq!(
+            Err(EmulatorError::UnknownOpcode { opcode: 0x5A00 | u16::from(second_byte), program_counter: 0x200 }),
+            instruction.interpret(&mut cpu, &mut memory, &mut framebuffer, &keypad, &quirks)
+        );
     }
 
     #[test]
@@ -621,11 +1145,13 @@ mod tests {
         let mut instruction: Instruction = Instruction::initialize(0x6A, 0xCA);
         let mut cpu: Cpu = Cpu::initialize();
         let mut memory: Memory = Memory::initialize();
-        let mut interface= Interface::default();
+        let mut framebuffer: Framebuffer = Framebuffer::initialize();
+        let keypad: Keypad = Keypad::initialize();
+        let quirks: Quirks = Quirks::DEFAULT;
 
         assert_eq!(0x00, cpu.get_v_register(0xA));
 
-        instruction.interpret(&mut cpu, &mut memory, &mut interface);
+        instruction.interpret(&mut cpu, &mut memory, &mut framebuffer, &keypad, &quirks).unwrap();
 
         assert_eq!(0xCA, cpu.get_v_register(0xA));
     }
@@ -640,11 +1166,13 @@ mod tests {
         let mut instruction: Instruction = Instruction::initialize(0x7A, argument_value);
         let mut cpu: Cpu = Cpu::initialize();
         let mut memory: Memory = Memory::initialize();
-        let mut interface= Interface::default();
+        let mut framebuffer: Framebuffer = Framebuffer::initialize();
+        let keypad: Keypad = Keypad::initialize();
+        let quirks: Quirks = Quirks::DEFAULT;
 
         cpu.set_v_register(0xA, register_value);
 
-        instruction.interpret(&mut cpu, &mut memory, &mut interface);
+        instruction.interpret(&mut cpu, &mut memory, &mut framebuffer, &keypad, &quirks).unwrap();
 
         assert_eq!(result, cpu.get_v_register(0xA));
     }
@@ -654,13 +1182,15 @@ mod tests {
         let mut instruction: Instruction = Instruction::initialize(0x8A, 0xC0);
         let mut cpu: Cpu = Cpu::initialize();
         let mut memory: Memory = Memory::initialize();
-        let mut interface= Interface::default();
+        let mut framebuffer: Framebuffer = Framebuffer::initialize();
+        let keypad: Keypad = Keypad::initialize();
+        let quirks: Quirks = Quirks::DEFAULT;
 
         cpu.set_v_register(0xC, 0xFE);
 
         assert_eq!(0x00, cpu.get_v_register(0xA));
 
-        instruction.interpret(&mut cpu, &mut memory, &mut interface);
+        instruction.interpret(&mut cpu, &mut memory, &mut framebuffer, &keypad, &quirks).unwrap();
 
         assert_eq!(0xFE, cpu.get_v_register(0xA));
     }
@@ -670,12 +1200,14 @@ mod tests {
         let mut instruction: Instruction = Instruction::initialize(0x8A, 0xC1);
         let mut cpu: Cpu = Cpu::initialize();
         let mut memory: Memory = Memory::initialize();
-        let mut interface= Interface::default();
+        let mut framebuffer: Framebuffer = Framebuffer::initialize();
+        let keypad: Keypad = Keypad::initialize();
+        let quirks: Quirks = Quirks::DEFAULT;
 
         cpu.set_v_register(0xA, 0b10101010);
         cpu.set_v_register(0xC, 0b11110000);
 
-        instruction.interpret(&mut cpu, &mut memory, &mut interface);
+        instruction.interpret(&mut cpu, &mut memory, &mut framebuffer, &keypad, &quirks).unwrap();
 
         assert_eq!(0b11111010, cpu.get_v_register(0xA));
     }
@@ -685,12 +1217,14 @@ mod tests {
         let mut instruction: Instruction = Instruction::initialize(0x8A, 0xC2);
         let mut cpu: Cpu = Cpu::initialize();
         let mut memory: Memory = Memory::initialize();
-        let mut interface= Interface::default();
+        let mut framebuffer: Framebuffer = Framebuffer::initialize();
+        let keypad: Keypad = Keypad::initialize();
+        let quirks: Quirks = Quirks::DEFAULT;
 
         cpu.set_v_register(0xA, 0b10101010);
         cpu.set_v_register(0xC, 0b11110000);
 
-        instruction.interpret(&mut cpu, &mut memory, &mut interface);
+        instruction.interpret(&mut cpu, &mut memory, &mut framebuffer, &keypad, &quirks).unwrap();
 
         assert_eq!(0b10100000, cpu.get_v_register(0xA));
     }
@@ -700,16 +1234,38 @@ mod tests {
         let mut instruction: Instruction = Instruction::initialize(0x8A, 0xC3);
         let mut cpu: Cpu = Cpu::initialize();
         let mut memory: Memory = Memory::initialize();
-        let mut interface= Interface::default();
+        let mut framebuffer: Framebuffer = Framebuffer::initialize();
+        let keypad: Keypad = Keypad::initialize();
+        let quirks: Quirks = Quirks::DEFAULT;
 
         cpu.set_v_register(0xA, 0b10101010);
         cpu.set_v_register(0xC, 0b11110000);
 
-        instruction.interpret(&mut cpu, &mut memory, &mut interface);
+        instruction.interpret(&mut cpu, &mut memory, &mut framebuffer, &keypad, &quirks).unwrap();
 
         assert_eq!(0b01011010, cpu.get_v_register(0xA));
     }
 
+    #[test_case(0xC1 ; "with or operation")]
+    #[test_case(0xC2 ; "with and operation")]
+    #[test_case(0xC3 ; "with xor operation")]
+    fn it_should_reset_vf_after_logic_operations_with_the_vf_reset_quirk(second_byte: u8) {
+        let mut instruction: Instruction = Instruction::initialize(0x8A, second_byte);
+        let mut cpu: Cpu = Cpu::initialize();
+        let mut memory: Memory = Memory::initialize();
+        let mut framebuffer: Framebuffer = Framebuffer::initialize();
+        let keypad: Keypad = Keypad::initialize();
+        let quirks: Quirks = Quirks::COSMAC_VIP;
+
+        cpu.set_v_register(0xA, 0b10101010);
+        cpu.set_v_register(0xC, 0b11110000);
+        cpu.set_v_register(0xF, 0x1);
+
+        instruction.interpret(&mut cpu, &mut memory, &mut framebuffer, &keypad, &quirks).unwrap();
+
+        assert_eq!(0x0, cpu.get_v_register(0xF));
+    }
+
     #[test_case(0x03, 0x07, 0xA, 0x0 ; "without overflow")]
     #[test_case(0xFF, 0x01, 0x00, 0x1 ; "with overflow")]
     fn it_should_put_value_of_sum_operation_between_v_registers_on_first_passed_register(
@@ -721,12 +1277,14 @@ mod tests {
         let mut instruction: Instruction = Instruction::initialize(0x8A, 0xC4);
         let mut cpu: Cpu = Cpu::initialize();
         let mut memory: Memory = Memory::initialize();
-        let mut interface= Interface::default();
+        let mut framebuffer: Framebuffer = Framebuffer::initialize();
+        let keypad: Keypad = Keypad::initialize();
+        let quirks: Quirks = Quirks::DEFAULT;
 
         cpu.set_v_register(0xA, first_value);
         cpu.set_v_register(0xC, second_value);
 
-        instruction.interpret(&mut cpu, &mut memory, &mut interface);
+        instruction.interpret(&mut cpu, &mut memory, &mut framebuffer, &keypad, &quirks).unwrap();
 
         assert_eq!(result, cpu.get_v_register(0xA));
         assert_eq!(flag, cpu.get_v_register(0xF));
@@ -743,19 +1301,21 @@ mod tests {
         let mut instruction: Instruction = Instruction::initialize(0x8A, 0xC5);
         let mut cpu: Cpu = Cpu::initialize();
         let mut memory: Memory = Memory::initialize();
-        let mut interface= Interface::default();
+        let mut framebuffer: Framebuffer = Framebuffer::initialize();
+        let keypad: Keypad = Keypad::initialize();
+        let quirks: Quirks = Quirks::DEFAULT;
 
         cpu.set_v_register(0xA, first_value);
         cpu.set_v_register(0xC, second_value);
 
-        instruction.interpret(&mut cpu, &mut memory, &mut interface);
+        instruction.interpret(&mut cpu, &mut memory, &mut framebuffer, &keypad, &quirks).unwrap();
 
         assert_eq!(result, cpu.get_v_register(0xA));
         assert_eq!(flag, cpu.get_v_register(0xF));
     }
 
-    #[test_case(0b01010101, 0b00101010, 0x0 ; "with 0 on least significant bit")]
-    #[test_case(0b10101010, 0b01010101, 0x1 ; "with 1 on least significant bit")]
+    #[test_case(0b01010100, 0b00101010, 0x0 ; "with 0 on least significant bit")]
+    #[test_case(0b10101011, 0b01010101, 0x1 ; "with 1 on least significant bit")]
     fn it_should_put_value_of_bitwise_shift_right_operation_between_v_registers_on_first_passed_register(
         register_value: u8, 
         result: u8,
@@ -764,11 +1324,13 @@ mod tests {
         let mut instruction: Instruction = Instruction::initialize(0x8A, 0xC6);
         let mut cpu: Cpu = Cpu::initialize();
         let mut memory: Memory = Memory::initialize();
-        let mut interface= Interface::default();
+        let mut framebuffer: Framebuffer = Framebuffer::initialize();
+        let keypad: Keypad = Keypad::initialize();
+        let quirks: Quirks = Quirks::DEFAULT;
 
         cpu.set_v_register(0xA, register_value);
 
-        instruction.interpret(&mut cpu, &mut memory, &mut interface);
+        instruction.interpret(&mut cpu, &mut memory, &mut framebuffer, &keypad, &quirks).unwrap();
 
         assert_eq!(result, cpu.get_v_register(0xA));
         assert_eq!(flag, cpu.get_v_register(0xF));
@@ -785,19 +1347,21 @@ mod tests {
         let mut instruction: Instruction = Instruction::initialize(0x8A, 0xC7);
         let mut cpu: Cpu = Cpu::initialize();
         let mut memory: Memory = Memory::initialize();
-        let mut interface= Interface::default();
+        let mut framebuffer: Framebuffer = Framebuffer::initialize();
+        let keypad: Keypad = Keypad::initialize();
+        let quirks: Quirks = Quirks::DEFAULT;
 
         cpu.set_v_register(0xA, first_value);
         cpu.set_v_register(0xC, second_value);
 
-        instruction.interpret(&mut cpu, &mut memory, &mut interface);
+        instruction.interpret(&mut cpu, &mut memory, &mut framebuffer, &keypad, &quirks).unwrap();
 
         assert_eq!(result, cpu.get_v_register(0xA));
         assert_eq!(flag, cpu.get_v_register(0xF));
     }
 
-    #[test_case(0b10101010, 0b01010100, 0x0 ; "with 0 on most significant bit")]
-    #[test_case(0b01010101, 0b10101010, 0x1 ; "with 1 on most significant bit")]
+    #[test_case(0b01010101, 0b10101010, 0x0 ; "with 0 on most significant bit")]
+    #[test_case(0b10101010, 0b01010100, 0x1 ; "with 1 on most significant bit")]
     fn it_should_put_value_of_bitwise_shift_left_operation_between_v_registers_on_first_passed_register(
         register_value: u8, 
         result: u8,
@@ -806,16 +1370,59 @@ mod tests {
         let mut instruction: Instruction = Instruction::initialize(0x8A, 0xCE);
         let mut cpu: Cpu = Cpu::initialize();
         let mut memory: Memory = Memory::initialize();
-        let mut interface= Interface::default();
+        let mut framebuffer: Framebuffer = Framebuffer::initialize();
+        let keypad: Keypad = Keypad::initialize();
+        let quirks: Quirks = Quirks::DEFAULT;
 
         cpu.set_v_register(0xA, register_value);
 
-        instruction.interpret(&mut cpu, &mut memory, &mut interface);
+        instruction.interpret(&mut cpu, &mut memory, &mut framebuffer, &keypad, &quirks).unwrap();
+
+        assert_eq!(result, cpu.get_v_register(0xA));
+        assert_eq!(flag, cpu.get_v_register(0xF));
+    }
+
+    #[test_case(0x06, 0b10101011, 0b01010101, 0x1 ; "with shift right")]
+    #[test_case(0x0E, 0b10101011, 0b01010110, 0x1 ; "with shift left")]
+    fn it_should_shift_the_second_v_register_without_the_shift_in_place_quirk(
+        second_byte: u8,
+        register_value: u8,
+        result: u8,
+        flag: u8,
+    ) {
+        let mut instruction: Instruction = Instruction::initialize(0x8A, 0xC0 | second_byte);
+        let mut cpu: Cpu = Cpu::initialize();
+        let mut memory: Memory = Memory::initialize();
+        let mut framebuffer: Framebuffer = Framebuffer::initialize();
+        let keypad: Keypad = Keypad::initialize();
+        let quirks: Quirks = Quirks::COSMAC_VIP;
+
+        cpu.set_v_register(0xA, 0x0);
+        cpu.set_v_register(0xC, register_value);
+
+        instruction.interpret(&mut cpu, &mut memory, &mut framebuffer, &keypad, &quirks).unwrap();
 
         assert_eq!(result, cpu.get_v_register(0xA));
+        assert_eq!(register_value, cpu.get_v_register(0xC));
         assert_eq!(flag, cpu.get_v_register(0xF));
     }
 
+    #[test]
+    fn it_should_keep_the_shifted_out_bit_when_shifting_vf() {
+        let mut instruction: Instruction = Instruction::initialize(0x8F, 0x06);
+        let mut cpu: Cpu = Cpu::initialize();
+        let mut memory: Memory = Memory::initialize();
+        let mut framebuffer: Framebuffer = Framebuffer::initialize();
+        let keypad: Keypad = Keypad::initialize();
+        let quirks: Quirks = Quirks::DEFAULT;
+
+        cpu.set_v_register(0xF, 0b00000011);
+
+        instruction.interpret(&mut cpu, &mut memory, &mut framebuffer, &keypad, &quirks).unwrap();
+
+        assert_eq!(0x1, cpu.get_v_register(0xF));
+    }
+
     #[test_case(0xFF, 0xAA, 0x202 ; "with different values")]
     #[test_case(0xFF, 0xFF, 0x200 ; "with the same value")]
     fn it_should_skip_next_instruction_if_v_registers_values_are_different(
@@ -826,12 +1433,38 @@ mod tests {
         let mut instruction: Instruction = Instruction::initialize(0x9A, 0xB0);
         let mut cpu: Cpu = Cpu::initialize();
         let mut memory: Memory = Memory::initialize();
-        let mut interface= Interface::default();
+        let mut framebuffer: Framebuffer = Framebuffer::initialize();
+        let keypad: Keypad = Keypad::initialize();
+        let quirks: Quirks = Quirks::DEFAULT;
 
         cpu.set_v_register(0xA, first_register_value);
         cpu.set_v_register(0xB, second_register_value);
 
-        instruction.interpret(&mut cpu, &mut memory, &mut interface);
+        instruction.interpret(&mut cpu, &mut memory, &mut framebuffer, &keypad, &quirks).unwrap();
+
+        assert_eq!(program_counter, cpu.get_program_counter());
+    }
+
+    #[test_case(0x3A, 0x00, Quirks::XO_CHIP, 0x206 ; "with argument equals v register value")]
+    #[test_case(0x4A, 0x01, Quirks::XO_CHIP, 0x206 ; "with argument differs v register value")]
+    #[test_case(0x5A, 0xB0, Quirks::XO_CHIP, 0x206 ; "with v registers values are the same")]
+    #[test_case(0x9A, 0xC0, Quirks::XO_CHIP, 0x206 ; "with v registers values are different")]
+    #[test_case(0xEA, 0xA1, Quirks::XO_CHIP, 0x206 ; "with key not pressed")]
+    #[test_case(0x3A, 0x00, Quirks::DEFAULT, 0x204 ; "without the xo-chip extensions")]
+    fn it_should_skip_the_whole_long_load_instruction(first_byte: u8, second_byte: u8, quirks: Quirks, program_counter: usize) {
+        let mut instruction: Instruction = Instruction::initialize(first_byte, second_byte);
+        let mut cpu: Cpu = Cpu::initialize();
+        let mut memory: Memory = Memory::initialize();
+        let mut framebuffer: Framebuffer = Framebuffer::initialize();
+        let keypad: Keypad = Keypad::initialize();
+
+        cpu.set_program_counter(0x202);
+        cpu.set_v_register(0xC, 0x1);
+
+        memory.set(0x202, 0xF0).unwrap();
+        memory.set(0x203, 0x00).unwrap();
+
+        instruction.interpret(&mut cpu, &mut memory, &mut framebuffer, &keypad, &quirks).unwrap();
 
         assert_eq!(program_counter, cpu.get_program_counter());
     }
@@ -841,39 +1474,210 @@ mod tests {
         let mut instruction: Instruction = Instruction::initialize(0xAC, 0xAF);
         let mut cpu: Cpu = Cpu::initialize();
         let mut memory: Memory = Memory::initialize();
-        let mut interface= Interface::default();
+        let mut framebuffer: Framebuffer = Framebuffer::initialize();
+        let keypad: Keypad = Keypad::initialize();
+        let quirks: Quirks = Quirks::DEFAULT;
 
-        instruction.interpret(&mut cpu, &mut memory, &mut interface);
+        instruction.interpret(&mut cpu, &mut memory, &mut framebuffer, &keypad, &quirks).unwrap();
 
         assert_eq!(0x0CAF, cpu.get_i_register());
     }
 
+    #[test]
+    fn it_should_put_next_word_on_i_register() {
+        let mut instruction: Instruction = Instruction::initialize(0xF0, 0x00);
+        let mut cpu: Cpu = Cpu::initialize();
+        let mut memory: Memory = Memory::initialize();
+        let mut framebuffer: Framebuffer = Framebuffer::initialize();
+        let keypad: Keypad = Keypad::initialize();
+        let quirks: Quirks = Quirks::XO_CHIP;
+
+        cpu.set_program_counter(0x202);
+
+        memory.set(0x202, 0xCA).unwrap();
+        memory.set(0x203, 0xFE).unwrap();
+
+        instruction.interpret(&mut cpu, &mut memory, &mut framebuffer, &keypad, &quirks).unwrap();
+
+        assert_eq!(0xCAFE, cpu.get_i_register());
+        assert_eq!(0x204, cpu.get_program_counter());
+    }
+
+    #[test]
+    fn it_should_fail_to_put_next_word_on_i_register_without_the_xo_chip_extensions() {
+        let mut instruction: Instruction = Instruction::initialize(0xF0, 0x00);
+        let mut cpu: Cpu = Cpu::initialize();
+        let mut memory: Memory = Memory::initialize();
+        let mut framebuffer: Framebuffer = Framebuffer::initialize();
+        let keypad: Keypad = Keypad::initialize();
+        let quirks: Quirks = Quirks::DEFAULT;
+
+        cpu.set_program_counter(0x202);
+
+        assert_eq!(
+            Err(EmulatorError::UnknownOpcode { opcode: 0xF000, program_counter: 0x200 }),
+            instruction.interpret(&mut cpu, &mut memory, &mut framebuffer, &keypad, &quirks)
+        );
+    }
+
+    #[test]
+    fn it_should_select_drawing_planes() {
+        let mut instruction: Instruction = Instruction::initialize(0xF3, 0x01);
+        let mut cpu: Cpu = Cpu::initialize();
+        let mut memory: Memory = Memory::initialize();
+        let mut framebuffer: Framebuffer = Framebuffer::initialize();
+        let keypad: Keypad = Keypad::initialize();
+        let quirks: Quirks = Quirks::XO_CHIP;
+
+        instruction.interpret(&mut cpu, &mut memory, &mut framebuffer, &keypad, &quirks).unwrap();
+
+        assert_eq!(0b0011, framebuffer.get_selected_planes());
+    }
+
+    #[test]
+    fn it_should_fail_to_select_drawing_planes_without_the_xo_chip_extensions() {
+        let mut instruction: Instruction = Instruction::initialize(0xF3, 0x01);
+        let mut cpu: Cpu = Cpu::initialize();
+        let mut memory: Memory = Memory::initialize();
+        let mut framebuffer: Framebuffer = Framebuffer::initialize();
+        let keypad: Keypad = Keypad::initialize();
+        let quirks: Quirks = Quirks::DEFAULT;
+
+        cpu.set_program_counter(0x202);
+
+        assert_eq!(
+            Err(EmulatorError::UnknownOpcode { opcode: 0xF301, program_counter: 0x200 }),
+            instruction.interpret(&mut cpu, &mut memory, &mut framebuffer, &keypad, &quirks)
+        );
+        assert_eq!(0b0001, framebuffer.get_selected_planes());
+    }
+
+    #[test]
+    fn it_should_put_the_audio_pattern_from_memory() {
+        let mut instruction: Instruction = Instruction::initialize(0xF0, 0x02);
+        let mut cpu: Cpu = Cpu::initialize();
+        let mut memory: Memory = Memory::initialize();
+        let mut framebuffer: Framebuffer = Framebuffer::initialize();
+        let keypad: Keypad = Keypad::initialize();
+        let quirks: Quirks = Quirks::XO_CHIP;
+        let pattern: [u8; 16] = [0x0, 0x1, 0x2, 0x3, 0x4, 0x5, 0x6, 0x7, 0x8, 0x9, 0xA, 0xB, 0xC, 0xD, 0xE, 0xF];
+
+        cpu.set_i_register(0x300);
+
+        for (index, byte) in pattern.iter().enumerate() {
+            memory.set(0x300 + index, *byte).unwrap();
+        }
+
+        instruction.interpret(&mut cpu, &mut memory, &mut framebuffer, &keypad, &quirks).unwrap();
+
+        assert_eq!(Some(pattern), cpu.get_audio_pattern());
+        assert_eq!(0x300, cpu.get_i_register());
+    }
+
+    #[test]
+    fn it_should_put_v_register_value_on_pitch() {
+        let mut instruction: Instruction = Instruction::initialize(0xF5, 0x3A);
+        let mut cpu: Cpu = Cpu::initialize();
+        let mut memory: Memory = Memory::initialize();
+        let mut framebuffer: Framebuffer = Framebuffer::initialize();
+        let keypad: Keypad = Keypad::initialize();
+        let quirks: Quirks = Quirks::XO_CHIP;
+
+        cpu.set_v_register(0x5, 0x70);
+
+        instruction.interpret(&mut cpu, &mut memory, &mut framebuffer, &keypad, &quirks).unwrap();
+
+        assert_eq!(0x70, cpu.get_pitch());
+    }
+
+    #[test_case(0xF0, 0x02 ; "with the audio pattern")]
+    #[test_case(0xF5, 0x3A ; "with the pitch")]
+    fn it_should_fail_to_set_the_audio_without_the_xo_chip_extensions(first_byte: u8, second_byte: u8) {
+        let mut instruction: Instruction = Instruction::initialize(first_byte, second_byte);
+        let mut cpu: Cpu = Cpu::initialize();
+        let mut memory: Memory = Memory::initialize();
+        let mut framebuffer: Framebuffer = Framebuffer::initialize();
+        let keypad: Keypad = Keypad::initialize();
+        let quirks: Quirks = Quirks::DEFAULT;
+
+        cpu.set_program_counter(0x202);
+
+        assert_eq!(
+            Err(EmulatorError::UnknownOpcode { opcode: u16::from_be_bytes([first_byte, second_byte]), program_counter: 0x200 }),
+            instruction.interpret(&mut cpu, &mut memory, &mut framebuffer, &keypad, &quirks)
+        );
+        assert_eq!(None, cpu.get_audio_pattern());
+        assert_eq!(64, cpu.get_pitch());
+    }
+
     #[test]
     fn it_should_jump_to_argument_value_plus_v0() {
         let mut instruction: Instruction = Instruction::initialize(0xB0, 0x03);
         let mut cpu: Cpu = Cpu::initialize();
         let mut memory: Memory = Memory::initialize();
-        let mut interface= Interface::default();
+        let mut framebuffer: Framebuffer = Framebuffer::initialize();
+        let keypad: Keypad = Keypad::initialize();
+        let quirks: Quirks = Quirks::DEFAULT;
 
         cpu.set_v_register(0x0, 0x4);
 
-        instruction.interpret(&mut cpu, &mut memory, &mut interface);
+        instruction.interpret(&mut cpu, &mut memory, &mut framebuffer, &keypad, &quirks).unwrap();
 
         assert_eq!(0x7, cpu.get_program_counter());
     }
 
     #[test]
-    fn it_should_put_value_of_bitwise_or_operation_between_argument_and_random_byte_on_passed_v_register() {
+    fn it_should_jump_to_argument_value_plus_vx_with_the_jump_quirk() {
+        let mut instruction: Instruction = Instruction::initialize(0xB2, 0x05);
+        let mut cpu: Cpu = Cpu::initialize();
+        let mut memory: Memory = Memory::initialize();
+        let mut framebuffer: Framebuffer = Framebuffer::initialize();
+        let keypad: Keypad = Keypad::initialize();
+        let quirks: Quirks = Quirks::CHIP_48;
+
+        cpu.set_v_register(0x0, 0x1);
+        cpu.set_v_register(0x2, 0x4);
+
+        instruction.interpret(&mut cpu, &mut memory, &mut framebuffer, &keypad, &quirks).unwrap();
+
+        assert_eq!(0x209, cpu.get_program_counter());
+    }
+
+    #[test_case(0x00 ; "with a zero mask")]
+    #[test_case(0x0F ; "with a low nibble mask")]
+    #[test_case(0xFF ; "with a full mask")]
+    fn it_should_mask_the_random_byte_with_the_argument(argument: u8) {
+        let mut instruction: Instruction = Instruction::initialize(0xCA, argument);
+        let mut cpu: Cpu = Cpu::initialize();
+        let mut memory: Memory = Memory::initialize();
+        let mut framebuffer: Framebuffer = Framebuffer::initialize();
+        let keypad: Keypad = Keypad::initialize();
+        let quirks: Quirks = Quirks::DEFAULT;
+        let mut random: Random = Random::initialize(0xCAFE);
+
+        cpu.set_random_state(0xCAFE);
+
+        instruction.interpret(&mut cpu, &mut memory, &mut framebuffer, &keypad, &quirks).unwrap();
+
+        assert_eq!(random.next_byte() & argument, cpu.get_v_register(0xA));
+    }
+
+    #[test]
+    fn it_should_take_the_random_byte_from_the_seeded_generator() {
         let mut instruction: Instruction = Instruction::initialize(0xCA, 0xFF);
         let mut cpu: Cpu = Cpu::initialize();
         let mut memory: Memory = Memory::initialize();
-        let mut interface= Interface::default();
+        let mut framebuffer: Framebuffer = Framebuffer::initialize();
+        let keypad: Keypad = Keypad::initialize();
+        let quirks: Quirks = Quirks::DEFAULT;
+        let mut random: Random = Random::initialize(0xCAFE);
 
-        assert_eq!(0x0, cpu.get_v_register(0xA));
+        cpu.set_random_state(0xCAFE);
 
-        instruction.interpret(&mut cpu, &mut memory, &mut interface);
+        instruction.interpret(&mut cpu, &mut memory, &mut framebuffer, &keypad, &quirks).unwrap();
 
-        assert_eq!(0xFF, cpu.get_v_register(0xA));
+        assert_eq!(random.next_byte(), cpu.get_v_register(0xA));
+        assert_eq!(random.get_state(), cpu.get_random_state());
     }
 
     #[test]
@@ -881,28 +1685,19 @@ mod tests {
         let mut instruction: Instruction = Instruction::initialize(0xDA, 0xC1);
         let mut cpu: Cpu = Cpu::initialize();
         let mut memory: Memory = Memory::initialize();
-        let mut interface= Interface::default();
+        let mut framebuffer: Framebuffer = Framebuffer::initialize();
+        let keypad: Keypad = Keypad::initialize();
+        let quirks: Quirks = Quirks::DEFAULT;
 
         cpu.set_i_register(0x400);
         cpu.set_v_register(0xA, 0x3);
         cpu.set_v_register(0xC, 0x0);
 
-        memory.set(0x400, 0b11001010);
-
-        interface.expect_draw_pixel().with(eq(true), eq(0x3), eq(0x0)).returning(|_, _, _| false);
-        interface.expect_draw_pixel().with(eq(true), eq(0x4), eq(0x0)).returning(|_, _, _| false);
-        interface.expect_draw_pixel().with(eq(false), eq(0x5), eq(0x0)).returning(|_, _, _| false);
-        interface.expect_draw_pixel().with(eq(false), eq(0x6), eq(0x0)).returning(|_, _, _| false);
-        interface.expect_draw_pixel().with(eq(true), eq(0x7), eq(0x0)).returning(|_, _, _| false);
-        interface.expect_draw_pixel().with(eq(false), eq(0x8), eq(0x0)).returning(|_, _, _| false);
-        interface.expect_draw_pixel().with(eq(true), eq(0x9), eq(0x0)).returning(|_, _, _| false);
-        interface.expect_draw_pixel().with(eq(false), eq(0xA), eq(0x0)).returning(|_, _, _| false);
+        memory.set(0x400, 0b11001010).unwrap();
 
-        interface.expect_refresh()
-            .returning(|| ());
-
-        instruction.interpret(&mut cpu, &mut memory, &mut interface);
+        instruction.interpret(&mut cpu, &mut memory, &mut framebuffer, &keypad, &quirks).unwrap();
 
+        assert_eq!([true, true, false, false, true, false, true, false], framebuffer.get_pixels()[0x3..0xB]);
         assert_eq!(0x0, cpu.get_v_register(0xF));
     }
 
@@ -911,28 +1706,21 @@ mod tests {
         let mut instruction: Instruction = Instruction::initialize(0xDA, 0xC1);
         let mut cpu: Cpu = Cpu::initialize();
         let mut memory: Memory = Memory::initialize();
-        let mut interface= Interface::default();
+        let mut framebuffer: Framebuffer = Framebuffer::initialize();
+        let keypad: Keypad = Keypad::initialize();
+        let quirks: Quirks = Quirks::DEFAULT;
 
         cpu.set_i_register(0x400);
         cpu.set_v_register(0xA, 0x3);
         cpu.set_v_register(0xC, 0x0);
 
-        memory.set(0x400, 0b11001010);
-
-        interface.expect_draw_pixel().with(eq(true), eq(0x3), eq(0x0)).returning(|_, _, _| false);
-        interface.expect_draw_pixel().with(eq(true), eq(0x4), eq(0x0)).returning(|_, _, _| false);
-        interface.expect_draw_pixel().with(eq(false), eq(0x5), eq(0x0)).returning(|_, _, _| false);
-        interface.expect_draw_pixel().with(eq(false), eq(0x6), eq(0x0)).returning(|_, _, _| false);
-        interface.expect_draw_pixel().with(eq(true), eq(0x7), eq(0x0)).returning(|_, _, _| true);
-        interface.expect_draw_pixel().with(eq(false), eq(0x8), eq(0x0)).returning(|_, _, _| false);
-        interface.expect_draw_pixel().with(eq(true), eq(0x9), eq(0x0)).returning(|_, _, _| false);
-        interface.expect_draw_pixel().with(eq(false), eq(0xA), eq(0x0)).returning(|_, _, _| false);
+        memory.set(0x400, 0b11001010).unwrap();
 
-        interface.expect_refresh()
-            .returning(|| ());
+        framebuffer.draw_pixel(true, 0x7, 0x0);
 
-        instruction.interpret(&mut cpu, &mut memory, &mut interface);
+        instruction.interpret(&mut cpu, &mut memory, &mut framebuffer, &keypad, &quirks).unwrap();
 
+        assert_eq!([true, true, false, false, false, false, true, false], framebuffer.get_pixels()[0x3..0xB]);
         assert_eq!(0x1, cpu.get_v_register(0xF));
     }
 
@@ -941,38 +1729,21 @@ mod tests {
         let mut instruction: Instruction = Instruction::initialize(0xDA, 0xC2);
         let mut cpu: Cpu = Cpu::initialize();
         let mut memory: Memory = Memory::initialize();
-        let mut interface= Interface::default();
+        let mut framebuffer: Framebuffer = Framebuffer::initialize();
+        let keypad: Keypad = Keypad::initialize();
+        let quirks: Quirks = Quirks::DEFAULT;
 
         cpu.set_i_register(0x400);
         cpu.set_v_register(0xA, 0x3);
         cpu.set_v_register(0xC, 0x0);
 
-        memory.set(0x400, 0b11001010);
-        memory.set(0x401, 0b11111111);
-
-        interface.expect_draw_pixel().with(eq(true), eq(0x3), eq(0x0)).returning(|_, _, _| false);
-        interface.expect_draw_pixel().with(eq(true), eq(0x4), eq(0x0)).returning(|_, _, _| false);
-        interface.expect_draw_pixel().with(eq(false), eq(0x5), eq(0x0)).returning(|_, _, _| false);
-        interface.expect_draw_pixel().with(eq(false), eq(0x6), eq(0x0)).returning(|_, _, _| false);
-        interface.expect_draw_pixel().with(eq(true), eq(0x7), eq(0x0)).returning(|_, _, _| false);
-        interface.expect_draw_pixel().with(eq(false), eq(0x8), eq(0x0)).returning(|_, _, _| false);
-        interface.expect_draw_pixel().with(eq(true), eq(0x9), eq(0x0)).returning(|_, _, _| false);
-        interface.expect_draw_pixel().with(eq(false), eq(0xA), eq(0x0)).returning(|_, _, _| false);
-
-        interface.expect_draw_pixel().with(eq(true), eq(0x3), eq(0x1)).returning(|_, _, _| false);
-        interface.expect_draw_pixel().with(eq(true), eq(0x4), eq(0x1)).returning(|_, _, _| false);
-        interface.expect_draw_pixel().with(eq(true), eq(0x5), eq(0x1)).returning(|_, _, _| false);
-        interface.expect_draw_pixel().with(eq(true), eq(0x6), eq(0x1)).returning(|_, _, _| false);
-        interface.expect_draw_pixel().with(eq(true), eq(0x7), eq(0x1)).returning(|_, _, _| false);
-        interface.expect_draw_pixel().with(eq(true), eq(0x8), eq(0x1)).returning(|_, _, _| false);
-        interface.expect_draw_pixel().with(eq(true), eq(0x9), eq(0x1)).returning(|_, _, _| false);
-        interface.expect_draw_pixel().with(eq(true), eq(0xA), eq(0x1)).returning(|_, _, _| false);
+        memory.set(0x400, 0b11001010).unwrap();
+        memory.set(0x401, 0b11111111).unwrap();
 
-        interface.expect_refresh()
-            .returning(|| ());
-
-        instruction.interpret(&mut cpu, &mut memory, &mut interface);
+        instruction.interpret(&mut cpu, &mut memory, &mut framebuffer, &keypad, &quirks).unwrap();
 
+        assert_eq!([true, true, false, false, true, false, true, false], framebuffer.get_pixels()[0x3..0xB]);
+        assert_eq!([true; 8], framebuffer.get_pixels()[0x43..0x4B]);
         assert_eq!(0x0, cpu.get_v_register(0xF));
     }
 
@@ -981,28 +1752,19 @@ mod tests {
         let mut instruction: Instruction = Instruction::initialize(0xDA, 0xC1);
         let mut cpu: Cpu = Cpu::initialize();
         let mut memory: Memory = Memory::initialize();
-        let mut interface= Interface::default();
+        let mut framebuffer: Framebuffer = Framebuffer::initialize();
+        let keypad: Keypad = Keypad::initialize();
+        let quirks: Quirks = Quirks::DEFAULT;
 
         cpu.set_i_register(0x400);
         cpu.set_v_register(0xA, 67);
         cpu.set_v_register(0xC, 0x0);
 
-        memory.set(0x400, 0b11001010);
-
-        interface.expect_draw_pixel().with(eq(true), eq(0x3), eq(0x0)).returning(|_, _, _| false);
-        interface.expect_draw_pixel().with(eq(true), eq(0x4), eq(0x0)).returning(|_, _, _| false);
-        interface.expect_draw_pixel().with(eq(false), eq(0x5), eq(0x0)).returning(|_, _, _| false);
-        interface.expect_draw_pixel().with(eq(false), eq(0x6), eq(0x0)).returning(|_, _, _| false);
-        interface.expect_draw_pixel().with(eq(true), eq(0x7), eq(0x0)).returning(|_, _, _| false);
-        interface.expect_draw_pixel().with(eq(false), eq(0x8), eq(0x0)).returning(|_, _, _| false);
-        interface.expect_draw_pixel().with(eq(true), eq(0x9), eq(0x0)).returning(|_, _, _| false);
-        interface.expect_draw_pixel().with(eq(false), eq(0xA), eq(0x0)).returning(|_, _, _| false);
+        memory.set(0x400, 0b11001010).unwrap();
 
-        interface.expect_refresh()
-            .returning(|| ());
-
-        instruction.interpret(&mut cpu, &mut memory, &mut interface);
+        instruction.interpret(&mut cpu, &mut memory, &mut framebuffer, &keypad, &quirks).unwrap();
 
+        assert_eq!([true, true, false, false, true, false, true, false], framebuffer.get_pixels()[0x3..0xB]);
         assert_eq!(0x0, cpu.get_v_register(0xF));
     }
 
@@ -1011,57 +1773,167 @@ mod tests {
         let mut instruction: Instruction = Instruction::initialize(0xDA, 0xC1);
         let mut cpu: Cpu = Cpu::initialize();
         let mut memory: Memory = Memory::initialize();
-        let mut interface= Interface::default();
+        let mut framebuffer: Framebuffer = Framebuffer::initialize();
+        let keypad: Keypad = Keypad::initialize();
+        let quirks: Quirks = Quirks::DEFAULT;
 
         cpu.set_i_register(0x400);
         cpu.set_v_register(0xA, 60);
         cpu.set_v_register(0xC, 0x0);
 
-        memory.set(0x400, 0b11111111);
+        memory.set(0x400, 0b11111111).unwrap();
+
+        instruction.interpret(&mut cpu, &mut memory, &mut framebuffer, &keypad, &quirks).unwrap();
+
+        assert_eq!([true; 4], framebuffer.get_pixels()[60..64]);
+        assert_eq!([false; 4], framebuffer.get_pixels()[64..68]);
+        assert_eq!(0x0, cpu.get_v_register(0xF));
+    }
+
+    #[test]
+    fn it_should_draw_byte_sprite_starting_at_location_i_on_register_stored_location_wrapping_extra_bits_with_the_wrap_quirk() {
+        let mut instruction: Instruction = Instruction::initialize(0xDA, 0xC2);
+        let mut cpu: Cpu = Cpu::initialize();
+        let mut memory: Memory = Memory::initialize();
+        let mut framebuffer: Framebuffer = Framebuffer::initialize();
+        let keypad: Keypad = Keypad::initialize();
+        let quirks: Quirks = Quirks { wrap_sprites: true, ..Quirks::DEFAULT };
 
-        interface.expect_draw_pixel().with(eq(true), eq(60), eq(0x0)).returning(|_, _, _| false);
-        interface.expect_draw_pixel().with(eq(true), eq(61), eq(0x0)).returning(|_, _, _| false);
-        interface.expect_draw_pixel().with(eq(true), eq(62), eq(0x0)).returning(|_, _, _| false);
-        interface.expect_draw_pixel().with(eq(true), eq(63), eq(0x0)).returning(|_, _, _| false);
+        cpu.set_i_register(0x400);
+        cpu.set_v_register(0xA, 60);
+        cpu.set_v_register(0xC, 31);
 
-        interface.expect_refresh()
-            .returning(|| ());
+        memory.set(0x400, 0b11111111).unwrap();
+        memory.set(0x401, 0b11111111).unwrap();
 
-        instruction.interpret(&mut cpu, &mut memory, &mut interface);
+        instruction.interpret(&mut cpu, &mut memory, &mut framebuffer, &keypad, &quirks).unwrap();
 
+        assert!(framebuffer.get_pixel(63, 31));
+        assert!(framebuffer.get_pixel(0, 31));
+        assert!(framebuffer.get_pixel(3, 31));
+        assert!(!framebuffer.get_pixel(4, 31));
+        assert!(framebuffer.get_pixel(60, 0));
+        assert!(framebuffer.get_pixel(3, 0));
         assert_eq!(0x0, cpu.get_v_register(0xF));
     }
 
+    #[test]
+    fn it_should_draw_a_16x16_sprite_in_high_resolution() {
+        let mut instruction: Instruction = Instruction::initialize(0xDA, 0xC0);
+        let mut cpu: Cpu = Cpu::initialize();
+        let mut memory: Memory = Memory::initialize();
+        let mut framebuffer: Framebuffer = Framebuffer::initialize();
+        let keypad: Keypad = Keypad::initialize();
+        let quirks: Quirks = Quirks::DEFAULT;
+
+        framebuffer.set_high_resolution(true);
+
+        cpu.set_i_register(0x400);
+        cpu.set_v_register(0xA, 100);
+        cpu.set_v_register(0xC, 40);
+
+        memory.set(0x400, 0b11001010).unwrap();
+        memory.set(0x401, 0b00000001).unwrap();
+        memory.set(0x41E, 0b10000000).unwrap();
+        memory.set(0x41F, 0b00000011).unwrap();
+
+        framebuffer.draw_pixel(true, 114, 55);
+
+        instruction.interpret(&mut cpu, &mut memory, &mut framebuffer, &keypad, &quirks).unwrap();
+
+        assert_eq!(
+            [true, true, false, false, true, false, true, false, false, false, false, false, false, false, false, true],
+            framebuffer.get_pixels()[100 + (40 * 128)..116 + (40 * 128)]
+        );
+        assert!(framebuffer.get_pixel(100, 55));
+        assert!(!framebuffer.get_pixel(114, 55));
+        assert!(framebuffer.get_pixel(115, 55));
+        assert_eq!(0x1, cpu.get_v_register(0xF));
+    }
+
+    #[test]
+    fn it_should_draw_byte_sprite_on_each_selected_plane() {
+        let mut instruction: Instruction = Instruction::initialize(0xDA, 0xC2);
+        let mut cpu: Cpu = Cpu::initialize();
+        let mut memory: Memory = Memory::initialize();
+        let mut framebuffer: Framebuffer = Framebuffer::initialize();
+        let keypad: Keypad = Keypad::initialize();
+        let quirks: Quirks = Quirks::XO_CHIP;
+
+        framebuffer.select_planes(0b0011);
+
+        cpu.set_i_register(0x400);
+        cpu.set_v_register(0xA, 0x3);
+        cpu.set_v_register(0xC, 0x0);
+
+        memory.set(0x400, 0b11000000).unwrap();
+        memory.set(0x401, 0b10000000).unwrap();
+        memory.set(0x402, 0b10100000).unwrap();
+        memory.set(0x403, 0b00000000).unwrap();
+
+        framebuffer.draw_plane_pixel(1, true, 0x5, 0x0);
+
+        instruction.interpret(&mut cpu, &mut memory, &mut framebuffer, &keypad, &quirks).unwrap();
+
+        assert_eq!([0x3, 0x1, 0x0, 0x0], framebuffer.get_colours()[0x3..0x7]);
+        assert_eq!([0x1, 0x0], framebuffer.get_colours()[0x43..0x45]);
+        assert_eq!(0x1, cpu.get_v_register(0xF));
+    }
+
+    #[test]
+    fn it_should_draw_nothing_without_selected_planes() {
+        let mut instruction: Instruction = Instruction::initialize(0xDA, 0xC1);
+        let mut cpu: Cpu = Cpu::initialize();
+        let mut memory: Memory = Memory::initialize();
+        let mut framebuffer: Framebuffer = Framebuffer::initialize();
+        let keypad: Keypad = Keypad::initialize();
+        let quirks: Quirks = Quirks::XO_CHIP;
+
+        framebuffer.select_planes(0b0000);
+
+        cpu.set_i_register(0x400);
+
+        memory.set(0x400, 0b11111111).unwrap();
+
+        instruction.interpret(&mut cpu, &mut memory, &mut framebuffer, &keypad, &quirks).unwrap();
+
+        assert!(framebuffer.get_colours().iter().all(|colour| *colour == 0x0));
+    }
+
     #[test_case(true, 0x202 ; "with key press")]
     #[test_case(false, 0x200 ; "without key press")]
     fn it_should_skip_next_instruction_if_key_with_v_register_value_is_pressed(press: bool, program_counter: usize) {
         let mut instruction: Instruction = Instruction::initialize(0xEA, 0x9E);
         let mut cpu: Cpu = Cpu::initialize();
         let mut memory: Memory = Memory::initialize();
-        let mut interface= Interface::default();
+        let mut framebuffer: Framebuffer = Framebuffer::initialize();
+        let mut keypad: Keypad = Keypad::initialize();
+        let quirks: Quirks = Quirks::DEFAULT;
 
         cpu.set_v_register(0xA, 0x1);
 
-        interface.expect_is_pressed().with(eq(0x1)).returning(move |_| press);
+        keypad.set_key(0x1, press);
 
-        instruction.interpret(&mut cpu, &mut memory, &mut interface);
+        instruction.interpret(&mut cpu, &mut memory, &mut framebuffer, &keypad, &quirks).unwrap();
 
         assert_eq!(program_counter, cpu.get_program_counter());
     }
 
-    #[test_case(false, 0x200 ; "with key press")]
-    #[test_case(true, 0x202 ; "without key press")]
+    #[test_case(true, 0x200 ; "with key press")]
+    #[test_case(false, 0x202 ; "without key press")]
     fn it_should_skip_next_instruction_if_key_with_v_register_value_is_not_pressed(press: bool, program_counter: usize) {
         let mut instruction: Instruction = Instruction::initialize(0xEA, 0xA1);
         let mut cpu: Cpu = Cpu::initialize();
         let mut memory: Memory = Memory::initialize();
-        let mut interface= Interface::default();
+        let mut framebuffer: Framebuffer = Framebuffer::initialize();
+        let mut keypad: Keypad = Keypad::initialize();
+        let quirks: Quirks = Quirks::DEFAULT;
 
         cpu.set_v_register(0xA, 0x2);
 
-        interface.expect_is_not_pressed().with(eq(0x2)).returning(move |_| press);
+        keypad.set_key(0x2, press);
 
-        instruction.interpret(&mut cpu, &mut memory, &mut interface);
+        instruction.interpret(&mut cpu, &mut memory, &mut framebuffer, &keypad, &quirks).unwrap();
 
         assert_eq!(program_counter, cpu.get_program_counter());
     }
@@ -1071,13 +1943,15 @@ mod tests {
         let mut instruction: Instruction = Instruction::initialize(0xFC, 0x07);
         let mut cpu: Cpu = Cpu::initialize();
         let mut memory: Memory = Memory::initialize();
-        let mut interface= Interface::default();
+        let mut framebuffer: Framebuffer = Framebuffer::initialize();
+        let keypad: Keypad = Keypad::initialize();
+        let quirks: Quirks = Quirks::DEFAULT;
 
         cpu.set_delay_timer(0xCA);
 
         assert_eq!(0x0, cpu.get_v_register(0xC));
 
-        instruction.interpret(&mut cpu, &mut memory, &mut interface);
+        instruction.interpret(&mut cpu, &mut memory, &mut framebuffer, &keypad, &quirks).unwrap();
 
         assert_eq!(0xCA, cpu.get_v_register(0xC));
     }
@@ -1087,12 +1961,13 @@ mod tests {
         let mut instruction: Instruction = Instruction::initialize(0xFC, 0x0A);
         let mut cpu: Cpu = Cpu::initialize();
         let mut memory: Memory = Memory::initialize();
-        let mut interface= Interface::default();
+        let mut framebuffer: Framebuffer = Framebuffer::initialize();
+        let mut keypad: Keypad = Keypad::initialize();
+        let quirks: Quirks = Quirks::DEFAULT;
 
-        interface.expect_get_pressed_key()
-            .returning(|| Some(0xA));
+        keypad.set_key(0xA, true);
 
-        instruction.interpret(&mut cpu, &mut memory, &mut interface);
+        instruction.interpret(&mut cpu, &mut memory, &mut framebuffer, &keypad, &quirks).unwrap();
 
         assert_eq!(0xA, cpu.get_v_register(0xC));
     }
@@ -1102,14 +1977,13 @@ mod tests {
         let mut instruction: Instruction = Instruction::initialize(0xFC, 0x0A);
         let mut cpu: Cpu = Cpu::initialize();
         let mut memory: Memory = Memory::initialize();
-        let mut interface= Interface::default();
-
-        interface.expect_get_pressed_key()
-            .returning(|| None);
+        let mut framebuffer: Framebuffer = Framebuffer::initialize();
+        let keypad: Keypad = Keypad::initialize();
+        let quirks: Quirks = Quirks::DEFAULT;
 
         cpu.set_program_counter(0x202);
 
-        instruction.interpret(&mut cpu, &mut memory, &mut interface);
+        instruction.interpret(&mut cpu, &mut memory, &mut framebuffer, &keypad, &quirks).unwrap();
 
         assert_eq!(0x200, cpu.get_program_counter());
         assert_eq!(0x0, cpu.get_v_register(0xC));
@@ -1120,13 +1994,15 @@ mod tests {
         let mut instruction: Instruction = Instruction::initialize(0xFC, 0x15);
         let mut cpu: Cpu = Cpu::initialize();
         let mut memory: Memory = Memory::initialize();
-        let mut interface= Interface::default();
+        let mut framebuffer: Framebuffer = Framebuffer::initialize();
+        let keypad: Keypad = Keypad::initialize();
+        let quirks: Quirks = Quirks::DEFAULT;
 
         cpu.set_v_register(0xC, 0xFE);
 
         assert_eq!(0x0, cpu.get_delay_timer());
 
-        instruction.interpret(&mut cpu, &mut memory, &mut interface);
+        instruction.interpret(&mut cpu, &mut memory, &mut framebuffer, &keypad, &quirks).unwrap();
 
         assert_eq!(0xFE, cpu.get_delay_timer());
     }
@@ -1136,13 +2012,15 @@ mod tests {
         let mut instruction: Instruction = Instruction::initialize(0xFC, 0x18);
         let mut cpu: Cpu = Cpu::initialize();
         let mut memory: Memory = Memory::initialize();
-        let mut interface= Interface::default();
+        let mut framebuffer: Framebuffer = Framebuffer::initialize();
+        let keypad: Keypad = Keypad::initialize();
+        let quirks: Quirks = Quirks::DEFAULT;
 
         cpu.set_v_register(0xC, 0xFE);
 
         assert_eq!(0x0, cpu.get_sound_timer());
 
-        instruction.interpret(&mut cpu, &mut memory, &mut interface);
+        instruction.interpret(&mut cpu, &mut memory, &mut framebuffer, &keypad, &quirks).unwrap();
 
         assert_eq!(0xFE, cpu.get_sound_timer());
     }
@@ -1157,12 +2035,14 @@ mod tests {
         let mut instruction: Instruction = Instruction::initialize(0xFC, 0x1E);
         let mut cpu: Cpu = Cpu::initialize();
         let mut memory: Memory = Memory::initialize();
-        let mut interface= Interface::default();
+        let mut framebuffer: Framebuffer = Framebuffer::initialize();
+        let keypad: Keypad = Keypad::initialize();
+        let quirks: Quirks = Quirks::DEFAULT;
 
         cpu.set_i_register(i_register_value);
         cpu.set_v_register(0xC, v_register_value);
 
-        instruction.interpret(&mut cpu, &mut memory, &mut interface);
+        instruction.interpret(&mut cpu, &mut memory, &mut framebuffer, &keypad, &quirks).unwrap();
 
         assert_eq!(result, cpu.get_i_register());
     }
@@ -1183,35 +2063,95 @@ mod tests {
     #[test_case(0xD, 145 ; "with a D sprite")]
     #[test_case(0xE, 150 ; "with a E sprite")]
     #[test_case(0xF, 155 ; "with a F sprite")]
-    fn it_should_put_location_of_sprite_for_v_register_digit_on_i_register(register_number: u8, position: u16) {
+    fn it_should_put_location_of_sprite_for_v_register_digit_on_i_register(digit: u8, position: u16) {
+        let mut instruction: Instruction = Instruction::initialize(0xF3, 0x29);
+        let mut cpu: Cpu = Cpu::initialize();
+        let mut memory: Memory = Memory::initialize();
+        let mut framebuffer: Framebuffer = Framebuffer::initialize();
+        let keypad: Keypad = Keypad::initialize();
+        let quirks: Quirks = Quirks::DEFAULT;
+
+        cpu.set_v_register(0x3, digit);
+
+        instruction.interpret(&mut cpu, &mut memory, &mut framebuffer, &keypad, &quirks).unwrap();
+
+        assert_eq!(position, cpu.get_i_register());
+    }
+
+    #[test_case(0x0, 0x7 ; "with the digit in v0")]
+    #[test_case(0x7, 0x0 ; "with the digit in v7")]
+    #[test_case(0xE, 0x1A ; "with the high nibble ignored")]
+    fn it_should_take_the_sprite_digit_from_the_value_of_the_v_register(register_number: u8, value: u8) {
         let mut instruction: Instruction = Instruction::initialize(
-            0xF0 + register_number, 
+            0xF0 + register_number,
             0x29
         );
         let mut cpu: Cpu = Cpu::initialize();
         let mut memory: Memory = Memory::initialize();
-        let mut interface= Interface::default();
+        let mut framebuffer: Framebuffer = Framebuffer::initialize();
+        let keypad: Keypad = Keypad::initialize();
+        let quirks: Quirks = Quirks::DEFAULT;
+
+        cpu.set_v_register(register_number as usize, value);
+
+        instruction.interpret(&mut cpu, &mut memory, &mut framebuffer, &keypad, &quirks).unwrap();
+
+        assert_eq!(0x50 + u16::from(value & 0xF) * 5, cpu.get_i_register());
+    }
+
+    #[test_case(0x0, 0xA0 ; "with a 0 sprite")]
+    #[test_case(0x9, 0xFA ; "with a 9 sprite")]
+    #[test_case(0xF, 0x136 ; "with a F sprite")]
+    fn it_should_put_location_of_big_sprite_for_v_register_digit_on_i_register(digit: u8, position: u16) {
+        let mut instruction: Instruction = Instruction::initialize(0xF3, 0x30);
+        let mut cpu: Cpu = Cpu::initialize();
+        let mut memory: Memory = Memory::initialize();
+        let mut framebuffer: Framebuffer = Framebuffer::initialize();
+        let keypad: Keypad = Keypad::initialize();
+        let quirks: Quirks = Quirks::SUPER_CHIP;
 
-        instruction.interpret(&mut cpu, &mut memory, &mut interface);
+        cpu.set_v_register(0x3, digit);
+
+        instruction.interpret(&mut cpu, &mut memory, &mut framebuffer, &keypad, &quirks).unwrap();
 
         assert_eq!(position, cpu.get_i_register());
     }
 
+    #[test_case(Quirks::DEFAULT ; "with the default profile")]
+    #[test_case(Quirks::OCTO ; "with the octo profile")]
+    fn it_should_fail_to_put_location_of_big_sprite_without_the_super_chip_profile(quirks: Quirks) {
+        let mut instruction: Instruction = Instruction::initialize(0xF3, 0x30);
+        let mut cpu: Cpu = Cpu::initialize();
+        let mut memory: Memory = Memory::initialize();
+        let mut framebuffer: Framebuffer = Framebuffer::initialize();
+        let keypad: Keypad = Keypad::initialize();
+
+        cpu.set_program_counter(0x202);
+
+        assert_eq!(
+            Err(EmulatorError::UnknownOpcode { opcode: 0xF330, program_counter: 0x200 }),
+            instruction.interpret(&mut cpu, &mut memory, &mut framebuffer, &keypad, &quirks)
+        );
+        assert_eq!(0x0, cpu.get_i_register());
+    }
+
     #[test]
     fn it_should_put_bcd_representation_of_v_register_in_memory_locations_starting_on_i_register_location() {
         let mut instruction: Instruction = Instruction::initialize(0xFC, 0x33);
         let mut cpu: Cpu = Cpu::initialize();
         let mut memory: Memory = Memory::initialize();
-        let mut interface= Interface::default();
+        let mut framebuffer: Framebuffer = Framebuffer::initialize();
+        let keypad: Keypad = Keypad::initialize();
+        let quirks: Quirks = Quirks::DEFAULT;
 
         cpu.set_i_register(0x200);
         cpu.set_v_register(0xC, 198);
 
-        instruction.interpret(&mut cpu, &mut memory, &mut interface);
+        instruction.interpret(&mut cpu, &mut memory, &mut framebuffer, &keypad, &quirks).unwrap();
 
-        assert_eq!(1, memory.get(0x200));
-        assert_eq!(9, memory.get(0x201));
-        assert_eq!(8, memory.get(0x202));
+        assert_eq!(Ok(1), memory.get(0x200));
+        assert_eq!(Ok(9), memory.get(0x201));
+        assert_eq!(Ok(8), memory.get(0x202));
     }
 
     #[test]
@@ -1219,7 +2159,9 @@ mod tests {
         let mut instruction: Instruction = Instruction::initialize(0xFF, 0x55);
         let mut cpu: Cpu = Cpu::initialize();
         let mut memory: Memory = Memory::initialize();
-        let mut interface= Interface::default();
+        let mut framebuffer: Framebuffer = Framebuffer::initialize();
+        let keypad: Keypad = Keypad::initialize();
+        let quirks: Quirks = Quirks::DEFAULT;
 
         cpu.set_v_register(0x0, 0x10);
         cpu.set_v_register(0x1, 0x11);
@@ -1240,24 +2182,41 @@ mod tests {
 
         cpu.set_i_register(0x200);
 
-        instruction.interpret(&mut cpu, &mut memory, &mut interface);
-
-        assert_eq!(0x10, memory.get(0x200));
-        assert_eq!(0x11, memory.get(0x201));
-        assert_eq!(0x12, memory.get(0x202));
-        assert_eq!(0x13, memory.get(0x203));
-        assert_eq!(0x14, memory.get(0x204));
-        assert_eq!(0x15, memory.get(0x205));
-        assert_eq!(0x16, memory.get(0x206));
-        assert_eq!(0x17, memory.get(0x207));
-        assert_eq!(0x18, memory.get(0x208));
-        assert_eq!(0x19, memory.get(0x209));
-        assert_eq!(0x1A, memory.get(0x20A));
-        assert_eq!(0x1B, memory.get(0x20B));
-        assert_eq!(0x1C, memory.get(0x20C));
-        assert_eq!(0x1D, memory.get(0x20D));
-        assert_eq!(0x1E, memory.get(0x20E));
-        assert_eq!(0x1F, memory.get(0x20F));
+        instruction.interpret(&mut cpu, &mut memory, &mut framebuffer, &keypad, &quirks).unwrap();
+
+        assert_eq!(Ok(0x10), memory.get(0x200));
+        assert_eq!(Ok(0x11), memory.get(0x201));
+        assert_eq!(Ok(0x12), memory.get(0x202));
+        assert_eq!(Ok(0x13), memory.get(0x203));
+        assert_eq!(Ok(0x14), memory.get(0x204));
+        assert_eq!(Ok(0x15), memory.get(0x205));
+        assert_eq!(Ok(0x16), memory.get(0x206));
+        assert_eq!(Ok(0x17), memory.get(0x207));
+        assert_eq!(Ok(0x18), memory.get(0x208));
+        assert_eq!(Ok(0x19), memory.get(0x209));
+        assert_eq!(Ok(0x1A), memory.get(0x20A));
+        assert_eq!(Ok(0x1B), memory.get(0x20B));
+        assert_eq!(Ok(0x1C), memory.get(0x20C));
+        assert_eq!(Ok(0x1D), memory.get(0x20D));
+        assert_eq!(Ok(0x1E), memory.get(0x20E));
+        assert_eq!(Ok(0x1F), memory.get(0x20F));
+    }
+
+    #[test]
+    fn it_should_fail_to_put_values_of_v_registers_in_memory_out_of_range() {
+        let mut instruction: Instruction = Instruction::initialize(0xF3, 0x55);
+        let mut cpu: Cpu = Cpu::initialize();
+        let mut memory: Memory = Memory::initialize();
+        let mut framebuffer: Framebuffer = Framebuffer::initialize();
+        let keypad: Keypad = Keypad::initialize();
+        let quirks: Quirks = Quirks::DEFAULT;
+
+        cpu.set_i_register(0xFFE);
+
+        assert_eq!(
+            Err(EmulatorError::MemoryFault { address: 0x1000 }),
+            instruction.interpret(&mut cpu, &mut memory, &mut framebuffer, &keypad, &quirks)
+        );
     }
 
     #[test]
@@ -1265,28 +2224,30 @@ mod tests {
         let mut instruction: Instruction = Instruction::initialize(0xFF, 0x65);
         let mut cpu: Cpu = Cpu::initialize();
         let mut memory: Memory = Memory::initialize();
-        let mut interface= Interface::default();
-
-        memory.set(0x200, 0x10);
-        memory.set(0x201, 0x11);
-        memory.set(0x202, 0x12);
-        memory.set(0x203, 0x13);
-        memory.set(0x204, 0x14);
-        memory.set(0x205, 0x15);
-        memory.set(0x206, 0x16);
-        memory.set(0x207, 0x17);
-        memory.set(0x208, 0x18);
-        memory.set(0x209, 0x19);
-        memory.set(0x20A, 0x1A);
-        memory.set(0x20B, 0x1B);
-        memory.set(0x20C, 0x1C);
-        memory.set(0x20D, 0x1D);
-        memory.set(0x20E, 0x1E);
-        memory.set(0x20F, 0x1F);
+        let mut framebuffer: Framebuffer = Framebuffer::initialize();
+        let keypad: Keypad = Keypad::initialize();
+        let quirks: Quirks = Quirks::DEFAULT;
+
+        memory.set(0x200, 0x10).unwrap();
+        memory.set(0x201, 0x11).unwrap();
+        memory.set(0x202, 0x12).unwrap();
+        memory.set(0x203, 0x13).unwrap();
+        memory.set(0x204, 0x14).unwrap();
+        memory.set(0x205, 0x15).unwrap();
+        memory.set(0x206, 0x16).unwrap();
+        memory.set(0x207, 0x17).unwrap();
+        memory.set(0x208, 0x18).unwrap();
+        memory.set(0x209, 0x19).unwrap();
+        memory.set(0x20A, 0x1A).unwrap();
+        memory.set(0x20B, 0x1B).unwrap();
+        memory.set(0x20C, 0x1C).unwrap();
+        memory.set(0x20D, 0x1D).unwrap();
+        memory.set(0x20E, 0x1E).unwrap();
+        memory.set(0x20F, 0x1F).unwrap();
 
         cpu.set_i_register(0x200);
 
-        instruction.interpret(&mut cpu, &mut memory, &mut interface);
+        instruction.interpret(&mut cpu, &mut memory, &mut framebuffer, &keypad, &quirks).unwrap();
 
         assert_eq!(0x10, cpu.get_v_register(0x0));
         assert_eq!(0x11, cpu.get_v_register(0x1));
@@ -1305,4 +2266,88 @@ mod tests {
         assert_eq!(0x1E, cpu.get_v_register(0xE));
         assert_eq!(0x1F, cpu.get_v_register(0xF));
     }
+
+    #[test_case(0x55, Quirks::DEFAULT, 0x300 ; "with store leaving i unchanged")]
+    #[test_case(0x55, Quirks::CHIP_48, 0x303 ; "with store increasing i by x")]
+    #[test_case(0x55, Quirks::COSMAC_VIP, 0x304 ; "with store increasing i by x plus one")]
+    #[test_case(0x65, Quirks::DEFAULT, 0x300 ; "with load leaving i unchanged")]
+    #[test_case(0x65, Quirks::CHIP_48, 0x303 ; "with load increasing i by x")]
+    #[test_case(0x65, Quirks::COSMAC_VIP, 0x304 ; "with load increasing i by x plus one")]
+    #[test_case(0x55, Quirks::XO_CHIP, 0x304 ; "with store on xo-chip")]
+    fn it_should_update_the_i_register_after_load_or_store_following_the_quirk(
+        second_byte: u8,
+        quirks: Quirks,
+        i_register: u16,
+    ) {
+        let mut instruction: Instruction = Instruction::initialize(0xF3, second_byte);
+        let mut cpu: Cpu = Cpu::initialize();
+        let mut memory: Memory = Memory::initialize();
+        let mut framebuffer: Framebuffer = Framebuffer::initialize();
+        let keypad: Keypad = Keypad::initialize();
+
+        cpu.set_i_register(0x300);
+
+        instruction.interpret(&mut cpu, &mut memory, &mut framebuffer, &keypad, &quirks).unwrap();
+
+        assert_eq!(i_register, cpu.get_i_register());
+    }
+
+    #[test]
+    fn it_should_put_values_of_v_registers_from_v0_to_passed_v_register_in_rpl_flags() {
+        let mut instruction: Instruction = Instruction::initialize(0xF7, 0x75);
+        let mut cpu: Cpu = Cpu::initialize();
+        let mut memory: Memory = Memory::initialize();
+        let mut framebuffer: Framebuffer = Framebuffer::initialize();
+        let keypad: Keypad = Keypad::initialize();
+        let quirks: Quirks = Quirks::SUPER_CHIP;
+
+        for register_number in 0x0..=0x8 {
+            cpu.set_v_register(register_number, 0x10 + register_number as u8);
+        }
+
+        instruction.interpret(&mut cpu, &mut memory, &mut framebuffer, &keypad, &quirks).unwrap();
+
+        assert_eq!(
+            [0x10, 0x11, 0x12, 0x13, 0x14, 0x15, 0x16, 0x17, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0],
+            cpu.get_rpl_flags()
+        );
+    }
+
+    #[test]
+    fn it_should_put_values_on_v_registers_from_v0_to_passed_v_register_from_rpl_flags() {
+        let mut instruction: Instruction = Instruction::initialize(0xFF, 0x85);
+        let mut cpu: Cpu = Cpu::initialize();
+        let mut memory: Memory = Memory::initialize();
+        let mut framebuffer: Framebuffer = Framebuffer::initialize();
+        let keypad: Keypad = Keypad::initialize();
+        let quirks: Quirks = Quirks::DEFAULT;
+
+        cpu.set_rpl_flags([
+            0x10, 0x11, 0x12, 0x13, 0x14, 0x15, 0x16, 0x17, 0x18, 0x19, 0x1A, 0x1B, 0x1C, 0x1D, 0x1E, 0x1F
+        ]);
+
+        instruction.interpret(&mut cpu, &mut memory, &mut framebuffer, &keypad, &quirks).unwrap();
+
+        for register_number in 0x0..=0xF {
+            assert_eq!(0x10 + register_number as u8, cpu.get_v_register(register_number));
+        }
+    }
+
+    #[test_case(0x75 ; "with store")]
+    #[test_case(0x85 ; "with load")]
+    fn it_should_fail_to_access_more_rpl_flags_than_available(second_byte: u8) {
+        let mut instruction: Instruction = Instruction::initialize(0xF8, second_byte);
+        let mut cpu: Cpu = Cpu::initialize();
+        let mut memory: Memory = Memory::initialize();
+        let mut framebuffer: Framebuffer = Framebuffer::initialize();
+        let keypad: Keypad = Keypad::initialize();
+        let quirks: Quirks = Quirks::SUPER_CHIP;
+
+        cpu.set_program_counter(0x202);
+
+        assert_eq!(
+            Err(EmulatorError::UnknownOpcode { opcode: 0xF800 | u16::from(second_byte), program_counter: 0x200 }),
+            instruction.interpret(&mut cpu, &mut memory, &mut framebuffer, &keypad, &quirks)
+        );
+    }
 }
\ No newline at end of file
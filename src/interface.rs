@@ -1,87 +1,80 @@
-
-use std::collections::HashSet;
-
 use crate::keyboard::Keyboard;
-use minifb::{Window, WindowOptions, KeyRepeat, Key};
-
-#[cfg(test)]
-use mockall::automock;
+use chip8rs::error::EmulatorError;
+use chip8rs::framebuffer::Framebuffer;
+use chip8rs::frontend::{Display, Hotkey, Input};
+use chip8rs::palette::Palette;
+use minifb::{Key, KeyRepeat, Window, WindowOptions};
 
 pub struct Interface {
     window: Window,
     buffer: Vec<u32>,
     keyboard: Keyboard,
+    palette: Palette,
 }
 
-#[cfg_attr(test, automock)]
 impl Interface {
     pub const TITLE: &'static str = "Chip-8";
-    pub const WIDTH: usize = 64;
-    pub const HEIGHT: usize = 32;
-    pub const BLACK: u32 = 0;
-    pub const WHITE: u32 = 16777215;
 
-    pub fn initialize() -> Self {
-        let window = Window::new(
+    pub fn initialize(palette: Palette) -> Result<Self, minifb::Error> {
+        let mut window = Window::new(
             Interface::TITLE, 
-            Interface::WIDTH * 10, 
-            Interface::HEIGHT * 10, 
-        WindowOptions::default()).unwrap_or_else(|e| {
-            panic!("{}", e);
-        });
+            Framebuffer::HIGH_RESOLUTION_WIDTH * 5, 
+            Framebuffer::HIGH_RESOLUTION_HEIGHT * 5, 
+            WindowOptions::default()
+        )?;
 
-        Interface {
-            window: window,
-            buffer: vec![0; Interface::WIDTH * Interface::HEIGHT],
+        // The frames are already paced by the motherboard's scheduler.
+        window.limit_update_rate(None);
+
+        Ok(Interface {
+            window,
+            buffer: vec![palette.get_colour(0); Framebuffer::WIDTH * Framebuffer::HEIGHT],
             keyboard: Keyboard::initialize(),
-        }
+            palette,
+        })
     }
+}
 
-    pub fn window_is_open(&self) -> bool {
+impl Display for Interface {
+    fn is_open(&self) -> bool {
         self.window.is_open()
     }
 
-    pub fn refresh(&mut self) {
-        self.window.update_with_buffer(&self.buffer, Interface::WIDTH, Interface::HEIGHT)
-            .expect("The pixels on screen could not be updated.");
-    }
-
-    pub fn clear(&mut self) {
-        self.buffer = vec!(Interface::BLACK; Interface::WIDTH * Interface::HEIGHT);
-    }
-
-    pub fn draw_pixel(&mut self, state: bool, x: usize, y: usize) -> bool {
-        let index: usize = x + (y * Interface::WIDTH);
+    fn refresh(&mut self, framebuffer: &Framebuffer) -> Result<(), EmulatorError> {
+        // The buffer follows the resolution of the framebuffer, and minifb
+        // stretches it to the size of the window.
+        self.buffer.resize(framebuffer.get_width() * framebuffer.get_height(), self.palette.get_colour(0));
 
-        let old_state: bool = self.buffer[index] == Interface::WHITE;
-
-        let color: u32 = match old_state != state {
-            true => Interface::WHITE,
-            false => Interface::BLACK,
-        };
-
-        self.buffer[index] = color;
+        for (index, colour) in framebuffer.get_colours().into_iter().enumerate() {
+            self.buffer[index] = self.palette.get_colour(colour);
+        }
 
-        old_state == true && state == false
+        self.window.update_with_buffer(&self.buffer, framebuffer.get_width(), framebuffer.get_height())
+            .map_err(|error| EmulatorError::DisplayOutput { message: error.to_string() })
     }
+}
 
-    pub fn is_pressed(&self, key_code: usize) -> bool {
-        self.window.is_key_down(
+impl Input for Interface {
+    fn is_pressed(&self, key_code: usize) -> bool {
+        key_code < self.keyboard.get_keys().len() && self.window.is_key_down(
             self.keyboard.get_key(key_code)
         )
     }
 
-    pub fn is_not_pressed(&self, key_code: usize) -> bool {
-        self.is_pressed(key_code) == false
-    }
-
-    pub fn get_pressed_key(&self) -> Option<usize> {
-        for (index, _key) in self.keyboard.get_keys().iter().enumerate() {
-            if self.is_pressed(index) {
-                return Some(index);
-            }
+    // Rewinding goes on for as long as its key is held, while a function key
+    // loads its slot once, or saves it when shift is held.
+    fn get_hotkey(&self) -> Option<Hotkey> {
+        if self.window.is_key_down(self.keyboard.get_rewind_key()) {
+            return Some(Hotkey::Rewind);
         }
 
-        return None;
+        let slot: usize = self.window.get_keys_pressed(KeyRepeat::No)
+            .into_iter()
+            .find_map(|key| self.keyboard.get_slot(key))?;
+
+        match self.window.is_key_down(Key::LeftShift) || self.window.is_key_down(Key::RightShift) {
+            true => Some(Hotkey::SaveState(slot)),
+            false => Some(Hotkey::LoadState(slot)),
+        }
     }
-}
\ No newline at end of file
+}
@@ -2,6 +2,7 @@ use minifb::Key;
 
 pub struct Keyboard {
     keys: [Key; 16],
+    slot_keys: [Key; 9],
 }
 
 impl Keyboard {
@@ -25,6 +26,17 @@ impl Keyboard {
                 Key::F,
                 Key::V,
             ],
+            slot_keys: [
+                Key::F1,
+                Key::F2,
+                Key::F3,
+                Key::F4,
+                Key::F5,
+                Key::F6,
+                Key::F7,
+                Key::F8,
+                Key::F9,
+            ],
         }
     }
 
@@ -35,6 +47,17 @@ impl Keyboard {
     pub fn get_key(&self, index: usize) -> Key {
         self.keys[index]
     }
+
+    pub fn get_rewind_key(&self) -> Key {
+        Key::Backspace
+    }
+
+    // F1 to F9 pick the save state slots 1 to 9.
+    pub fn get_slot(&self, key: Key) -> Option<usize> {
+        self.slot_keys.iter()
+            .position(|slot_key| *slot_key == key)
+            .map(|index| index + 1)
+    }
 }
 
 #[cfg(test)]
@@ -108,4 +131,14 @@ mod tests {
         assert_eq!(keyboard.get_key(0xE), Key::F);
         assert_eq!(keyboard.get_key(0xF), Key::V);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn it_should_get_a_slot() {
+        let keyboard: Keyboard = Keyboard::initialize();
+
+        assert_eq!(keyboard.get_slot(Key::F1), Some(1));
+        assert_eq!(keyboard.get_slot(Key::F9), Some(9));
+        assert_eq!(keyboard.get_slot(Key::F10), None);
+        assert_eq!(keyboard.get_slot(Key::X), None);
+    }
+}
@@ -0,0 +1,78 @@
+pub struct Keypad {
+    keys: [bool; Keypad::KEYS],
+}
+
+impl Keypad {
+    pub const KEYS: usize = 16;
+
+    pub fn initialize() -> Self {
+        Keypad {
+            keys: [false; Keypad::KEYS],
+        }
+    }
+
+    pub fn set_key(&mut self, key_code: usize, pressed: bool) {
+        self.keys[key_code] = pressed;
+    }
+
+    pub fn is_pressed(&self, key_code: usize) -> bool {
+        self.keys.get(key_code).copied().unwrap_or(false)
+    }
+
+    pub fn is_not_pressed(&self, key_code: usize) -> bool {
+        !self.is_pressed(key_code)
+    }
+
+    pub fn get_pressed_key(&self) -> Option<usize> {
+        self.keys.iter().position(|pressed| *pressed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_should_initialize_the_keypad() {
+        let keypad: Keypad = Keypad::initialize();
+
+        assert_eq!([false; 16], keypad.keys);
+    }
+
+    #[test]
+    fn it_should_set_a_key() {
+        let mut keypad: Keypad = Keypad::initialize();
+
+        keypad.set_key(0xA, true);
+
+        assert!(keypad.keys[0xA]);
+
+        keypad.set_key(0xA, false);
+
+        assert!(!keypad.keys[0xA]);
+    }
+
+    #[test]
+    fn it_should_check_if_a_key_is_pressed() {
+        let mut keypad: Keypad = Keypad::initialize();
+
+        keypad.keys[0x3] = true;
+
+        assert!(keypad.is_pressed(0x3));
+        assert!(!keypad.is_not_pressed(0x3));
+        assert!(!keypad.is_pressed(0x4));
+        assert!(keypad.is_not_pressed(0x4));
+        assert!(!keypad.is_pressed(0x10));
+    }
+
+    #[test]
+    fn it_should_get_the_pressed_key() {
+        let mut keypad: Keypad = Keypad::initialize();
+
+        assert_eq!(None, keypad.get_pressed_key());
+
+        keypad.keys[0xB] = true;
+
+        assert_eq!(Some(0xB), keypad.get_pressed_key());
+    }
+}
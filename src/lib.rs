@@ -0,0 +1,25 @@
+pub mod analysis;
+pub mod assembler;
+pub mod audio;
+pub mod cpu;
+pub mod debugger;
+pub mod disassembler;
+pub mod error;
+pub mod flags;
+pub mod framebuffer;
+pub mod frontend;
+pub mod gdb;
+pub mod instruction;
+pub mod keypad;
+pub mod machine;
+pub mod memory;
+pub mod motherboard;
+pub mod op;
+pub mod palette;
+pub mod policy;
+pub mod quirks;
+pub mod random;
+pub mod rewind;
+pub mod scheduler;
+pub mod state;
+pub mod watchpoint;
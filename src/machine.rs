@@ -0,0 +1,1085 @@
+use crate::cpu::Cpu;
+use crate::error::EmulatorError;
+use crate::framebuffer::Framebuffer;
+use crate::keypad::Keypad;
+use crate::memory::Memory;
+use crate::policy::UnknownOpcodePolicy;
+use crate::quirks::Quirks;
+use crate::rewind::RewindBuffer;
+use crate::state::{StateReader, StateWriter};
+use crate::watchpoint::{Trigger, Watchpoint};
+use std::collections::BTreeSet;
+
+pub struct Machine {
+    cpu: Cpu,
+    memory: Memory,
+    framebuffer: Framebuffer,
+    keypad: Keypad,
+    instructions_per_frame: usize,
+    unknown_opcode_policy: UnknownOpcodePolicy,
+    quirks: Quirks,
+    paused: bool,
+    rewind_buffer: RewindBuffer,
+    breakpoints: BTreeSet<usize>,
+    // Lets the instruction the machine was paused on run after resuming,
+    // instead of hitting its breakpoint again.
+    skip_breakpoint: bool,
+    // Pauses the machine once the stack gets back to this many calls, for
+    // stepping over and out of subroutines.
+    stack_depth_target: Option<usize>,
+    watchpoints: Vec<Watchpoint>,
+    // What set off a watchpoint on the last instruction.
+    trigger: Option<Trigger>,
+}
+
+impl Machine {
+    // 12 instructions on each of the 60 frames per second gets close to the
+    // ~700 instructions per second most ROMs were written for.
+    pub const INSTRUCTIONS_PER_FRAME: usize = 12;
+
+    pub fn initialize() -> Self {
+        let mut memory: Memory = Memory::initialize();
+
+        memory.set_font();
+
+        Machine {
+            cpu: Cpu::initialize(),
+            memory,
+            framebuffer: Framebuffer::initialize(),
+            keypad: Keypad::initialize(),
+            instructions_per_frame: Machine::INSTRUCTIONS_PER_FRAME,
+            unknown_opcode_policy: UnknownOpcodePolicy::Ignore,
+            quirks: Quirks::DEFAULT,
+            paused: false,
+            rewind_buffer: RewindBuffer::initialize(0),
+            breakpoints: BTreeSet::new(),
+            skip_breakpoint: false,
+            stack_depth_target: None,
+            watchpoints: Vec::new(),
+            trigger: None,
+        }
+    }
+
+    pub fn load_rom(&mut self, program: Vec<u8>) -> Result<(), EmulatorError> {
+        self.memory.store_program(program)
+    }
+
+    pub fn step(&mut self) -> Result<(), EmulatorError> {
+        self.trigger = None;
+
+        let result: Result<(), EmulatorError> = self.cpu.fetch_and_decode(
+            &mut self.memory,
+            &mut self.framebuffer,
+            &self.keypad,
+            &self.quirks
+        );
+
+        if !self.watchpoints.is_empty() {
+            self.check_watchpoints();
+        }
+
+        match result {
+            Err(EmulatorError::UnknownOpcode { opcode, program_counter }) => {
+                self.handle_unknown_opcode(opcode, program_counter)
+            },
+            _ => result,
+        }
+    }
+
+    fn check_watchpoints(&mut self) {
+        let memory_trigger: Option<Trigger> = self.memory.take_trigger();
+        let cpu_trigger: Option<Trigger> = self.cpu.take_trigger();
+
+        self.trigger = memory_trigger.or(cpu_trigger);
+
+        if self.trigger.is_some() {
+            self.pause();
+        }
+    }
+
+    fn handle_unknown_opcode(&mut self, opcode: u16, program_counter: usize) -> Result<(), EmulatorError> {
+        match self.unknown_opcode_policy {
+            UnknownOpcodePolicy::Ignore => Ok(()),
+            UnknownOpcodePolicy::Warn => {
+                eprintln!("Warning: skipping unknown opcode {:04X} at {:#05X}.", opcode, program_counter);
+
+                Ok(())
+            },
+            UnknownOpcodePolicy::Halt => Err(EmulatorError::UnknownOpcode { opcode, program_counter }),
+            UnknownOpcodePolicy::Break => {
+                self.paused = true;
+
+                Ok(())
+            },
+        }
+    }
+
+    pub fn run_frame(&mut self) -> Result<(), EmulatorError> {
+        if self.paused || self.is_halted() {
+            return Ok(());
+        }
+
+        if self.rewind_buffer.get_capacity() > 0 {
+            self.rewind_buffer.push(self.save_state());
+        }
+
+        for _ in 0..self.instructions_per_frame {
+            if self.is_on_breakpoint() {
+                self.pause();
+
+                return Ok(());
+            }
+
+            let is_drawing: bool = matches!(self.current_opcode(), Some(opcode) if opcode & 0xF000 == 0xD000);
+
+            self.step()?;
+            self.skip_breakpoint = false;
+
+            if matches!(self.stack_depth_target, Some(depth) if self.cpu.get_stack().len() <= depth) {
+                self.stack_depth_target = None;
+                self.paused = true;
+            }
+
+            if self.paused || self.is_halted() {
+                return Ok(());
+            }
+
+            // The original interpreter waited for the vertical blank before
+            // drawing, so only one sprite could be drawn on each frame.
+            if is_drawing && self.quirks.wait_for_display {
+                break;
+            }
+        }
+
+        self.cpu.decrease_timers_on_tick();
+
+        Ok(())
+    }
+
+    pub fn run_frames(&mut self, frames: usize) -> Result<(), EmulatorError> {
+        self.run_until(frames, |_| false)?;
+
+        Ok(())
+    }
+
+    pub fn run_until<F: FnMut(&Machine) -> bool>(
+        &mut self,
+        frames: usize,
+        mut condition: F,
+    ) -> Result<usize, EmulatorError> {
+        for frame in 1..=frames {
+            self.run_frame()?;
+
+            if self.paused || self.is_halted() || condition(self) {
+                return Ok(frame);
+            }
+        }
+
+        Ok(frames)
+    }
+
+    pub fn is_idle(&self) -> bool {
+        let program_counter: usize = self.cpu.get_program_counter();
+
+        self.current_opcode() == Some(0x1000 | program_counter as u16)
+    }
+
+    pub fn current_opcode(&self) -> Option<u16> {
+        let program_counter: usize = self.cpu.get_program_counter();
+
+        match (self.memory.peek(program_counter), self.memory.peek(program_counter + 1)) {
+            (Ok(first_byte), Ok(second_byte)) => Some(u16::from(first_byte) << 8 | u16::from(second_byte)),
+            _ => None,
+        }
+    }
+
+    pub fn is_halted(&self) -> bool {
+        self.cpu.is_halted()
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    // Pausing also gives up on getting out of a subroutine.
+    pub fn pause(&mut self) {
+        self.paused = true;
+        self.stack_depth_target = None;
+    }
+
+    pub fn resume(&mut self) {
+        self.paused = false;
+        self.skip_breakpoint = true;
+    }
+
+    // Runs a whole subroutine when the next instruction calls one, once the
+    // machine is resumed, and a single instruction otherwise.
+    pub fn step_over(&mut self) -> Result<(), EmulatorError> {
+        match self.current_opcode() {
+            Some(opcode) if opcode & 0xF000 == 0x2000 => {
+                self.stack_depth_target = Some(self.cpu.get_stack().len());
+                self.resume();
+
+                Ok(())
+            },
+            _ => self.step(),
+        }
+    }
+
+    // Runs until the current subroutine returns, once the machine is resumed.
+    // Returns false when there is no subroutine to get out of.
+    pub fn step_out(&mut self) -> bool {
+        match self.cpu.get_stack().len() {
+            0 => false,
+            depth => {
+                self.stack_depth_target = Some(depth - 1);
+                self.resume();
+
+                true
+            },
+        }
+    }
+
+    pub fn add_breakpoint(&mut self, address: usize) {
+        self.breakpoints.insert(address);
+    }
+
+    pub fn remove_breakpoint(&mut self, address: usize) -> bool {
+        self.breakpoints.remove(&address)
+    }
+
+    pub fn get_breakpoints(&self) -> Vec<usize> {
+        self.breakpoints.iter().copied().collect()
+    }
+
+    pub fn add_watchpoint(&mut self, watchpoint: Watchpoint) {
+        self.watchpoints.push(watchpoint);
+
+        self.apply_watchpoints();
+    }
+
+    pub fn remove_watchpoint(&mut self, index: usize) -> Option<Watchpoint> {
+        if index >= self.watchpoints.len() {
+            return None;
+        }
+
+        let watchpoint: Watchpoint = self.watchpoints.remove(index);
+
+        self.apply_watchpoints();
+
+        Some(watchpoint)
+    }
+
+    pub fn get_watchpoints(&self) -> &[Watchpoint] {
+        &self.watchpoints
+    }
+
+    pub fn get_trigger(&self) -> Option<Trigger> {
+        self.trigger
+    }
+
+    // The memory and the CPU only get the watchpoints they can set off, so
+    // they skip watching altogether when they have none.
+    fn apply_watchpoints(&mut self) {
+        let (memory_watchpoints, cpu_watchpoints): (Vec<Watchpoint>, Vec<Watchpoint>) = self.watchpoints
+            .iter()
+            .partition(|watchpoint| watchpoint.is_on_memory());
+
+        self.memory.set_watchpoints(memory_watchpoints);
+        self.cpu.set_watchpoints(cpu_watchpoints);
+    }
+
+    fn is_on_breakpoint(&self) -> bool {
+        !self.skip_breakpoint
+            && !self.breakpoints.is_empty()
+            && self.breakpoints.contains(&self.cpu.get_program_counter())
+    }
+
+    pub fn set_unknown_opcode_policy(&mut self, policy: UnknownOpcodePolicy) {
+        self.unknown_opcode_policy = policy;
+    }
+
+    pub fn get_unknown_opcode_policy(&self) -> UnknownOpcodePolicy {
+        self.unknown_opcode_policy
+    }
+
+    pub fn set_quirks(&mut self, quirks: Quirks) {
+        self.quirks = quirks;
+
+        self.memory.set_extended(quirks.xo_chip);
+        self.memory.set_big_font(quirks.super_chip);
+    }
+
+    pub fn get_quirks(&self) -> Quirks {
+        self.quirks
+    }
+
+    pub fn set_instructions_per_frame(&mut self, amount: usize) {
+        self.instructions_per_frame = amount;
+    }
+
+    pub fn get_instructions_per_frame(&self) -> usize {
+        self.instructions_per_frame
+    }
+
+    pub fn set_decode_cache(&mut self, enabled: bool) {
+        self.memory.set_decode_cache(enabled);
+    }
+
+    pub fn is_decode_cache_enabled(&self) -> bool {
+        self.memory.is_decode_cache_enabled()
+    }
+
+    pub fn set_rpl_flags(&mut self, flags: [u8; Cpu::RPL_FLAGS]) {
+        self.cpu.set_rpl_flags(flags);
+    }
+
+    pub fn set_random_seed(&mut self, seed: u64) {
+        self.cpu.set_random_state(seed);
+    }
+
+    // The configuration of the emulator (speed and unknown opcode policy) and
+    // the keypad are left out, as they belong to the user and not to the game.
+    pub fn save_state(&self) -> Vec<u8> {
+        let mut writer: StateWriter = StateWriter::initialize();
+
+        self.quirks.save_state(&mut writer);
+        self.cpu.save_state(&mut writer);
+        self.memory.save_state(&mut writer);
+        self.framebuffer.save_state(&mut writer);
+
+        writer.into_bytes()
+    }
+
+    pub fn load_state(&mut self, state: &[u8]) -> Result<(), EmulatorError> {
+        let mut reader: StateReader = StateReader::initialize(state)?;
+
+        let quirks: Quirks = Quirks::load_state(&mut reader)?;
+        let cpu: Cpu = Cpu::load_state(&mut reader)?;
+        let mut memory: Memory = Memory::load_state(&mut reader)?;
+        let framebuffer: Framebuffer = Framebuffer::load_state(&mut reader)?;
+
+        reader.finish()?;
+
+        if quirks.xo_chip != (memory.get_size() == Memory::EXTENDED_SIZE) {
+            return Err(EmulatorError::InvalidState);
+        }
+
+        // The cache isn't part of the state, it just carries on empty.
+        memory.set_decode_cache(self.memory.is_decode_cache_enabled());
+
+        self.quirks = quirks;
+        self.cpu = cpu;
+        self.memory = memory;
+        self.framebuffer = framebuffer;
+
+        self.apply_watchpoints();
+
+        Ok(())
+    }
+
+    // Goes back to the start of the last frames run, as far as the rewind
+    // buffer remembers, and returns how many frames were undone.
+    pub fn rewind(&mut self, frames: usize) -> Result<usize, EmulatorError> {
+        let mut state: Option<Vec<u8>> = None;
+        let mut rewound: usize = 0;
+
+        while rewound < frames {
+            match self.rewind_buffer.pop() {
+                Some(previous) => state = Some(previous),
+                None => break,
+            }
+
+            rewound += 1;
+        }
+
+        if let Some(state) = state {
+            self.load_state(&state)?;
+        }
+
+        Ok(rewound)
+    }
+
+    pub fn set_rewind_frames(&mut self, frames: usize) {
+        self.rewind_buffer.set_capacity(frames);
+    }
+
+    pub fn get_rewind_frames(&self) -> usize {
+        self.rewind_buffer.get_capacity()
+    }
+
+    pub fn set_key(&mut self, key_code: usize, pressed: bool) {
+        self.keypad.set_key(key_code, pressed);
+    }
+
+    pub fn framebuffer(&self) -> &Framebuffer {
+        &self.framebuffer
+    }
+
+    pub fn cpu(&self) -> &Cpu {
+        &self.cpu
+    }
+
+    pub fn memory(&self) -> &Memory {
+        &self.memory
+    }
+
+    pub fn cpu_mut(&mut self) -> &mut Cpu {
+        &mut self.cpu
+    }
+
+    pub fn memory_mut(&mut self) -> &mut Memory {
+        &mut self.memory
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+    use super::*;
+
+    #[test]
+    fn it_should_initialize_the_machine_with_the_font_loaded() {
+        let machine: Machine = Machine::initialize();
+
+        for (index, byte) in Memory::FONT.iter().cloned().enumerate() {
+            assert_eq!(byte, machine.memory.get(0x50 + index).unwrap());
+        }
+
+        assert_eq!(0x200, machine.cpu.get_program_counter());
+    }
+
+    #[test_case(Quirks::DEFAULT, false ; "without the super-chip profile")]
+    #[test_case(Quirks::SUPER_CHIP, true ; "with the super-chip profile")]
+    #[test_case(Quirks::XO_CHIP, true ; "with the xo-chip profile")]
+    fn it_should_load_the_big_font_only_on_the_super_chip_profiles(quirks: Quirks, is_loaded: bool) {
+        let mut machine: Machine = Machine::initialize();
+
+        machine.set_quirks(quirks);
+
+        for (index, byte) in Memory::BIG_FONT.iter().cloned().enumerate() {
+            let expected: u8 = match is_loaded {
+                true => byte,
+                false => 0x0,
+            };
+
+            assert_eq!(expected, machine.memory.get(0xA0 + index).unwrap());
+        }
+    }
+
+    #[test]
+    fn it_should_load_a_rom() {
+        let mut machine: Machine = Machine::initialize();
+
+        machine.load_rom(vec![0x60, 0xCA]).unwrap();
+
+        assert_eq!(Ok(0x60), machine.memory.get(0x200));
+        assert_eq!(Ok(0xCA), machine.memory.get(0x201));
+    }
+
+    #[test]
+    fn it_should_fail_to_load_a_rom_too_large() {
+        let mut machine: Machine = Machine::initialize();
+
+        assert_eq!(
+            Err(EmulatorError::RomTooLarge { size: 0xE01, capacity: 0xE00 }),
+            machine.load_rom(vec![0x0; 0xE01])
+        );
+    }
+
+    #[test]
+    fn it_should_load_a_large_rom_with_the_xo_chip_extensions() {
+        let mut machine: Machine = Machine::initialize();
+
+        machine.set_quirks(Quirks::XO_CHIP);
+
+        machine.load_rom(vec![0x7; 0x2000]).unwrap();
+
+        assert_eq!(Ok(0x7), machine.memory.get(0x21FF));
+    }
+
+    #[test]
+    fn it_should_step_a_single_instruction() {
+        let mut machine: Machine = Machine::initialize();
+
+        machine.load_rom(vec![0x60, 0xCA, 0x61, 0xFE]).unwrap();
+
+        machine.step().unwrap();
+
+        assert_eq!(0xCA, machine.cpu.get_v_register(0x0));
+        assert_eq!(0x0, machine.cpu.get_v_register(0x1));
+        assert_eq!(0x202, machine.cpu.get_program_counter());
+    }
+
+    #[test]
+    fn it_should_run_a_frame() {
+        let mut machine: Machine = Machine::initialize();
+
+        machine.load_rom(vec![0x70, 0x01, 0x12, 0x00]).unwrap();
+
+        machine.run_frame().unwrap();
+
+        assert_eq!((Machine::INSTRUCTIONS_PER_FRAME / 2) as u8, machine.cpu.get_v_register(0x0));
+    }
+
+    #[test]
+    fn it_should_stop_running_a_frame_on_an_error() {
+        let mut machine: Machine = Machine::initialize();
+
+        machine.load_rom(vec![0x70, 0x01, 0x00, 0xEE, 0x70, 0x01]).unwrap();
+
+        assert_eq!(Err(EmulatorError::StackUnderflow), machine.run_frame());
+        assert_eq!(0x1, machine.cpu.get_v_register(0x0));
+    }
+
+    #[test_case(UnknownOpcodePolicy::Ignore ; "with the ignore policy")]
+    #[test_case(UnknownOpcodePolicy::Warn ; "with the warn policy")]
+    fn it_should_skip_unknown_opcodes(policy: UnknownOpcodePolicy) {
+        let mut machine: Machine = Machine::initialize();
+
+        machine.load_rom(vec![0x01, 0x23, 0x60, 0xCA]).unwrap();
+
+        machine.set_unknown_opcode_policy(policy);
+        machine.step().unwrap();
+        machine.step().unwrap();
+
+        assert_eq!(0xCA, machine.cpu.get_v_register(0x0));
+        assert!(!machine.is_paused());
+    }
+
+    #[test]
+    fn it_should_halt_on_unknown_opcodes() {
+        let mut machine: Machine = Machine::initialize();
+
+        machine.load_rom(vec![0x60, 0xCA, 0x01, 0x23]).unwrap();
+
+        machine.set_unknown_opcode_policy(UnknownOpcodePolicy::Halt);
+
+        assert_eq!(
+            Err(EmulatorError::UnknownOpcode { opcode: 0x0123, program_counter: 0x202 }),
+            machine.run_frame()
+        );
+    }
+
+    #[test]
+    fn it_should_break_on_unknown_opcodes() {
+        let mut machine: Machine = Machine::initialize();
+
+        machine.load_rom(vec![0x01, 0x23, 0x60, 0xCA, 0x12, 0x04]).unwrap();
+
+        machine.set_unknown_opcode_policy(UnknownOpcodePolicy::Break);
+        machine.run_frame().unwrap();
+
+        assert!(machine.is_paused());
+        assert_eq!(0x202, machine.cpu.get_program_counter());
+
+        machine.run_frame().unwrap();
+
+        assert_eq!(0x202, machine.cpu.get_program_counter());
+
+        machine.resume();
+        machine.run_frame().unwrap();
+
+        assert_eq!(0xCA, machine.cpu.get_v_register(0x0));
+    }
+
+    #[test]
+    fn it_should_stop_running_until_a_condition_when_paused() {
+        let mut machine: Machine = Machine::initialize();
+
+        machine.load_rom(vec![0x01, 0x23, 0x12, 0x02]).unwrap();
+
+        machine.set_unknown_opcode_policy(UnknownOpcodePolicy::Break);
+
+        assert_eq!(Ok(1), machine.run_until(100, |_| false));
+    }
+
+    #[test]
+    fn it_should_run_a_configured_amount_of_instructions_per_frame() {
+        let mut machine: Machine = Machine::initialize();
+
+        machine.load_rom(vec![0x70, 0x01, 0x12, 0x00]).unwrap();
+
+        machine.set_instructions_per_frame(40);
+        machine.run_frame().unwrap();
+
+        assert_eq!(40, machine.get_instructions_per_frame());
+        assert_eq!(20, machine.cpu.get_v_register(0x0));
+    }
+
+    #[test]
+    fn it_should_not_decrease_the_timers_when_stepping() {
+        let mut machine: Machine = Machine::initialize();
+
+        machine.load_rom(vec![0x12, 0x00]).unwrap();
+
+        machine.cpu.set_delay_timer(0x5);
+        machine.step().unwrap();
+
+        assert_eq!(0x5, machine.cpu.get_delay_timer());
+    }
+
+    #[test]
+    fn it_should_decrease_the_timers_once_per_frame() {
+        let mut machine: Machine = Machine::initialize();
+
+        machine.load_rom(vec![0x12, 0x00]).unwrap();
+
+        machine.cpu.set_delay_timer(0x5);
+        machine.cpu.set_sound_timer(0x3);
+        machine.run_frame().unwrap();
+
+        assert_eq!(0x4, machine.cpu.get_delay_timer());
+        assert_eq!(0x2, machine.cpu.get_sound_timer());
+    }
+
+    #[test]
+    fn it_should_run_multiple_frames() {
+        let mut machine: Machine = Machine::initialize();
+
+        machine.load_rom(vec![0x70, 0x01, 0x12, 0x00]).unwrap();
+
+        machine.run_frames(3).unwrap();
+
+        assert_eq!((3 * Machine::INSTRUCTIONS_PER_FRAME / 2) as u8, machine.cpu.get_v_register(0x0));
+    }
+
+    #[test]
+    fn it_should_run_until_a_condition_is_met() {
+        let mut machine: Machine = Machine::initialize();
+
+        machine.load_rom(vec![0x70, 0x01, 0x12, 0x00]).unwrap();
+
+        let frames: usize = machine.run_until(100, |machine| machine.cpu().get_v_register(0x0) >= 30).unwrap();
+
+        assert_eq!(5, frames);
+    }
+
+    #[test]
+    fn it_should_stop_running_when_reaching_the_frame_limit() {
+        let mut machine: Machine = Machine::initialize();
+
+        machine.load_rom(vec![0x12, 0x00]).unwrap();
+
+        let frames: usize = machine.run_until(10, |_| false).unwrap();
+
+        assert_eq!(10, frames);
+    }
+
+    #[test_case(vec![0x12, 0x00], true ; "with a jump to itself")]
+    #[test_case(vec![0x12, 0x02], false ; "with a jump to another address")]
+    #[test_case(vec![0x60, 0x00], false ; "with another instruction")]
+    fn it_should_check_if_the_machine_is_idle(program: Vec<u8>, idle: bool) {
+        let mut machine: Machine = Machine::initialize();
+
+        machine.load_rom(program).unwrap();
+
+        assert_eq!(idle, machine.is_idle());
+    }
+
+    #[test]
+    fn it_should_draw_on_the_framebuffer() {
+        let mut machine: Machine = Machine::initialize();
+
+        machine.load_rom(vec![0xA0, 0x50, 0xD0, 0x15]).unwrap();
+
+        machine.step().unwrap();
+        machine.step().unwrap();
+
+        assert_eq!([true; 4], machine.framebuffer().get_pixels()[0x0..0x4]);
+    }
+
+    #[test_case(Quirks::DEFAULT, 0x3 ; "without the display wait quirk")]
+    #[test_case(Quirks::COSMAC_VIP, 0x0 ; "with the display wait quirk")]
+    fn it_should_follow_the_display_wait_quirk(quirks: Quirks, additions: u8) {
+        let mut machine: Machine = Machine::initialize();
+
+        machine.load_rom(vec![0xD0, 0x01, 0x70, 0x01, 0xD0, 0x01, 0x70, 0x01, 0xD0, 0x01, 0x70, 0x01, 0x12, 0x0C]).unwrap();
+
+        machine.set_quirks(quirks);
+        machine.run_frame().unwrap();
+
+        assert_eq!(quirks, machine.get_quirks());
+        assert_eq!(additions, machine.cpu.get_v_register(0x0));
+    }
+
+    #[test]
+    fn it_should_stop_running_when_the_program_exits() {
+        let mut machine: Machine = Machine::initialize();
+
+        machine.load_rom(vec![0x70, 0x01, 0x00, 0xFD, 0x70, 0x01]).unwrap();
+
+        assert_eq!(1, machine.run_until(10, |_| false).unwrap());
+        assert!(machine.is_halted());
+        assert_eq!(0x1, machine.cpu.get_v_register(0x0));
+
+        machine.run_frame().unwrap();
+
+        assert_eq!(0x1, machine.cpu.get_v_register(0x0));
+    }
+
+    #[test]
+    fn it_should_set_a_key() {
+        let mut machine: Machine = Machine::initialize();
+
+        machine.load_rom(vec![0xF0, 0x0A]).unwrap();
+
+        machine.set_key(0x7, true);
+        machine.step().unwrap();
+
+        assert_eq!(0x7, machine.cpu.get_v_register(0x0));
+    }
+
+    #[test]
+    fn it_should_restore_a_saved_state() {
+        let mut machine: Machine = Machine::initialize();
+
+        machine.load_rom(vec![0xA2, 0x0A, 0xD0, 0x05, 0xC1, 0xFF, 0x22, 0x0C, 0x12, 0x04, 0xF0, 0x90, 0x00, 0xEE]).unwrap();
+        machine.run_frame().unwrap();
+
+        let state: Vec<u8> = machine.save_state();
+        let screen: String = machine.framebuffer.to_string();
+
+        machine.run_frames(3).unwrap();
+
+        let random_byte: u8 = machine.cpu.get_v_register(0x1);
+
+        machine.load_state(&state).unwrap();
+
+        assert_eq!(state, machine.save_state());
+        assert_eq!(screen, machine.framebuffer.to_string());
+
+        machine.run_frames(3).unwrap();
+
+        assert_eq!(random_byte, machine.cpu.get_v_register(0x1));
+    }
+
+    #[test]
+    fn it_should_restore_the_quirks_of_a_saved_state() {
+        let mut machine: Machine = Machine::initialize();
+
+        machine.set_quirks(Quirks::XO_CHIP);
+        machine.load_rom(vec![0x12, 0x00]).unwrap();
+
+        let state: Vec<u8> = machine.save_state();
+        let mut restored: Machine = Machine::initialize();
+
+        restored.load_state(&state).unwrap();
+
+        assert_eq!(Quirks::XO_CHIP, restored.get_quirks());
+        assert_eq!(Memory::EXTENDED_SIZE, restored.memory.get_size());
+    }
+
+    #[test_case(0x5 ; "with a truncated state")]
+    #[test_case(0x100 ; "with a state cut in the middle")]
+    fn it_should_keep_the_machine_untouched_when_loading_a_corrupted_state(length: usize) {
+        let mut machine: Machine = Machine::initialize();
+
+        let state: Vec<u8> = machine.save_state();
+
+        machine.load_rom(vec![0x60, 0xCA]).unwrap();
+        machine.step().unwrap();
+
+        assert_eq!(Err(EmulatorError::InvalidState), machine.load_state(&state[..length]));
+        assert_eq!(0xCA, machine.cpu.get_v_register(0x0));
+    }
+
+    #[test]
+    fn it_should_refuse_a_state_with_memory_not_matching_the_quirks() {
+        let mut machine: Machine = Machine::initialize();
+        let mut state: Vec<u8> = machine.save_state();
+
+        // The XO-CHIP flag is the last byte of the quirks, right after the header.
+        state[0xD] = 0x1;
+
+        assert_eq!(Err(EmulatorError::InvalidState), machine.load_state(&state));
+    }
+
+    #[test]
+    fn it_should_rewind_frames() {
+        let mut machine: Machine = Machine::initialize();
+
+        machine.set_rewind_frames(10);
+        machine.set_instructions_per_frame(1);
+        machine.load_rom(vec![0x70, 0x01, 0x12, 0x00]).unwrap();
+        machine.run_frames(6).unwrap();
+
+        assert_eq!(0x3, machine.cpu.get_v_register(0x0));
+        assert_eq!(Ok(2), machine.rewind(2));
+        assert_eq!(0x2, machine.cpu.get_v_register(0x0));
+        assert_eq!(0x200, machine.cpu.get_program_counter());
+    }
+
+    #[test]
+    fn it_should_rewind_only_as_far_as_the_buffer_goes() {
+        let mut machine: Machine = Machine::initialize();
+
+        machine.set_rewind_frames(3);
+        machine.set_instructions_per_frame(1);
+        machine.load_rom(vec![0x70, 0x01, 0x12, 0x00]).unwrap();
+        machine.run_frames(10).unwrap();
+
+        assert_eq!(Ok(3), machine.rewind(5));
+        assert_eq!(0x4, machine.cpu.get_v_register(0x0));
+        assert_eq!(Ok(0), machine.rewind(1));
+    }
+
+    #[test]
+    fn it_should_not_rewind_without_rewind_frames() {
+        let mut machine: Machine = Machine::initialize();
+
+        machine.load_rom(vec![0x70, 0x01, 0x12, 0x00]).unwrap();
+        machine.run_frames(2).unwrap();
+
+        assert_eq!(Ok(0), machine.rewind(1));
+        assert_eq!(Machine::INSTRUCTIONS_PER_FRAME as u8, machine.cpu.get_v_register(0x0));
+    }
+
+    #[test]
+    fn it_should_pause_on_a_breakpoint() {
+        let mut machine: Machine = Machine::initialize();
+
+        machine.load_rom(vec![0x70, 0x01, 0x70, 0x01, 0x12, 0x00]).unwrap();
+        machine.add_breakpoint(0x202);
+        machine.run_frame().unwrap();
+
+        assert!(machine.is_paused());
+        assert_eq!(0x202, machine.cpu.get_program_counter());
+        assert_eq!(0x1, machine.cpu.get_v_register(0x0));
+    }
+
+    #[test]
+    fn it_should_continue_from_a_breakpoint() {
+        let mut machine: Machine = Machine::initialize();
+
+        machine.set_instructions_per_frame(4);
+        machine.load_rom(vec![0x70, 0x01, 0x70, 0x01, 0x12, 0x00]).unwrap();
+        machine.add_breakpoint(0x202);
+        machine.run_frame().unwrap();
+        machine.resume();
+        machine.run_frame().unwrap();
+
+        assert!(machine.is_paused());
+        assert_eq!(0x202, machine.cpu.get_program_counter());
+        assert_eq!(0x3, machine.cpu.get_v_register(0x0));
+    }
+
+    #[test]
+    fn it_should_remove_a_breakpoint() {
+        let mut machine: Machine = Machine::initialize();
+
+        machine.add_breakpoint(0x202);
+        machine.add_breakpoint(0x200);
+
+        assert_eq!(vec![0x200, 0x202], machine.get_breakpoints());
+        assert!(machine.remove_breakpoint(0x202));
+        assert!(!machine.remove_breakpoint(0x204));
+        assert_eq!(vec![0x200], machine.get_breakpoints());
+    }
+
+    #[test]
+    fn it_should_step_over_a_subroutine() {
+        let mut machine: Machine = Machine::initialize();
+
+        machine.load_rom(vec![0x22, 0x06, 0x70, 0x10, 0x12, 0x04, 0x71, 0x01, 0x71, 0x01, 0x00, 0xEE]).unwrap();
+        machine.pause();
+        machine.step_over().unwrap();
+        machine.run_frame().unwrap();
+
+        assert!(machine.is_paused());
+        assert_eq!(0x202, machine.cpu.get_program_counter());
+        assert_eq!(0x2, machine.cpu.get_v_register(0x1));
+        assert_eq!(0x0, machine.cpu.get_v_register(0x0));
+    }
+
+    #[test]
+    fn it_should_step_over_an_instruction_that_is_not_a_call() {
+        let mut machine: Machine = Machine::initialize();
+
+        machine.load_rom(vec![0x70, 0x01, 0x12, 0x00]).unwrap();
+        machine.pause();
+        machine.step_over().unwrap();
+
+        assert!(machine.is_paused());
+        assert_eq!(0x202, machine.cpu.get_program_counter());
+    }
+
+    #[test]
+    fn it_should_step_out_of_a_subroutine() {
+        let mut machine: Machine = Machine::initialize();
+
+        machine.load_rom(vec![0x22, 0x06, 0x70, 0x10, 0x12, 0x04, 0x71, 0x01, 0x71, 0x01, 0x00, 0xEE]).unwrap();
+        machine.pause();
+        machine.step().unwrap();
+        machine.step().unwrap();
+
+        assert!(machine.step_out());
+
+        machine.run_frame().unwrap();
+
+        assert!(machine.is_paused());
+        assert_eq!(0x202, machine.cpu.get_program_counter());
+        assert_eq!(0x2, machine.cpu.get_v_register(0x1));
+    }
+
+    #[test]
+    fn it_should_not_step_out_without_a_subroutine() {
+        let mut machine: Machine = Machine::initialize();
+
+        machine.pause();
+
+        assert!(!machine.step_out());
+        assert!(machine.is_paused());
+    }
+
+    #[test]
+    fn it_should_stop_stepping_over_on_a_breakpoint() {
+        let mut machine: Machine = Machine::initialize();
+
+        machine.load_rom(vec![0x22, 0x06, 0x70, 0x10, 0x12, 0x04, 0x71, 0x01, 0x71, 0x01, 0x00, 0xEE]).unwrap();
+        machine.add_breakpoint(0x208);
+        machine.pause();
+        machine.step_over().unwrap();
+        machine.run_frame().unwrap();
+
+        assert_eq!(0x208, machine.cpu.get_program_counter());
+
+        machine.remove_breakpoint(0x208);
+        machine.resume();
+        machine.run_frame().unwrap();
+
+        assert!(!machine.is_paused());
+    }
+
+    #[test]
+    fn it_should_pause_on_a_memory_watchpoint() {
+        let mut machine: Machine = Machine::initialize();
+
+        machine.load_rom(vec![0xA3, 0x00, 0x60, 0xCA, 0xF0, 0x55, 0x12, 0x06]).unwrap();
+        machine.add_watchpoint(Watchpoint::Write { start: 0x300, end: 0x30F });
+        machine.run_frame().unwrap();
+
+        assert!(machine.is_paused());
+        assert_eq!(0x206, machine.cpu.get_program_counter());
+        assert_eq!(Some(Trigger::Write { address: 0x300, value: 0xCA }), machine.get_trigger());
+    }
+
+    #[test]
+    fn it_should_pause_on_a_register_watchpoint() {
+        let mut machine: Machine = Machine::initialize();
+
+        machine.load_rom(vec![0x70, 0x01, 0x12, 0x00]).unwrap();
+        machine.add_watchpoint(Watchpoint::VRegister { register: 0x0, value: 0x3 });
+        machine.run_frames(10).unwrap();
+
+        assert!(machine.is_paused());
+        assert_eq!(0x3, machine.cpu.get_v_register(0x0));
+        assert_eq!(Some(Trigger::VRegister { register: 0x0, value: 0x3 }), machine.get_trigger());
+    }
+
+    #[test]
+    fn it_should_not_set_off_a_read_watchpoint_when_peeking_at_the_next_instruction() {
+        let mut machine: Machine = Machine::initialize();
+
+        machine.load_rom(vec![0x12, 0x00]).unwrap();
+        machine.add_watchpoint(Watchpoint::Read { start: 0x200, end: 0x201 });
+
+        assert!(machine.is_idle());
+        assert_eq!(None, machine.memory.take_trigger());
+    }
+
+    #[test_case(Quirks::DEFAULT ; "without the xo-chip extensions")]
+    #[test_case(Quirks::XO_CHIP ; "with the xo-chip extensions")]
+    fn it_should_not_set_off_a_read_watchpoint_when_skipping_the_next_instruction(quirks: Quirks) {
+        let mut machine: Machine = Machine::initialize();
+
+        machine.set_quirks(quirks);
+        machine.load_rom(vec![0x30, 0x00, 0x12, 0x02, 0x12, 0x04]).unwrap();
+        machine.add_watchpoint(Watchpoint::Read { start: 0x202, end: 0x203 });
+
+        machine.step().unwrap();
+
+        assert_eq!(0x204, machine.cpu.get_program_counter());
+        assert!(!machine.is_paused());
+    }
+
+    #[test]
+    fn it_should_remove_a_watchpoint() {
+        let mut machine: Machine = Machine::initialize();
+
+        machine.load_rom(vec![0x70, 0x01, 0x12, 0x00]).unwrap();
+        machine.add_watchpoint(Watchpoint::IRegister { value: 0x300 });
+        machine.add_watchpoint(Watchpoint::VRegister { register: 0x0, value: 0x1 });
+
+        assert_eq!(Some(Watchpoint::VRegister { register: 0x0, value: 0x1 }), machine.remove_watchpoint(0x1));
+        assert_eq!(None, machine.remove_watchpoint(0x1));
+        assert_eq!(&[Watchpoint::IRegister { value: 0x300 }], machine.get_watchpoints());
+
+        machine.run_frame().unwrap();
+
+        assert!(!machine.is_paused());
+    }
+
+    #[test]
+    fn it_should_keep_the_watchpoints_when_loading_a_state() {
+        let mut machine: Machine = Machine::initialize();
+
+        machine.load_rom(vec![0x70, 0x01, 0x12, 0x00]).unwrap();
+        machine.add_watchpoint(Watchpoint::VRegister { register: 0x0, value: 0x1 });
+
+        let state: Vec<u8> = machine.save_state();
+
+        machine.load_state(&state).unwrap();
+        machine.run_frame().unwrap();
+
+        assert!(machine.is_paused());
+    }
+
+    #[test]
+    fn it_should_run_code_overwritten_by_a_store_with_the_decode_cache() {
+        let mut machine: Machine = Machine::initialize();
+
+        machine.set_decode_cache(true);
+        machine.load_rom(vec![
+            0x60, 0x74, 0x61, 0x01, 0x12, 0x0C, 0xA2, 0x0C, 0xF1, 0x55, 0x12, 0x0C, 0x73, 0x01, 0x12, 0x06,
+        ]).unwrap();
+
+        for _ in 0..9 {
+            machine.step().unwrap();
+        }
+
+        assert_eq!(0x1, machine.cpu.get_v_register(0x3));
+        assert_eq!(0x1, machine.cpu.get_v_register(0x4));
+    }
+
+    #[test]
+    fn it_should_run_code_overwritten_by_a_bcd_with_the_decode_cache() {
+        let mut machine: Machine = Machine::initialize();
+
+        machine.set_decode_cache(true);
+        machine.set_unknown_opcode_policy(UnknownOpcodePolicy::Halt);
+        machine.load_rom(vec![0x60, 0xCD, 0x12, 0x0A, 0xA2, 0x0A, 0xF0, 0x33, 0x12, 0x0A, 0x73, 0x01, 0x12, 0x04]).unwrap();
+
+        for _ in 0..7 {
+            machine.step().unwrap();
+        }
+
+        assert_eq!(
+            Err(EmulatorError::UnknownOpcode { opcode: 0x0200, program_counter: 0x20A }),
+            machine.step()
+        );
+    }
+
+    #[test]
+    fn it_should_keep_the_decode_cache_when_loading_a_state() {
+        let mut machine: Machine = Machine::initialize();
+
+        machine.set_decode_cache(true);
+
+        let state: Vec<u8> = machine.save_state();
+
+        machine.load_state(&state).unwrap();
+
+        assert!(machine.is_decode_cache_enabled());
+    }
+}
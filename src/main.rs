@@ -1,26 +1,250 @@
-mod cpu;
 mod interface;
-mod instruction;
 mod keyboard;
-mod memory;
-mod motherboard;
+mod options;
 
-use motherboard::Motherboard;
-use std::fs;
-use std::env;
+use chip8rs::assembler;
+use chip8rs::audio::{Audio, NullAudio, ToneGenerator, WavAudio};
+use chip8rs::debugger::{Command, Debugger};
+use chip8rs::error::EmulatorError;
+use chip8rs::flags::{self, FlagStore};
+use chip8rs::gdb::GdbStub;
+use chip8rs::machine::Machine;
+use chip8rs::policy::UnknownOpcodePolicy;
+use chip8rs::motherboard::Motherboard;
+use chip8rs::state::StateStore;
+use interface::Interface;
+use options::Options;
+use std::error::Error;
+use std::fs::File;
+use std::io::{self, BufRead, BufWriter, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+use std::{env, fs, process};
 
 fn main() {
-    let args: Vec<String> = env::args().skip(1).collect();
+    if let Err(error) = run(env::args().skip(1).collect()) {
+        eprintln!("{}", error);
 
-    if args.len() < 1 {
-        panic!("The location to the chip8 ROM is required.");
+        process::exit(1);
     }
+}
 
-    let rom_path: &String = &args[0];
+fn run(args: Vec<String>) -> Result<(), Box<dyn Error>> {
+    let options: Options = Options::parse(args)?;
 
-    let program: Vec<u8> = fs::read(rom_path)
-        .expect("The file path is invalid.");
+    let program: Vec<u8> = read_program(&options.rom_path)?;
 
-    Motherboard::initialize()
-        .emulate(program);
-}
\ No newline at end of file
+    let mut machine: Machine = Machine::initialize();
+
+    machine.set_instructions_per_frame(options.instructions_per_frame);
+    machine.set_unknown_opcode_policy(options.unknown_opcode_policy);
+    machine.set_quirks(options.quirks);
+    machine.set_decode_cache(options.decode_cache);
+    machine.set_random_seed(get_random_seed());
+
+    // Headless runs are mostly scripts and tests, so they only keep the flags
+    // in a directory passed on purpose.
+    let flag_store: Option<FlagStore> = match options.headless && options.flags_directory.is_none() {
+        true => None,
+        false => Some(FlagStore::initialize(get_flags_directory(&options))),
+    };
+    let rom_hash: u64 = flags::hash_rom(&program);
+
+    if let Some(rpl_flags) = flag_store.as_ref().map(|store| store.load(rom_hash)).transpose()?.flatten() {
+        machine.set_rpl_flags(rpl_flags);
+    }
+
+    let audio: Box<dyn Audio> = create_audio(&options)?;
+
+    if let Some(port) = options.gdb {
+        run_gdb_stub(machine, program, port, flag_store.as_ref(), rom_hash)
+    } else if options.debug {
+        run_debugger(machine, program, flag_store.as_ref(), rom_hash)
+    } else if options.headless {
+        run_headless(machine, program, audio, &options, flag_store.as_ref(), rom_hash)
+    } else {
+        // Only the window can rewind, so the headless runs don't keep the frames.
+        machine.set_rewind_frames(options.rewind_frames);
+
+        // The window can't resume a paused machine, so breaking halts instead.
+        if machine.get_unknown_opcode_policy() == UnknownOpcodePolicy::Break {
+            eprintln!("Warning: the break policy needs --debug or --gdb, unknown opcodes will halt the emulator instead.");
+
+            machine.set_unknown_opcode_policy(UnknownOpcodePolicy::Halt);
+        }
+
+        let mut motherboard: Motherboard = Motherboard::initialize(machine, Box::new(Interface::initialize(options.palette.clone())?));
+
+        motherboard.set_audio(audio);
+        motherboard.set_state_store(StateStore::initialize(get_states_directory(&options)));
+
+        let result: Result<(), EmulatorError> = motherboard.emulate(program);
+
+        save_flags(flag_store.as_ref(), rom_hash, motherboard.machine())?;
+
+        Ok(result?)
+    }
+}
+
+// Octo sources are assembled on the way in, so they run like any ROM.
+fn read_program(path: &str) -> Result<Vec<u8>, Box<dyn Error>> {
+    if !path.ends_with(".8o") {
+        return Ok(fs::read(path).map_err(|error| format!("The ROM {} could not be read: {}", path, error))?);
+    }
+
+    let source: String = fs::read_to_string(path)
+        .map_err(|error| format!("The source {} could not be read: {}", path, error))?;
+
+    Ok(assembler::assemble(&source).map_err(|error| format!("{}: {}", path, error))?)
+}
+
+fn save_flags(flag_store: Option<&FlagStore>, rom_hash: u64, machine: &Machine) -> io::Result<()> {
+    match flag_store {
+        Some(store) => store.save(rom_hash, machine.cpu().get_rpl_flags()),
+        None => Ok(()),
+    }
+}
+
+fn get_flags_directory(options: &Options) -> PathBuf {
+    get_data_directory(&options.flags_directory, "flags")
+}
+
+fn get_states_directory(options: &Options) -> PathBuf {
+    get_data_directory(&options.states_directory, "states")
+}
+
+fn get_data_directory(directory: &Option<String>, name: &str) -> PathBuf {
+    match (directory, env::var_os("HOME")) {
+        (Some(directory), _) => PathBuf::from(directory),
+        (None, Some(home)) => PathBuf::from(home).join(".chip8rs").join(name),
+        (None, None) => PathBuf::from(".chip8rs").join(name),
+    }
+}
+
+fn get_random_seed() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_nanos() as u64)
+        .unwrap_or(0x0)
+}
+
+fn create_audio(options: &Options) -> Result<Box<dyn Audio>, Box<dyn Error>> {
+    let path: &str = match options.wav.as_deref() {
+        Some(path) => path,
+        None => return Ok(Box::new(NullAudio)),
+    };
+
+    let file: File = File::create(path)
+        .map_err(|error| format!("The WAV file {} could not be created: {}", path, error))?;
+
+    Ok(Box::new(WavAudio::initialize(BufWriter::new(file), ToneGenerator::SAMPLE_RATE)?))
+}
+
+fn run_headless(
+    mut machine: Machine,
+    program: Vec<u8>,
+    mut audio: Box<dyn Audio>,
+    options: &Options,
+    flag_store: Option<&FlagStore>,
+    rom_hash: u64,
+) -> Result<(), Box<dyn Error>> {
+    machine.load_rom(program)?;
+
+    let until_idle: bool = options.until_idle;
+    let mut tone_generator: ToneGenerator = ToneGenerator::initialize(ToneGenerator::SAMPLE_RATE);
+
+    let mut audio_result: Result<(), EmulatorError> = Ok(());
+
+    let result: Result<usize, EmulatorError> = machine.run_until(options.frames, |machine| {
+        audio_result = audio.play(&tone_generator.generate(machine.cpu()));
+
+        audio_result.is_err() || (until_idle && machine.is_idle())
+    });
+
+    save_flags(flag_store, rom_hash, &machine)?;
+
+    result?;
+    audio_result?;
+    audio.finish()?;
+
+    let screen: String = machine.framebuffer().to_string();
+
+    match options.dump.as_deref() {
+        None | Some("-") => print!("{}", screen),
+        Some(path) => fs::write(path, screen)
+            .map_err(|error| format!("The framebuffer could not be dumped to {}: {}", path, error))?,
+    }
+
+    Ok(())
+}
+
+fn run_debugger(
+    mut machine: Machine,
+    program: Vec<u8>,
+    flag_store: Option<&FlagStore>,
+    rom_hash: u64,
+) -> Result<(), Box<dyn Error>> {
+    machine.load_rom(program)?;
+
+    let debugger: Debugger = Debugger::initialize(Debugger::FRAMES);
+    let stdin: io::Stdin = io::stdin();
+    let mut lines = stdin.lock().lines();
+
+    println!("{}", Debugger::show_registers(&machine));
+
+    loop {
+        print!("(chip8) ");
+        io::stdout().flush()?;
+
+        let line: String = match lines.next() {
+            Some(line) => line?,
+            None => break,
+        };
+
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        // The errors of the program are shown like any other output, so it
+        // can still be inspected after them.
+        match line.parse::<Command>() {
+            Ok(Command::Quit) => break,
+            Ok(command) => match debugger.execute(&mut machine, command) {
+                Ok(output) => println!("{}", output),
+                Err(error) => println!("{}", error),
+            },
+            Err(error) => println!("{}", error),
+        }
+    }
+
+    save_flags(flag_store, rom_hash, &machine)?;
+
+    Ok(())
+}
+
+// Only one debugger is served, and only on this computer, since the
+// protocol lets it read and write anything.
+fn run_gdb_stub(
+    mut machine: Machine,
+    program: Vec<u8>,
+    port: u16,
+    flag_store: Option<&FlagStore>,
+    rom_hash: u64,
+) -> Result<(), Box<dyn Error>> {
+    machine.load_rom(program)?;
+
+    let listener: TcpListener = TcpListener::bind(("127.0.0.1", port))
+        .map_err(|error| format!("The port {} could not be listened on: {}", port, error))?;
+
+    println!("Waiting for GDB on 127.0.0.1:{}...", port);
+
+    let (stream, _): (TcpStream, _) = listener.accept()?;
+    let mut stub: GdbStub = GdbStub::initialize(machine);
+
+    stub.serve(stream)?;
+
+    save_flags(flag_store, rom_hash, stub.machine())?;
+
+    Ok(())
+}
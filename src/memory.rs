@@ -1,8 +1,27 @@
+use crate::error::EmulatorError;
+use crate::instruction::Instruction;
+use crate::state::{StateReader, StateWriter};
+use crate::watchpoint::{self, Trigger, Watchpoint};
+use std::cell::Cell;
+
 pub struct Memory {
-    bytes: [u8; 0xFFF],
+    bytes: Vec<u8>,
+    watchpoints: Vec<Watchpoint>,
+    // Reads only borrow the memory, so the trigger has to be kept in a cell.
+    trigger: Cell<Option<Trigger>>,
+    // The instructions already decoded on each address, when the cache is on.
+    // Writing a byte drops the instructions it's part of, so self-modifying
+    // code still runs what it wrote.
+    decoded: Option<Vec<Option<Instruction>>>,
 }
 
 impl Memory {
+    pub const SIZE: usize = 0x1000;
+    pub const EXTENDED_SIZE: usize = 0x10000;
+    pub const PROGRAM_START: usize = 0x200;
+    pub const FONT_START: usize = 0x50;
+    pub const BIG_FONT_START: usize = 0xA0;
+
     pub const FONT: [u8; 80] = [
         0xF0, 0x90, 0x90, 0x90, 0xF0, // 0
         0x20, 0x60, 0x20, 0x20, 0x70, // 1
@@ -22,30 +41,195 @@ impl Memory {
         0xF0, 0x80, 0xF0, 0x80, 0x80  // F
     ];
 
+    // The 8x10 digits from SUPER-CHIP, with the letters added by Octo.
+    pub const BIG_FONT: [u8; 160] = [
+        0xFF, 0xFF, 0xC3, 0xC3, 0xC3, 0xC3, 0xC3, 0xC3, 0xFF, 0xFF, // 0
+        0x18, 0x78, 0x78, 0x18, 0x18, 0x18, 0x18, 0x18, 0xFF, 0xFF, // 1
+        0xFF, 0xFF, 0x03, 0x03, 0xFF, 0xFF, 0xC0, 0xC0, 0xFF, 0xFF, // 2
+        0xFF, 0xFF, 0x03, 0x03, 0xFF, 0xFF, 0x03, 0x03, 0xFF, 0xFF, // 3
+        0xC3, 0xC3, 0xC3, 0xC3, 0xFF, 0xFF, 0x03, 0x03, 0x03, 0x03, // 4
+        0xFF, 0xFF, 0xC0, 0xC0, 0xFF, 0xFF, 0x03, 0x03, 0xFF, 0xFF, // 5
+        0xFF, 0xFF, 0xC0, 0xC0, 0xFF, 0xFF, 0xC3, 0xC3, 0xFF, 0xFF, // 6
+        0xFF, 0xFF, 0x03, 0x03, 0x06, 0x0C, 0x18, 0x18, 0x18, 0x18, // 7
+        0xFF, 0xFF, 0xC3, 0xC3, 0xFF, 0xFF, 0xC3, 0xC3, 0xFF, 0xFF, // 8
+        0xFF, 0xFF, 0xC3, 0xC3, 0xFF, 0xFF, 0x03, 0x03, 0xFF, 0xFF, // 9
+        0x7E, 0xFF, 0xC3, 0xC3, 0xC3, 0xFF, 0xFF, 0xC3, 0xC3, 0xC3, // A
+        0xFC, 0xFC, 0xC3, 0xC3, 0xFC, 0xFC, 0xC3, 0xC3, 0xFC, 0xFC, // B
+        0x3C, 0xFF, 0xC3, 0xC0, 0xC0, 0xC0, 0xC0, 0xC3, 0xFF, 0x3C, // C
+        0xFC, 0xFE, 0xC3, 0xC3, 0xC3, 0xC3, 0xC3, 0xC3, 0xFE, 0xFC, // D
+        0xFF, 0xFF, 0xC0, 0xC0, 0xFF, 0xFF, 0xC0, 0xC0, 0xFF, 0xFF, // E
+        0xFF, 0xFF, 0xC0, 0xC0, 0xFF, 0xFF, 0xC0, 0xC0, 0xC0, 0xC0  // F
+    ];
+
     pub fn initialize() -> Memory {
         Memory {
-            bytes: [0x0; 0xFFF],
+            bytes: vec![0x0; Memory::SIZE],
+            watchpoints: Vec::new(),
+            trigger: Cell::new(None),
+            decoded: None,
+        }
+    }
+
+    pub fn set_extended(&mut self, enabled: bool) {
+        let size: usize = match enabled {
+            true => Memory::EXTENDED_SIZE,
+            false => Memory::SIZE,
+        };
+
+        self.bytes.resize(size, 0x0);
+
+        if let Some(decoded) = self.decoded.as_mut() {
+            decoded.resize(size, None);
+        }
+    }
+
+    pub fn set_decode_cache(&mut self, enabled: bool) {
+        self.decoded = match enabled {
+            true => Some(vec![None; self.bytes.len()]),
+            false => None,
+        };
+    }
+
+    pub fn is_decode_cache_enabled(&self) -> bool {
+        self.decoded.is_some()
+    }
+
+    // Going through the cache would skip the reads the watchpoints look for.
+    pub fn get_decoded(&self, location: usize) -> Option<Instruction> {
+        if !self.watchpoints.is_empty() {
+            return None;
         }
+
+        self.decoded.as_ref()?.get(location).copied().flatten()
+    }
+
+    pub fn set_decoded(&mut self, location: usize, instruction: Instruction) {
+        if let Some(entry) = self.decoded.as_mut().and_then(|decoded| decoded.get_mut(location)) {
+            *entry = Some(instruction);
+        }
+    }
+
+    pub fn get_size(&self) -> usize {
+        self.bytes.len()
     }
 
-    pub fn store_program(&mut self, program: Vec<u8>) {
+    pub fn save_state(&self, writer: &mut StateWriter) {
+        writer.write_u32(self.bytes.len() as u32);
+        writer.write_bytes(&self.bytes);
+    }
+
+    pub fn load_state(reader: &mut StateReader) -> Result<Self, EmulatorError> {
+        let size: usize = reader.read_u32()? as usize;
+
+        if size != Memory::SIZE && size != Memory::EXTENDED_SIZE {
+            return Err(EmulatorError::InvalidState);
+        }
+
+        let mut memory: Memory = Memory::initialize();
+
+        memory.bytes = reader.read_bytes(size)?.to_vec();
+
+        Ok(memory)
+    }
+
+    pub fn store_program(&mut self, program: Vec<u8>) -> Result<(), EmulatorError> {
+        let capacity: usize = self.bytes.len() - Memory::PROGRAM_START;
+
+        if program.len() > capacity {
+            return Err(EmulatorError::RomTooLarge { size: program.len(), capacity });
+        }
+
         for (index, byte) in program.iter().cloned().enumerate() {
-            self.bytes[0x200 + index] = byte;
+            self.bytes[Memory::PROGRAM_START + index] = byte;
         }
+
+        self.clear_decoded();
+
+        Ok(())
     }
 
     pub fn set_font(&mut self) {
         for (index, byte) in Memory::FONT.iter().cloned().enumerate() {
-            self.bytes[0x50 + index] = byte;
+            self.bytes[Memory::FONT_START + index] = byte;
         }
+
+        self.clear_decoded();
     }
 
-    pub fn set(&mut self, location: usize, byte: u8) {
-        self.bytes[location] = byte;
+    pub fn set_big_font(&mut self, enabled: bool) {
+        for (index, byte) in Memory::BIG_FONT.iter().cloned().enumerate() {
+            self.bytes[Memory::BIG_FONT_START + index] = match enabled {
+                true => byte,
+                false => 0x0,
+            };
+        }
+
+        self.clear_decoded();
     }
 
-    pub fn get(&self, location: usize) -> u8 {
-        self.bytes[location]
+    pub fn set(&mut self, location: usize, byte: u8) -> Result<(), EmulatorError> {
+        match self.bytes.get_mut(location) {
+            Some(value) => {
+                *value = byte;
+
+                self.invalidate(location);
+
+                if !self.watchpoints.is_empty() {
+                    self.watch(Trigger::Write { address: location, value: byte });
+                }
+
+                Ok(())
+            },
+            None => Err(EmulatorError::MemoryFault { address: location }),
+        }
+    }
+
+    pub fn get(&self, location: usize) -> Result<u8, EmulatorError> {
+        if !self.watchpoints.is_empty() {
+            self.watch(Trigger::Read { address: location });
+        }
+
+        self.peek(location)
+    }
+
+    // Reads without setting the watchpoints off, for looking at the memory
+    // from outside of the program.
+    pub fn peek(&self, location: usize) -> Result<u8, EmulatorError> {
+        self.bytes.get(location)
+            .copied()
+            .ok_or(EmulatorError::MemoryFault { address: location })
+    }
+
+    pub fn set_watchpoints(&mut self, watchpoints: Vec<Watchpoint>) {
+        self.watchpoints = watchpoints;
+    }
+
+    pub fn take_trigger(&self) -> Option<Trigger> {
+        self.trigger.take()
+    }
+
+    fn clear_decoded(&mut self) {
+        if let Some(decoded) = self.decoded.as_mut() {
+            decoded.fill(None);
+        }
+    }
+
+    // The byte is the first half of the instruction on its address and the
+    // second half of the one before it.
+    fn invalidate(&mut self, location: usize) {
+        if let Some(decoded) = self.decoded.as_mut() {
+            decoded[location] = None;
+
+            if location > 0 {
+                decoded[location - 1] = None;
+            }
+        }
+    }
+
+    fn watch(&self, trigger: Trigger) {
+        if let Some(trigger) = watchpoint::check(&self.watchpoints, trigger) {
+            self.trigger.set(Some(trigger));
+        }
     }
 }
 
@@ -57,7 +241,7 @@ mod tests {
     fn it_should_initialize_the_memory() {
         let memory: Memory = Memory::initialize();
 
-        assert_eq!([0x0; 0xFFF], memory.bytes);
+        assert_eq!(vec![0x0; 0x1000], memory.bytes);
     }
 
     #[test]
@@ -66,13 +250,62 @@ mod tests {
 
         let vector: Vec<u8> = vec![0x7, 0x6, 0x5];
 
-        memory.store_program(vector);
+        memory.store_program(vector).unwrap();
 
         assert_eq!(0x7, memory.bytes[0x200]);
         assert_eq!(0x6, memory.bytes[0x201]);
         assert_eq!(0x5, memory.bytes[0x202]);
     }
 
+    #[test]
+    fn it_should_store_a_program_filling_the_whole_memory() {
+        let mut memory: Memory = Memory::initialize();
+
+        memory.store_program(vec![0x7; 0xE00]).unwrap();
+
+        assert_eq!(0x7, memory.bytes[0xFFF]);
+    }
+
+    #[test]
+    fn it_should_fail_to_store_a_program_larger_than_the_memory() {
+        let mut memory: Memory = Memory::initialize();
+
+        assert_eq!(
+            Err(EmulatorError::RomTooLarge { size: 0xE01, capacity: 0xE00 }),
+            memory.store_program(vec![0x7; 0xE01])
+        );
+    }
+
+    #[test]
+    fn it_should_extend_the_memory_keeping_its_bytes() {
+        let mut memory: Memory = Memory::initialize();
+
+        memory.bytes[0xFFF] = 0x7;
+
+        memory.set_extended(true);
+
+        assert_eq!(0x10000, memory.get_size());
+        assert_eq!(0x7, memory.bytes[0xFFF]);
+
+        memory.set_extended(false);
+
+        assert_eq!(0x1000, memory.get_size());
+    }
+
+    #[test]
+    fn it_should_store_a_program_larger_than_the_memory_when_extended() {
+        let mut memory: Memory = Memory::initialize();
+
+        memory.set_extended(true);
+        memory.store_program(vec![0x7; 0xFE00]).unwrap();
+
+        assert_eq!(0x7, memory.bytes[0xFFFF]);
+        assert_eq!(
+            Err(EmulatorError::RomTooLarge { size: 0xFE01, capacity: 0xFE00 }),
+            memory.store_program(vec![0x7; 0xFE01])
+        );
+    }
+
     #[test]
     fn it_should_set_the_font() {
         let mut memory: Memory = Memory::initialize();
@@ -84,21 +317,179 @@ mod tests {
         }
     }
 
+    #[test]
+    fn it_should_set_and_clear_the_big_font() {
+        let mut memory: Memory = Memory::initialize();
+
+        memory.set_big_font(true);
+
+        for (index, byte) in Memory::BIG_FONT.iter().cloned().enumerate() {
+            assert_eq!(byte, memory.bytes[0xA0 + index]);
+        }
+
+        memory.set_big_font(false);
+
+        for index in 0..Memory::BIG_FONT.len() {
+            assert_eq!(0x0, memory.bytes[0xA0 + index]);
+        }
+    }
+
     #[test]
     fn it_should_set_a_byte() {
         let mut memory: Memory = Memory::initialize();
 
-        memory.set(0x0, 0x8);
+        memory.set(0x0, 0x8).unwrap();
 
         assert_eq!(0x8, memory.bytes[0x0]);
     }
 
+    #[test]
+    fn it_should_fail_to_set_a_byte_out_of_range() {
+        let mut memory: Memory = Memory::initialize();
+
+        assert_eq!(
+            Err(EmulatorError::MemoryFault { address: 0x1000 }),
+            memory.set(0x1000, 0x8)
+        );
+    }
+
     #[test]
     fn it_should_get_a_byte() {
         let mut memory: Memory = Memory::initialize();
 
         memory.bytes[0xF] = 0x7;
 
-        assert_eq!(0x7, memory.get(0xF));
+        assert_eq!(Ok(0x7), memory.get(0xF));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn it_should_fail_to_get_a_byte_out_of_range() {
+        let memory: Memory = Memory::initialize();
+
+        assert_eq!(
+            Err(EmulatorError::MemoryFault { address: 0x1000 }),
+            memory.get(0x1000)
+        );
+    }
+
+    #[test]
+    fn it_should_save_and_load_the_state() {
+        let mut memory: Memory = Memory::initialize();
+
+        memory.set_extended(true);
+        memory.set(0xFFFF, 0xCA).unwrap();
+
+        let mut writer: StateWriter = StateWriter::initialize();
+
+        memory.save_state(&mut writer);
+
+        let bytes: Vec<u8> = writer.into_bytes();
+        let mut reader: StateReader = StateReader::initialize(&bytes).unwrap();
+        let restored: Memory = Memory::load_state(&mut reader).unwrap();
+
+        assert_eq!(0x10000, restored.get_size());
+        assert_eq!(Ok(0xCA), restored.get(0xFFFF));
+    }
+
+    #[test]
+    fn it_should_fail_to_load_a_state_with_an_unknown_memory_size() {
+        let mut writer: StateWriter = StateWriter::initialize();
+
+        writer.write_u32(0x2000);
+        writer.write_bytes(&[0x0; 0x2000]);
+
+        let bytes: Vec<u8> = writer.into_bytes();
+        let mut reader: StateReader = StateReader::initialize(&bytes).unwrap();
+
+        assert!(matches!(Memory::load_state(&mut reader), Err(EmulatorError::InvalidState)));
+    }
+
+    #[test]
+    fn it_should_set_off_a_write_watchpoint() {
+        let mut memory: Memory = Memory::initialize();
+
+        memory.set_watchpoints(vec![Watchpoint::Write { start: 0x300, end: 0x30F }]);
+        memory.set(0x2FF, 0xCA).unwrap();
+
+        assert_eq!(None, memory.take_trigger());
+
+        memory.set(0x30F, 0xCA).unwrap();
+
+        assert_eq!(Some(Trigger::Write { address: 0x30F, value: 0xCA }), memory.take_trigger());
+        assert_eq!(None, memory.take_trigger());
+    }
+
+    #[test]
+    fn it_should_set_off_a_read_watchpoint() {
+        let mut memory: Memory = Memory::initialize();
+
+        memory.set_watchpoints(vec![Watchpoint::Read { start: 0x300, end: 0x300 }]);
+        memory.peek(0x300).unwrap();
+
+        assert_eq!(None, memory.take_trigger());
+
+        memory.get(0x300).unwrap();
+
+        assert_eq!(Some(Trigger::Read { address: 0x300 }), memory.take_trigger());
+    }
+
+    #[test]
+    fn it_should_keep_the_decoded_instructions_only_when_the_cache_is_enabled() {
+        let mut memory: Memory = Memory::initialize();
+        let clear: Instruction = Instruction::initialize(0x00, 0xE0);
+
+        memory.set_decoded(0x200, clear);
+
+        assert_eq!(None, memory.get_decoded(0x200));
+
+        memory.set_decode_cache(true);
+        memory.set_decoded(0x200, clear);
+
+        assert!(memory.is_decode_cache_enabled());
+        assert_eq!(Some(clear), memory.get_decoded(0x200));
+    }
+
+    #[test]
+    fn it_should_drop_the_decoded_instructions_a_write_is_part_of() {
+        let mut memory: Memory = Memory::initialize();
+        let clear: Instruction = Instruction::initialize(0x00, 0xE0);
+        let call: Instruction = Instruction::initialize(0x22, 0x06);
+        let exit: Instruction = Instruction::initialize(0x00, 0xFD);
+
+        memory.set_decode_cache(true);
+        memory.set_decoded(0x200, clear);
+        memory.set_decoded(0x201, call);
+        memory.set_decoded(0x202, exit);
+
+        memory.set(0x201, 0x7).unwrap();
+
+        assert_eq!(None, memory.get_decoded(0x200));
+        assert_eq!(None, memory.get_decoded(0x201));
+        assert_eq!(Some(exit), memory.get_decoded(0x202));
+    }
+
+    #[test]
+    fn it_should_drop_the_decoded_instructions_when_storing_a_program() {
+        let mut memory: Memory = Memory::initialize();
+        let clear: Instruction = Instruction::initialize(0x00, 0xE0);
+
+        memory.set_decode_cache(true);
+        memory.set_decoded(0x200, clear);
+
+        memory.store_program(vec![0x00, 0xEE]).unwrap();
+
+        assert_eq!(None, memory.get_decoded(0x200));
+    }
+
+    #[test]
+    fn it_should_skip_the_decoded_instructions_while_watching() {
+        let mut memory: Memory = Memory::initialize();
+        let clear: Instruction = Instruction::initialize(0x00, 0xE0);
+
+        memory.set_decode_cache(true);
+        memory.set_decoded(0x200, clear);
+        memory.set_watchpoints(vec![Watchpoint::Read { start: 0x300, end: 0x300 }]);
+
+        assert_eq!(None, memory.get_decoded(0x200));
+    }
+}
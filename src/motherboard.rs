@@ -1,40 +1,370 @@
-use crate::cpu::Cpu;
-use crate::memory::Memory;
-use mockall_double::double;
-use std::{thread, time};
-
-#[double]
-use crate::interface::Interface;
+use crate::audio::{Audio, NullAudio, ToneGenerator};
+use crate::error::EmulatorError;
+use crate::flags;
+use crate::frontend::{Frontend, Hotkey};
+use crate::keypad::Keypad;
+use crate::machine::Machine;
+use crate::policy::UnknownOpcodePolicy;
+use crate::scheduler::Scheduler;
+use crate::state::StateStore;
 
 pub struct Motherboard {
-    cpu: Cpu,
-    interface: Interface,
-    memory: Memory,
+    machine: Machine,
+    frontend: Box<dyn Frontend>,
+    audio: Box<dyn Audio>,
+    tone_generator: ToneGenerator,
+    scheduler: Scheduler,
+    state_store: Option<StateStore>,
 }
 
 impl Motherboard {
-    pub fn initialize() -> Self {
+    pub fn initialize(machine: Machine, frontend: Box<dyn Frontend>) -> Self {
         Motherboard {
-            cpu: Cpu::initialize(),
-            interface: Interface::initialize(),
-            memory: Memory::initialize(),
+            machine,
+            frontend,
+            audio: Box::new(NullAudio),
+            tone_generator: ToneGenerator::initialize(ToneGenerator::SAMPLE_RATE),
+            scheduler: Scheduler::initialize(),
+            state_store: None,
         }
     }
 
-    pub fn emulate(&mut self, program: Vec<u8>) {
-        self.memory.store_program(program);
-        self.memory.set_font();
+    pub fn set_audio(&mut self, audio: Box<dyn Audio>) {
+        self.audio = audio;
+    }
+
+    pub fn set_state_store(&mut self, state_store: StateStore) {
+        self.state_store = Some(state_store);
+    }
+
+    pub fn emulate(&mut self, program: Vec<u8>) -> Result<(), EmulatorError> {
+        let result: Result<(), EmulatorError> = self.run(program);
+
+        // The audio is finished even after an error, so what was played so
+        // far can still be listened to.
+        result.and(self.audio.finish())
+    }
+
+    fn run(&mut self, program: Vec<u8>) -> Result<(), EmulatorError> {
+        // Nothing here can resume a paused machine, so breaking would freeze
+        // the game for good.
+        if self.machine.get_unknown_opcode_policy() == UnknownOpcodePolicy::Break {
+            return Err(EmulatorError::BreakWithoutDebugger);
+        }
+
+        let rom_hash: u64 = flags::hash_rom(&program);
+
+        self.machine.load_rom(program)?;
+
+        self.frontend.refresh(self.machine.framebuffer())?;
+
+        while !self.machine.is_halted() && self.frontend.is_open() {
+            for key_code in 0..Keypad::KEYS {
+                self.machine.set_key(
+                    key_code,
+                    self.frontend.is_pressed(key_code)
+                );
+            }
 
-        self.interface.clear();
-        self.interface.refresh();
+            // While rewinding, the frames go back instead of running.
+            match self.frontend.get_hotkey() {
+                Some(Hotkey::Rewind) => {
+                    self.machine.rewind(1)?;
+                },
+                Some(Hotkey::SaveState(slot)) => {
+                    self.save_state(slot, rom_hash);
+                    self.machine.run_frame()?;
+                },
+                Some(Hotkey::LoadState(slot)) => {
+                    self.load_state(slot, rom_hash);
+                    self.machine.run_frame()?;
+                },
+                None => self.machine.run_frame()?,
+            }
 
-        while self.interface.window_is_open() {
-            self.cpu.fetch_and_decode(
-                &mut self.memory, 
-                &mut self.interface
-            );
+            self.frontend.refresh(self.machine.framebuffer())?;
+            self.audio.play(&self.tone_generator.generate(self.machine.cpu()))?;
 
-            self.cpu.decrease_timers_on_tick();
+            self.scheduler.wait_for_next_frame();
+        }
+
+        Ok(())
+    }
+
+    // A slot that can't be saved or loaded shouldn't end the game, so the
+    // failures are only reported.
+    fn save_state(&self, slot: usize, rom_hash: u64) {
+        if let Some(state_store) = &self.state_store {
+            match state_store.save(rom_hash, slot, &self.machine.save_state()) {
+                Ok(()) => eprintln!("Saved the state to slot {}.", slot),
+                Err(error) => eprintln!("Warning: the state could not be saved to slot {}: {}", slot, error),
+            }
         }
     }
-}
\ No newline at end of file
+
+    fn load_state(&mut self, slot: usize, rom_hash: u64) {
+        let result: Result<(), String> = match &self.state_store {
+            Some(state_store) => match state_store.load(rom_hash, slot) {
+                Ok(Some(state)) => self.machine.load_state(&state).map_err(|error| error.to_string()),
+                Ok(None) => Err(String::from("there is no state saved in it.")),
+                Err(error) => Err(error.to_string()),
+            },
+            None => return,
+        };
+
+        match result {
+            Ok(()) => eprintln!("Loaded the state from slot {}.", slot),
+            Err(error) => eprintln!("Warning: the state in slot {} could not be loaded: {}", slot, error),
+        }
+    }
+
+    pub fn machine(&self) -> &Machine {
+        &self.machine
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::frontend::{Display, Input};
+    use crate::framebuffer::Framebuffer;
+    use mockall::{mock, Sequence};
+    use mockall::predicate::eq;
+    use std::path::PathBuf;
+    use std::{env, fs};
+
+    mock! {
+        pub Frontend {}
+
+        impl Display for Frontend {
+            fn is_open(&self) -> bool;
+            fn refresh(&mut self, framebuffer: &Framebuffer) -> Result<(), EmulatorError>;
+        }
+
+        impl Input for Frontend {
+            fn is_pressed(&self, key_code: usize) -> bool;
+            fn get_hotkey(&self) -> Option<Hotkey>;
+        }
+    }
+
+    mock! {
+        pub Audio {}
+
+        impl Audio for Audio {
+            fn play(&mut self, samples: &[f32]) -> Result<(), EmulatorError>;
+            fn finish(&mut self) -> Result<(), EmulatorError>;
+        }
+    }
+
+    #[test]
+    fn it_should_emulate_until_the_display_is_closed() {
+        let mut frontend: MockFrontend = MockFrontend::new();
+        let mut sequence: Sequence = Sequence::new();
+
+        frontend.expect_refresh().times(1).in_sequence(&mut sequence).returning(|_| Ok(()));
+        frontend.expect_is_open().times(1).in_sequence(&mut sequence).returning(|| true);
+        frontend.expect_is_pressed().times(Keypad::KEYS).returning(|_| false);
+        frontend.expect_get_hotkey().times(1).returning(|| None);
+        frontend.expect_refresh().times(1).in_sequence(&mut sequence).returning(|_| Ok(()));
+        frontend.expect_is_open().times(1).in_sequence(&mut sequence).returning(|| false);
+
+        let mut motherboard: Motherboard = Motherboard::initialize(Machine::initialize(), Box::new(frontend));
+
+        motherboard.emulate(vec![0x70, 0x01, 0x12, 0x00]).unwrap();
+
+        assert_eq!(
+            (Machine::INSTRUCTIONS_PER_FRAME / 2) as u8,
+            motherboard.machine().cpu().get_v_register(0x0)
+        );
+    }
+
+    #[test]
+    fn it_should_play_the_tone_while_the_sound_timer_runs() {
+        let mut frontend: MockFrontend = MockFrontend::new();
+        let mut audio: MockAudio = MockAudio::new();
+        let mut sequence: Sequence = Sequence::new();
+
+        frontend.expect_refresh().returning(|_| Ok(()));
+        frontend.expect_is_open().times(1).in_sequence(&mut sequence).returning(|| true);
+        frontend.expect_is_open().times(1).in_sequence(&mut sequence).returning(|| false);
+        frontend.expect_is_pressed().returning(|_| false);
+        frontend.expect_get_hotkey().returning(|| None);
+
+        audio.expect_play()
+            .withf(|samples: &[f32]| samples.len() == 735 && samples[0] == ToneGenerator::VOLUME)
+            .times(1)
+            .returning(|_| Ok(()));
+        audio.expect_finish().times(1).returning(|| Ok(()));
+
+        let mut motherboard: Motherboard = Motherboard::initialize(Machine::initialize(), Box::new(frontend));
+
+        motherboard.set_audio(Box::new(audio));
+        motherboard.emulate(vec![0x60, 0x05, 0xF0, 0x18, 0x12, 0x04]).unwrap();
+    }
+
+    #[test]
+    fn it_should_stop_emulating_when_the_audio_cannot_be_played() {
+        let mut frontend: MockFrontend = MockFrontend::new();
+        let mut audio: MockAudio = MockAudio::new();
+
+        frontend.expect_refresh().returning(|_| Ok(()));
+        frontend.expect_is_open().times(1).returning(|| true);
+        frontend.expect_is_pressed().returning(|_| false);
+        frontend.expect_get_hotkey().returning(|| None);
+
+        audio.expect_play().times(1).returning(|_| Err(EmulatorError::AudioOutput { message: String::from("No space left on device") }));
+        audio.expect_finish().times(1).returning(|| Ok(()));
+
+        let mut motherboard: Motherboard = Motherboard::initialize(Machine::initialize(), Box::new(frontend));
+
+        motherboard.set_audio(Box::new(audio));
+
+        assert_eq!(
+            Err(EmulatorError::AudioOutput { message: String::from("No space left on device") }),
+            motherboard.emulate(vec![0x70, 0x01, 0x12, 0x00])
+        );
+    }
+
+    #[test]
+    fn it_should_stop_emulating_when_the_screen_cannot_be_refreshed() {
+        let mut frontend: MockFrontend = MockFrontend::new();
+
+        frontend.expect_refresh().times(1).returning(|_| Err(EmulatorError::DisplayOutput { message: String::from("Update failed") }));
+
+        let mut motherboard: Motherboard = Motherboard::initialize(Machine::initialize(), Box::new(frontend));
+
+        assert_eq!(
+            Err(EmulatorError::DisplayOutput { message: String::from("Update failed") }),
+            motherboard.emulate(vec![0x70, 0x01, 0x12, 0x00])
+        );
+    }
+
+    #[test]
+    fn it_should_stop_emulating_when_the_program_exits() {
+        let mut frontend: MockFrontend = MockFrontend::new();
+
+        frontend.expect_refresh().times(2).returning(|_| Ok(()));
+        frontend.expect_is_open().times(1).returning(|| true);
+        frontend.expect_is_pressed().returning(|_| false);
+        frontend.expect_get_hotkey().returning(|| None);
+
+        let mut motherboard: Motherboard = Motherboard::initialize(Machine::initialize(), Box::new(frontend));
+
+        motherboard.emulate(vec![0x00, 0xFD]).unwrap();
+
+        assert!(motherboard.machine().is_halted());
+    }
+
+    #[test]
+    fn it_should_stop_emulating_on_an_error() {
+        let mut frontend: MockFrontend = MockFrontend::new();
+
+        frontend.expect_refresh().times(1).returning(|_| Ok(()));
+        frontend.expect_is_open().times(1).returning(|| true);
+        frontend.expect_is_pressed().returning(|_| false);
+        frontend.expect_get_hotkey().returning(|| None);
+
+        let mut motherboard: Motherboard = Motherboard::initialize(Machine::initialize(), Box::new(frontend));
+
+        assert_eq!(
+            Err(EmulatorError::StackUnderflow),
+            motherboard.emulate(vec![0x00, 0xEE])
+        );
+    }
+
+    #[test]
+    fn it_should_refuse_to_break_on_unknown_opcodes() {
+        let mut frontend: MockFrontend = MockFrontend::new();
+
+        frontend.expect_refresh().never();
+        frontend.expect_is_open().never();
+
+        let mut machine: Machine = Machine::initialize();
+
+        machine.set_unknown_opcode_policy(UnknownOpcodePolicy::Break);
+
+        let mut motherboard: Motherboard = Motherboard::initialize(machine, Box::new(frontend));
+
+        assert_eq!(Err(EmulatorError::BreakWithoutDebugger), motherboard.emulate(vec![0xFF, 0xFF]));
+        assert_eq!(UnknownOpcodePolicy::Break, motherboard.machine().get_unknown_opcode_policy());
+    }
+
+    #[test]
+    fn it_should_pass_the_pressed_keys_to_the_machine() {
+        let mut frontend: MockFrontend = MockFrontend::new();
+        let mut sequence: Sequence = Sequence::new();
+
+        frontend.expect_refresh().returning(|_| Ok(()));
+        frontend.expect_is_open().times(1).in_sequence(&mut sequence).returning(|| true);
+        frontend.expect_is_open().times(1).in_sequence(&mut sequence).returning(|| false);
+        frontend.expect_is_pressed().with(eq(0x5)).returning(|_| true);
+        frontend.expect_is_pressed().returning(|_| false);
+        frontend.expect_get_hotkey().returning(|| None);
+
+        let mut motherboard: Motherboard = Motherboard::initialize(Machine::initialize(), Box::new(frontend));
+
+        motherboard.emulate(vec![0xF0, 0x0A, 0x12, 0x02]).unwrap();
+
+        assert_eq!(0x5, motherboard.machine().cpu().get_v_register(0x0));
+    }
+
+    #[test]
+    fn it_should_save_and_load_states_with_the_hotkeys() {
+        let mut frontend: MockFrontend = MockFrontend::new();
+        let mut frames: usize = 0;
+        let mut hotkeys = vec![Some(Hotkey::SaveState(0x1)), None, Some(Hotkey::LoadState(0x1))].into_iter();
+        let directory: PathBuf = env::temp_dir().join(format!("chip8rs-motherboard-states-{}", std::process::id()));
+
+        frontend.expect_refresh().returning(|_| Ok(()));
+        frontend.expect_is_open().returning(move || {
+            frames += 1;
+
+            frames <= 3
+        });
+        frontend.expect_is_pressed().returning(|_| false);
+        frontend.expect_get_hotkey().times(3).returning(move || hotkeys.next().flatten());
+
+        let mut motherboard: Motherboard = Motherboard::initialize(Machine::initialize(), Box::new(frontend));
+
+        motherboard.set_state_store(StateStore::initialize(directory.clone()));
+        motherboard.emulate(vec![0x70, 0x01, 0x12, 0x00]).unwrap();
+
+        // The state was saved before the first frame, so loading it on the third
+        // frame leaves the machine as if only one frame had run.
+        assert_eq!(
+            (Machine::INSTRUCTIONS_PER_FRAME / 2) as u8,
+            motherboard.machine().cpu().get_v_register(0x0)
+        );
+
+        fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn it_should_rewind_while_the_hotkey_is_held() {
+        let mut frontend: MockFrontend = MockFrontend::new();
+        let mut frames: usize = 0;
+        let mut hotkeys = vec![None, None, None, Some(Hotkey::Rewind), Some(Hotkey::Rewind)].into_iter();
+
+        frontend.expect_refresh().returning(|_| Ok(()));
+        frontend.expect_is_open().returning(move || {
+            frames += 1;
+
+            frames <= 5
+        });
+        frontend.expect_is_pressed().returning(|_| false);
+        frontend.expect_get_hotkey().times(5).returning(move || hotkeys.next().flatten());
+
+        let mut machine: Machine = Machine::initialize();
+
+        machine.set_rewind_frames(10);
+
+        let mut motherboard: Motherboard = Motherboard::initialize(machine, Box::new(frontend));
+
+        motherboard.emulate(vec![0x70, 0x01, 0x12, 0x00]).unwrap();
+
+        assert_eq!(
+            (Machine::INSTRUCTIONS_PER_FRAME / 2) as u8,
+            motherboard.machine().cpu().get_v_register(0x0)
+        );
+    }
+}
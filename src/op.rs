@@ -0,0 +1,276 @@
+// Every instruction with its arguments taken out of the opcode, so the
+// interpreter, the disassembler, the assembler and the analysis read the
+// opcodes the same way.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Op {
+    Clear,
+    Return,
+    ScrollDown { n: u8 },
+    ScrollRight,
+    ScrollLeft,
+    Exit,
+    LowResolution,
+    HighResolution,
+    Jump { nnn: u16 },
+    Call { nnn: u16 },
+    SkipIfEqualImm { x: usize, kk: u8 },
+    SkipIfNotEqualImm { x: usize, kk: u8 },
+    SkipIfEqual { x: usize, y: usize },
+    SaveRange { x: usize, y: usize },
+    LoadRange { x: usize, y: usize },
+    LoadImm { x: usize, kk: u8 },
+    AddImm { x: usize, kk: u8 },
+    Move { x: usize, y: usize },
+    Or { x: usize, y: usize },
+    And { x: usize, y: usize },
+    Xor { x: usize, y: usize },
+    Add { x: usize, y: usize },
+    Sub { x: usize, y: usize },
+    ShiftRight { x: usize, y: usize },
+    SubReverse { x: usize, y: usize },
+    ShiftLeft { x: usize, y: usize },
+    SkipIfNotEqual { x: usize, y: usize },
+    LoadI { nnn: u16 },
+    JumpOffset { x: usize, nnn: u16 },
+    Random { x: usize, kk: u8 },
+    Draw { x: usize, y: usize, n: u8 },
+    SkipIfKey { x: usize },
+    SkipIfNotKey { x: usize },
+    LoadLongI,
+    SelectPlanes { n: u8 },
+    LoadAudio,
+    GetDelay { x: usize },
+    WaitKey { x: usize },
+    SetDelay { x: usize },
+    SetSound { x: usize },
+    AddI { x: usize },
+    Font { x: usize },
+    BigFont { x: usize },
+    SetPitch { x: usize },
+    Bcd { x: usize },
+    Store { x: usize },
+    Load { x: usize },
+    StoreFlags { x: usize },
+    LoadFlags { x: usize },
+    Unknown { opcode: u16 },
+}
+
+pub fn decode(opcode: u16) -> Op {
+    let x: usize = usize::from(opcode >> 8 & 0xF);
+    let y: usize = usize::from(opcode >> 4 & 0xF);
+    let n: u8 = (opcode & 0xF) as u8;
+    let kk: u8 = opcode as u8;
+    let nnn: u16 = opcode & 0xFFF;
+
+    match opcode & 0xF000 {
+        0x0000 => match opcode {
+            0x00E0 => Op::Clear,
+            0x00EE => Op::Return,
+            0x00FB => Op::ScrollRight,
+            0x00FC => Op::ScrollLeft,
+            0x00FD => Op::Exit,
+            0x00FE => Op::LowResolution,
+            0x00FF => Op::HighResolution,
+            _ if opcode & 0xFFF0 == 0x00C0 => Op::ScrollDown { n },
+            _ => Op::Unknown { opcode },
+        },
+        0x1000 => Op::Jump { nnn },
+        0x2000 => Op::Call { nnn },
+        0x3000 => Op::SkipIfEqualImm { x, kk },
+        0x4000 => Op::SkipIfNotEqualImm { x, kk },
+        0x5000 => match n {
+            0x0 => Op::SkipIfEqual { x, y },
+            0x2 => Op::SaveRange { x, y },
+            0x3 => Op::LoadRange { x, y },
+            _ => Op::Unknown { opcode },
+        },
+        0x6000 => Op::LoadImm { x, kk },
+        0x7000 => Op::AddImm { x, kk },
+        0x8000 => match n {
+            0x0 => Op::Move { x, y },
+            0x1 => Op::Or { x, y },
+            0x2 => Op::And { x, y },
+            0x3 => Op::Xor { x, y },
+            0x4 => Op::Add { x, y },
+            0x5 => Op::Sub { x, y },
+            0x6 => Op::ShiftRight { x, y },
+            0x7 => Op::SubReverse { x, y },
+            0xE => Op::ShiftLeft { x, y },
+            _ => Op::Unknown { opcode },
+        },
+        0x9000 if n == 0x0 => Op::SkipIfNotEqual { x, y },
+        0xA000 => Op::LoadI { nnn },
+        0xB000 => Op::JumpOffset { x, nnn },
+        0xC000 => Op::Random { x, kk },
+        0xD000 => Op::Draw { x, y, n },
+        0xE000 => match kk {
+            0x9E => Op::SkipIfKey { x },
+            0xA1 => Op::SkipIfNotKey { x },
+            _ => Op::Unknown { opcode },
+        },
+        0xF000 => match kk {
+            0x00 if x == 0x0 => Op::LoadLongI,
+            0x01 => Op::SelectPlanes { n: x as u8 },
+            0x02 if x == 0x0 => Op::LoadAudio,
+            0x07 => Op::GetDelay { x },
+            0x0A => Op::WaitKey { x },
+            0x15 => Op::SetDelay { x },
+            0x18 => Op::SetSound { x },
+            0x1E => Op::AddI { x },
+            0x29 => Op::Font { x },
+            0x30 => Op::BigFont { x },
+            0x33 => Op::Bcd { x },
+            0x3A => Op::SetPitch { x },
+            0x55 => Op::Store { x },
+            0x65 => Op::Load { x },
+            0x75 => Op::StoreFlags { x },
+            0x85 => Op::LoadFlags { x },
+            _ => Op::Unknown { opcode },
+        },
+        _ => Op::Unknown { opcode },
+    }
+}
+
+impl Op {
+    pub fn encode(&self) -> u16 {
+        let with_x = |base: u16, x: usize| base | (x as u16) << 8;
+        let with_xy = |base: u16, x: usize, y: usize| base | (x as u16) << 8 | (y as u16) << 4;
+
+        match *self {
+            Op::Clear => 0x00E0,
+            Op::Return => 0x00EE,
+            Op::ScrollDown { n } => 0x00C0 | u16::from(n),
+            Op::ScrollRight => 0x00FB,
+            Op::ScrollLeft => 0x00FC,
+            Op::Exit => 0x00FD,
+            Op::LowResolution => 0x00FE,
+            Op::HighResolution => 0x00FF,
+            Op::Jump { nnn } => 0x1000 | nnn,
+            Op::Call { nnn } => 0x2000 | nnn,
+            Op::SkipIfEqualImm { x, kk } => with_x(0x3000 | u16::from(kk), x),
+            Op::SkipIfNotEqualImm { x, kk } => with_x(0x4000 | u16::from(kk), x),
+            Op::SkipIfEqual { x, y } => with_xy(0x5000, x, y),
+            Op::SaveRange { x, y } => with_xy(0x5002, x, y),
+            Op::LoadRange { x, y } => with_xy(0x5003, x, y),
+            Op::LoadImm { x, kk } => with_x(0x6000 | u16::from(kk), x),
+            Op::AddImm { x, kk } => with_x(0x7000 | u16::from(kk), x),
+            Op::Move { x, y } => with_xy(0x8000, x, y),
+            Op::Or { x, y } => with_xy(0x8001, x, y),
+            Op::And { x, y } => with_xy(0x8002, x, y),
+            Op::Xor { x, y } => with_xy(0x8003, x, y),
+            Op::Add { x, y } => with_xy(0x8004, x, y),
+            Op::Sub { x, y } => with_xy(0x8005, x, y),
+            Op::ShiftRight { x, y } => with_xy(0x8006, x, y),
+            Op::SubReverse { x, y } => with_xy(0x8007, x, y),
+            Op::ShiftLeft { x, y } => with_xy(0x800E, x, y),
+            Op::SkipIfNotEqual { x, y } => with_xy(0x9000, x, y),
+            Op::LoadI { nnn } => 0xA000 | nnn,
+            Op::JumpOffset { x, nnn } => with_x(0xB000 | nnn, x),
+            Op::Random { x, kk } => with_x(0xC000 | u16::from(kk), x),
+            Op::Draw { x, y, n } => with_xy(0xD000 | u16::from(n), x, y),
+            Op::SkipIfKey { x } => with_x(0xE09E, x),
+            Op::SkipIfNotKey { x } => with_x(0xE0A1, x),
+            Op::LoadLongI => 0xF000,
+            Op::SelectPlanes { n } => 0xF001 | u16::from(n) << 8,
+            Op::LoadAudio => 0xF002,
+            Op::GetDelay { x } => with_x(0xF007, x),
+            Op::WaitKey { x } => with_x(0xF00A, x),
+            Op::SetDelay { x } => with_x(0xF015, x),
+            Op::SetSound { x } => with_x(0xF018, x),
+            Op::AddI { x } => with_x(0xF01E, x),
+            Op::Font { x } => with_x(0xF029, x),
+            Op::BigFont { x } => with_x(0xF030, x),
+            Op::Bcd { x } => with_x(0xF033, x),
+            Op::SetPitch { x } => with_x(0xF03A, x),
+            Op::Store { x } => with_x(0xF055, x),
+            Op::Load { x } => with_x(0xF065, x),
+            Op::StoreFlags { x } => with_x(0xF075, x),
+            Op::LoadFlags { x } => with_x(0xF085, x),
+            Op::Unknown { opcode } => opcode,
+        }
+    }
+
+    // Only run when the XO-CHIP extensions are on.
+    pub fn is_xo_chip(&self) -> bool {
+        matches!(
+            self,
+            Op::SaveRange { .. } | Op::LoadRange { .. } | Op::LoadLongI | Op::SelectPlanes { .. } | Op::LoadAudio | Op::SetPitch { .. }
+        )
+    }
+
+    // Only run on the profiles with the SUPER-CHIP big font.
+    pub fn is_super_chip(&self) -> bool {
+        matches!(self, Op::BigFont { .. })
+    }
+
+    // F000 is followed by the address it loads.
+    pub fn get_length(&self) -> usize {
+        match self {
+            Op::LoadLongI => 4,
+            _ => 2,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+    use super::*;
+
+    #[test_case(0x00C4, Op::ScrollDown { n: 0x4 } ; "with 00Cn")]
+    #[test_case(0x00EE, Op::Return ; "with 00EE")]
+    #[test_case(0x12A4, Op::Jump { nnn: 0x2A4 } ; "with 1nnn")]
+    #[test_case(0x5122, Op::SaveRange { x: 0x1, y: 0x2 } ; "with 5xy2")]
+    #[test_case(0x7A01, Op::AddImm { x: 0xA, kk: 0x01 } ; "with 7xkk")]
+    #[test_case(0x812E, Op::ShiftLeft { x: 0x1, y: 0x2 } ; "with 8xyE")]
+    #[test_case(0xB312, Op::JumpOffset { x: 0x3, nnn: 0x312 } ; "with Bnnn")]
+    #[test_case(0xD120, Op::Draw { x: 0x1, y: 0x2, n: 0x0 } ; "with Dxy0")]
+    #[test_case(0xF000, Op::LoadLongI ; "with F000")]
+    #[test_case(0xF301, Op::SelectPlanes { n: 0x3 } ; "with Fn01")]
+    #[test_case(0xF53A, Op::SetPitch { x: 0x5 } ; "with Fx3A")]
+    #[test_case(0x0123, Op::Unknown { opcode: 0x0123 } ; "with a machine code routine")]
+    #[test_case(0x9121, Op::Unknown { opcode: 0x9121 } ; "with an unknown 9xyn")]
+    #[test_case(0xF100, Op::Unknown { opcode: 0xF100 } ; "with F000 on a register")]
+    #[test_case(0xF102, Op::Unknown { opcode: 0xF102 } ; "with F002 on a register")]
+    fn it_should_decode_an_opcode(opcode: u16, op: Op) {
+        assert_eq!(op, decode(opcode));
+    }
+
+    #[test]
+    fn it_should_encode_every_opcode_back() {
+        for opcode in 0x0000..=0xFFFF {
+            assert_eq!(opcode, decode(opcode).encode(), "{:04X} was decoded as {:?}", opcode, decode(opcode));
+        }
+    }
+
+    #[test]
+    fn it_should_decode_every_known_opcode_into_one_op() {
+        let known: usize = (0x0000..=0xFFFF)
+            .filter(|opcode| !matches!(decode(*opcode), Op::Unknown { .. }))
+            .count();
+
+        // The seven fixed ones of 0nnn and the 16 of 00Cn, every x and nnn of
+        // ten prefixes, three 5xyn, nine 8xyn and 9xy0, two Exkk, then F000,
+        // F002 and fourteen Fxkk.
+        let expected: usize = 7 + 0x10
+            + 10 * 0x1000
+            + 13 * 0x100
+            + 2 * 0x10
+            + 2 + 14 * 0x10;
+
+        assert_eq!(expected, known);
+    }
+
+    #[test_case(Op::SaveRange { x: 0x1, y: 0x2 }, true ; "with a range store")]
+    #[test_case(Op::SetPitch { x: 0x1 }, true ; "with the pitch")]
+    #[test_case(Op::Store { x: 0x1 }, false ; "with a store")]
+    fn it_should_tell_the_xo_chip_instructions(op: Op, is_xo_chip: bool) {
+        assert_eq!(is_xo_chip, op.is_xo_chip());
+    }
+
+    #[test_case(Op::BigFont { x: 0x1 }, true ; "with the big font")]
+    #[test_case(Op::Font { x: 0x1 }, false ; "with the font")]
+    fn it_should_tell_the_super_chip_instructions(op: Op, is_super_chip: bool) {
+        assert_eq!(is_super_chip, op.is_super_chip());
+    }
+}
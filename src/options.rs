@@ -0,0 +1,323 @@
+use chip8rs::machine::Machine;
+use chip8rs::palette::Palette;
+use chip8rs::policy::UnknownOpcodePolicy;
+use chip8rs::quirks::Quirks;
+
+#[derive(Debug, PartialEq)]
+pub struct Options {
+    pub rom_path: String,
+    pub instructions_per_frame: usize,
+    pub headless: bool,
+    pub frames: usize,
+    pub until_idle: bool,
+    pub dump: Option<String>,
+    pub unknown_opcode_policy: UnknownOpcodePolicy,
+    pub quirks: Quirks,
+    pub flags_directory: Option<String>,
+    pub states_directory: Option<String>,
+    pub palette: Palette,
+    pub wav: Option<String>,
+    pub rewind_frames: usize,
+    pub decode_cache: bool,
+    pub debug: bool,
+    pub gdb: Option<u16>,
+}
+
+impl Options {
+    pub const HEADLESS_FRAMES: usize = 600;
+    // 30 seconds of gameplay.
+    pub const REWIND_FRAMES: usize = 1800;
+
+    pub fn parse(args: Vec<String>) -> Result<Self, String> {
+        let mut rom_path: Option<String> = None;
+        let mut instructions_per_frame: usize = Machine::INSTRUCTIONS_PER_FRAME;
+        let mut headless: bool = false;
+        let mut frames: usize = Options::HEADLESS_FRAMES;
+        let mut until_idle: bool = false;
+        let mut dump: Option<String> = None;
+        let mut unknown_opcode_policy: UnknownOpcodePolicy = UnknownOpcodePolicy::Ignore;
+        let mut quirks: Quirks = Quirks::DEFAULT;
+        let mut flags_directory: Option<String> = None;
+        let mut states_directory: Option<String> = None;
+        let mut palette: Palette = Palette::initialize();
+        let mut wav: Option<String> = None;
+        let mut rewind_frames: usize = Options::REWIND_FRAMES;
+        let mut decode_cache: bool = false;
+        let mut debug: bool = false;
+        let mut gdb: Option<u16> = None;
+
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--instructions-per-frame" => {
+                    instructions_per_frame = Options::parse_number(&arg, args.next())?;
+                },
+                "--headless" => headless = true,
+                "--frames" => frames = Options::parse_number(&arg, args.next())?,
+                "--until-idle" => until_idle = true,
+                "--dump" => {
+                    dump = Some(args.next().ok_or("The option --dump requires a path.")?);
+                },
+                "--unknown-opcode" => {
+                    unknown_opcode_policy = args.next()
+                        .ok_or("The option --unknown-opcode requires a policy.")?
+                        .parse()?;
+                },
+                "--quirks" => {
+                    quirks = args.next()
+                        .ok_or("The option --quirks requires a profile.")?
+                        .parse()?;
+                },
+                "--flags-directory" => {
+                    flags_directory = Some(args.next().ok_or("The option --flags-directory requires a path.")?);
+                },
+                "--states-directory" => {
+                    states_directory = Some(args.next().ok_or("The option --states-directory requires a path.")?);
+                },
+                "--palette" => {
+                    palette = args.next()
+                        .ok_or("The option --palette requires a list of colours.")?
+                        .parse()?;
+                },
+                "--wav" => {
+                    wav = Some(args.next().ok_or("The option --wav requires a path.")?);
+                },
+                "--rewind-frames" => rewind_frames = Options::parse_number(&arg, args.next())?,
+                "--decode-cache" => decode_cache = true,
+                "--debug" => debug = true,
+                "--gdb" => {
+                    gdb = Some(args.next()
+                        .and_then(|port| port.parse().ok())
+                        .ok_or("The option --gdb requires a port.")?);
+                },
+                _ if arg.starts_with("--") => return Err(format!("Unknown option {}.", arg)),
+                _ => rom_path = Some(arg),
+            }
+        }
+
+        Ok(Options {
+            rom_path: rom_path.ok_or("The location to the chip8 ROM is required.")?,
+            instructions_per_frame,
+            headless,
+            frames,
+            until_idle,
+            dump,
+            unknown_opcode_policy,
+            quirks,
+            flags_directory,
+            states_directory,
+            palette,
+            wav,
+            rewind_frames,
+            decode_cache,
+            debug,
+            gdb,
+        })
+    }
+
+    fn parse_number(option: &str, value: Option<String>) -> Result<usize, String> {
+        value.and_then(|value| value.parse().ok())
+            .ok_or(format!("The option {} requires a number.", option))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn arguments(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn it_should_parse_the_rom_path() {
+        let options: Options = Options::parse(arguments(&["Pong.ch8"])).unwrap();
+
+        assert_eq!(Options {
+            rom_path: String::from("Pong.ch8"),
+            instructions_per_frame: Machine::INSTRUCTIONS_PER_FRAME,
+            headless: false,
+            frames: Options::HEADLESS_FRAMES,
+            until_idle: false,
+            dump: None,
+            unknown_opcode_policy: UnknownOpcodePolicy::Ignore,
+            quirks: Quirks::DEFAULT,
+            flags_directory: None,
+            states_directory: None,
+            palette: Palette::initialize(),
+            wav: None,
+            rewind_frames: Options::REWIND_FRAMES,
+            decode_cache: false,
+            debug: false,
+            gdb: None,
+        }, options);
+    }
+
+    #[test]
+    fn it_should_parse_the_headless_options() {
+        let options: Options = Options::parse(arguments(&[
+            "--headless", "--frames", "120", "--until-idle", "--dump", "screen.txt", "Pong.ch8"
+        ])).unwrap();
+
+        assert!(options.headless);
+        assert_eq!(120, options.frames);
+        assert!(options.until_idle);
+        assert_eq!(Some(String::from("screen.txt")), options.dump);
+    }
+
+    #[test]
+    fn it_should_parse_the_unknown_opcode_policy() {
+        let options: Options = Options::parse(
+            arguments(&["--unknown-opcode", "halt", "Pong.ch8"])
+        ).unwrap();
+
+        assert_eq!(UnknownOpcodePolicy::Halt, options.unknown_opcode_policy);
+    }
+
+    #[test]
+    fn it_should_fail_with_an_unknown_opcode_policy() {
+        assert_eq!(
+            Err(String::from("Unknown opcode policy explode, expected ignore, warn, halt or break.")),
+            Options::parse(arguments(&["--unknown-opcode", "explode", "Pong.ch8"]))
+        );
+    }
+
+    #[test]
+    fn it_should_parse_the_quirks_profile() {
+        let options: Options = Options::parse(
+            arguments(&["--quirks", "vip", "Pong.ch8"])
+        ).unwrap();
+
+        assert_eq!(Quirks::COSMAC_VIP, options.quirks);
+    }
+
+    #[test]
+    fn it_should_fail_without_a_quirks_profile() {
+        assert_eq!(
+            Err(String::from("The option --quirks requires a profile.")),
+            Options::parse(arguments(&["Pong.ch8", "--quirks"]))
+        );
+    }
+
+    #[test]
+    fn it_should_parse_the_flags_directory() {
+        let options: Options = Options::parse(
+            arguments(&["--flags-directory", "saves", "Pong.ch8"])
+        ).unwrap();
+
+        assert_eq!(Some(String::from("saves")), options.flags_directory);
+    }
+
+    #[test]
+    fn it_should_parse_the_states_directory() {
+        let options: Options = Options::parse(
+            arguments(&["--states-directory", "states", "Pong.ch8"])
+        ).unwrap();
+
+        assert_eq!(Some(String::from("states")), options.states_directory);
+    }
+
+    #[test]
+    fn it_should_parse_the_palette() {
+        let options: Options = Options::parse(
+            arguments(&["--palette", "996600,FFCC00,FF6600,662200", "Pong.ch8"])
+        ).unwrap();
+
+        assert_eq!(0xFFCC00, options.palette.get_colour(0x1));
+    }
+
+    #[test]
+    fn it_should_fail_with_an_invalid_palette() {
+        assert_eq!(
+            Err(String::from("The palette has 1 colours, but it needs 4 or 16.")),
+            Options::parse(arguments(&["--palette", "FFFFFF", "Pong.ch8"]))
+        );
+    }
+
+    #[test]
+    fn it_should_parse_the_wav_path() {
+        let options: Options = Options::parse(
+            arguments(&["--wav", "sound.wav", "Pong.ch8"])
+        ).unwrap();
+
+        assert_eq!(Some(String::from("sound.wav")), options.wav);
+    }
+
+    #[test]
+    fn it_should_parse_the_rewind_frames() {
+        let options: Options = Options::parse(arguments(&["--rewind-frames", "300", "Pong.ch8"])).unwrap();
+
+        assert_eq!(300, options.rewind_frames);
+    }
+
+    #[test]
+    fn it_should_parse_the_decode_cache_flag() {
+        let options: Options = Options::parse(arguments(&["--decode-cache", "Pong.ch8"])).unwrap();
+
+        assert!(options.decode_cache);
+    }
+
+    #[test]
+    fn it_should_parse_the_debug_flag() {
+        let options: Options = Options::parse(arguments(&["--debug", "Pong.ch8"])).unwrap();
+
+        assert!(options.debug);
+    }
+
+    #[test]
+    fn it_should_parse_the_gdb_port() {
+        let options: Options = Options::parse(arguments(&["--gdb", "9000", "Pong.ch8"])).unwrap();
+
+        assert_eq!(Some(9000), options.gdb);
+    }
+
+    #[test]
+    fn it_should_fail_with_an_invalid_gdb_port() {
+        assert_eq!(
+            Err(String::from("The option --gdb requires a port.")),
+            Options::parse(arguments(&["--gdb", "70000", "Pong.ch8"]))
+        );
+    }
+
+    #[test]
+    fn it_should_fail_without_a_dump_path() {
+        assert_eq!(
+            Err(String::from("The option --dump requires a path.")),
+            Options::parse(arguments(&["Pong.ch8", "--dump"]))
+        );
+    }
+
+    #[test]
+    fn it_should_parse_the_instructions_per_frame() {
+        let options: Options = Options::parse(
+            arguments(&["--instructions-per-frame", "30", "Pong.ch8"])
+        ).unwrap();
+
+        assert_eq!(30, options.instructions_per_frame);
+    }
+
+    #[test]
+    fn it_should_fail_without_a_rom_path() {
+        assert_eq!(
+            Err(String::from("The location to the chip8 ROM is required.")),
+            Options::parse(arguments(&[]))
+        );
+    }
+
+    #[test]
+    fn it_should_fail_with_an_invalid_number() {
+        assert_eq!(
+            Err(String::from("The option --instructions-per-frame requires a number.")),
+            Options::parse(arguments(&["--instructions-per-frame", "fast", "Pong.ch8"]))
+        );
+    }
+
+    #[test]
+    fn it_should_fail_with_an_unknown_option() {
+        assert_eq!(
+            Err(String::from("Unknown option --turbo.")),
+            Options::parse(arguments(&["--turbo", "Pong.ch8"]))
+        );
+    }
+}
@@ -0,0 +1,80 @@
+use std::str::FromStr;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Palette {
+    colours: Vec<u32>,
+}
+
+impl Palette {
+    pub fn initialize() -> Self {
+        Palette {
+            colours: vec![0x000000, 0xFFFFFF, 0xAAAAAA, 0x555555],
+        }
+    }
+
+    // A palette with four colours only shows the first two planes.
+    pub fn get_colour(&self, colour: u8) -> u32 {
+        self.colours[colour as usize % self.colours.len()]
+    }
+}
+
+impl FromStr for Palette {
+    type Err = String;
+
+    fn from_str(colours: &str) -> Result<Self, Self::Err> {
+        let colours: Vec<u32> = colours.split(',')
+            .map(|colour| u32::from_str_radix(colour.trim().trim_start_matches('#'), 16)
+                .ok()
+                .filter(|colour| *colour <= 0xFFFFFF)
+                .ok_or(format!("Unknown colour {}, expected an RGB hex code like FFCC00.", colour)))
+            .collect::<Result<Vec<u32>, String>>()?;
+
+        match colours.len() {
+            4 | 16 => Ok(Palette { colours }),
+            amount => Err(format!("The palette has {} colours, but it needs 4 or 16.", amount)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_should_initialize_a_black_and_white_palette() {
+        let palette: Palette = Palette::initialize();
+
+        assert_eq!(0x000000, palette.get_colour(0x0));
+        assert_eq!(0xFFFFFF, palette.get_colour(0x1));
+    }
+
+    #[test]
+    fn it_should_parse_a_palette() {
+        let palette: Palette = "996600,#FFCC00, FF6600,662200".parse().unwrap();
+
+        assert_eq!(vec![0x996600, 0xFFCC00, 0xFF6600, 0x662200], palette.colours);
+    }
+
+    #[test]
+    fn it_should_wrap_colours_missing_from_a_small_palette() {
+        let palette: Palette = "000000,111111,222222,333333".parse().unwrap();
+
+        assert_eq!(0x111111, palette.get_colour(0x5));
+    }
+
+    #[test]
+    fn it_should_fail_to_parse_an_unknown_colour() {
+        assert_eq!(
+            Err(String::from("Unknown colour purple, expected an RGB hex code like FFCC00.")),
+            "000000,purple,222222,333333".parse::<Palette>()
+        );
+    }
+
+    #[test]
+    fn it_should_fail_to_parse_a_palette_with_the_wrong_amount_of_colours() {
+        assert_eq!(
+            Err(String::from("The palette has 2 colours, but it needs 4 or 16.")),
+            "000000,FFFFFF".parse::<Palette>()
+        );
+    }
+}
@@ -0,0 +1,45 @@
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum UnknownOpcodePolicy {
+    Ignore,
+    Warn,
+    Halt,
+    Break,
+}
+
+impl FromStr for UnknownOpcodePolicy {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "ignore" => Ok(UnknownOpcodePolicy::Ignore),
+            "warn" => Ok(UnknownOpcodePolicy::Warn),
+            "halt" => Ok(UnknownOpcodePolicy::Halt),
+            "break" => Ok(UnknownOpcodePolicy::Break),
+            _ => Err(format!("Unknown opcode policy {}, expected ignore, warn, halt or break.", name)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+    use super::*;
+
+    #[test_case("ignore", UnknownOpcodePolicy::Ignore ; "with ignore")]
+    #[test_case("warn", UnknownOpcodePolicy::Warn ; "with warn")]
+    #[test_case("halt", UnknownOpcodePolicy::Halt ; "with halt")]
+    #[test_case("break", UnknownOpcodePolicy::Break ; "with break")]
+    fn it_should_parse_a_policy(name: &str, policy: UnknownOpcodePolicy) {
+        assert_eq!(Ok(policy), name.parse());
+    }
+
+    #[test]
+    fn it_should_fail_to_parse_an_unknown_policy() {
+        assert_eq!(
+            Err(String::from("Unknown opcode policy panic, expected ignore, warn, halt or break.")),
+            "panic".parse::<UnknownOpcodePolicy>()
+        );
+    }
+}
@@ -0,0 +1,239 @@
+use crate::cpu::Cpu;
+use crate::error::EmulatorError;
+use crate::state::{StateReader, StateWriter};
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum IndexIncrement {
+    Unchanged,
+    ByX,
+    ByXPlusOne,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Quirks {
+    // 8xy6 and 8xyE shift Vx in place instead of putting the shifted Vy on Vx.
+    pub shift_vx_in_place: bool,
+    // How Fx55 and Fx65 leave the I register after accessing the memory.
+    pub load_store_increment: IndexIncrement,
+    // Bnnn jumps to nnn plus Vx (with x being the highest nibble of nnn) instead of V0.
+    pub jump_with_vx: bool,
+    // Sprites drawn past the edges of the screen show up on the other side instead of being cut.
+    pub wrap_sprites: bool,
+    // 8xy1, 8xy2 and 8xy3 set VF to 0.
+    pub reset_vf_on_logic: bool,
+    // Dxyn waits for the next frame, so at most one sprite is drawn per frame.
+    pub wait_for_display: bool,
+    // How many RPL user flags Fx75 and Fx85 can access.
+    pub rpl_flags: usize,
+    // Enables the SUPER-CHIP big font and the Fx30 instruction pointing I at it.
+    pub super_chip: bool,
+    // Enables the XO-CHIP extensions: 64 KiB of memory, the F000 nnnn long load
+    // and the Fn01 drawing plane selection.
+    pub xo_chip: bool,
+}
+
+impl Quirks {
+    // How chip8rs behaved before the profiles existed, kept as the default so
+    // ROMs run without a profile don't change.
+    pub const DEFAULT: Quirks = Quirks {
+        shift_vx_in_place: true,
+        load_store_increment: IndexIncrement::Unchanged,
+        jump_with_vx: false,
+        wrap_sprites: false,
+        reset_vf_on_logic: false,
+        wait_for_display: false,
+        rpl_flags: 16,
+        super_chip: false,
+        xo_chip: false,
+    };
+
+    pub const COSMAC_VIP: Quirks = Quirks {
+        shift_vx_in_place: false,
+        load_store_increment: IndexIncrement::ByXPlusOne,
+        jump_with_vx: false,
+        wrap_sprites: false,
+        reset_vf_on_logic: true,
+        wait_for_display: true,
+        rpl_flags: 8,
+        super_chip: false,
+        xo_chip: false,
+    };
+
+    pub const CHIP_48: Quirks = Quirks {
+        shift_vx_in_place: true,
+        load_store_increment: IndexIncrement::ByX,
+        jump_with_vx: true,
+        wrap_sprites: false,
+        reset_vf_on_logic: false,
+        wait_for_display: false,
+        rpl_flags: 8,
+        super_chip: false,
+        xo_chip: false,
+    };
+
+    pub const SUPER_CHIP: Quirks = Quirks {
+        shift_vx_in_place: true,
+        load_store_increment: IndexIncrement::Unchanged,
+        jump_with_vx: true,
+        wrap_sprites: false,
+        reset_vf_on_logic: false,
+        wait_for_display: false,
+        rpl_flags: 8,
+        super_chip: true,
+        xo_chip: false,
+    };
+
+    pub const OCTO: Quirks = Quirks {
+        shift_vx_in_place: false,
+        load_store_increment: IndexIncrement::ByXPlusOne,
+        jump_with_vx: false,
+        wrap_sprites: true,
+        reset_vf_on_logic: false,
+        wait_for_display: false,
+        rpl_flags: 16,
+        super_chip: false,
+        xo_chip: false,
+    };
+
+    pub const XO_CHIP: Quirks = Quirks {
+        shift_vx_in_place: false,
+        load_store_increment: IndexIncrement::ByXPlusOne,
+        jump_with_vx: false,
+        wrap_sprites: true,
+        reset_vf_on_logic: false,
+        wait_for_display: false,
+        rpl_flags: 16,
+        super_chip: true,
+        xo_chip: true,
+    };
+
+    pub fn save_state(&self, writer: &mut StateWriter) {
+        writer.write_bool(self.shift_vx_in_place);
+        writer.write_u8(match self.load_store_increment {
+            IndexIncrement::Unchanged => 0x0,
+            IndexIncrement::ByX => 0x1,
+            IndexIncrement::ByXPlusOne => 0x2,
+        });
+        writer.write_bool(self.jump_with_vx);
+        writer.write_bool(self.wrap_sprites);
+        writer.write_bool(self.reset_vf_on_logic);
+        writer.write_bool(self.wait_for_display);
+        writer.write_u8(self.rpl_flags as u8);
+        writer.write_bool(self.super_chip);
+        writer.write_bool(self.xo_chip);
+    }
+
+    pub fn load_state(reader: &mut StateReader) -> Result<Self, EmulatorError> {
+        let shift_vx_in_place: bool = reader.read_bool()?;
+        let load_store_increment: IndexIncrement = match reader.read_u8()? {
+            0x0 => IndexIncrement::Unchanged,
+            0x1 => IndexIncrement::ByX,
+            0x2 => IndexIncrement::ByXPlusOne,
+            _ => return Err(EmulatorError::InvalidState),
+        };
+        let jump_with_vx: bool = reader.read_bool()?;
+        let wrap_sprites: bool = reader.read_bool()?;
+        let reset_vf_on_logic: bool = reader.read_bool()?;
+        let wait_for_display: bool = reader.read_bool()?;
+        let rpl_flags: usize = reader.read_u8()? as usize;
+
+        if rpl_flags > Cpu::RPL_FLAGS {
+            return Err(EmulatorError::InvalidState);
+        }
+
+        let super_chip: bool = reader.read_bool()?;
+
+        Ok(Quirks {
+            shift_vx_in_place,
+            load_store_increment,
+            jump_with_vx,
+            wrap_sprites,
+            reset_vf_on_logic,
+            wait_for_display,
+            rpl_flags,
+            super_chip,
+            xo_chip: reader.read_bool()?,
+        })
+    }
+}
+
+impl FromStr for Quirks {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "default" => Ok(Quirks::DEFAULT),
+            "vip" => Ok(Quirks::COSMAC_VIP),
+            "chip48" => Ok(Quirks::CHIP_48),
+            "schip" => Ok(Quirks::SUPER_CHIP),
+            "octo" => Ok(Quirks::OCTO),
+            "xochip" => Ok(Quirks::XO_CHIP),
+            _ => Err(format!("Unknown quirks profile {}, expected default, vip, chip48, schip, octo or xochip.", name)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+    use super::*;
+
+    #[test_case("default", Quirks::DEFAULT ; "with the default profile")]
+    #[test_case("vip", Quirks::COSMAC_VIP ; "with the cosmac vip profile")]
+    #[test_case("chip48", Quirks::CHIP_48 ; "with the chip-48 profile")]
+    #[test_case("schip", Quirks::SUPER_CHIP ; "with the super-chip profile")]
+    #[test_case("octo", Quirks::OCTO ; "with the octo profile")]
+    #[test_case("xochip", Quirks::XO_CHIP ; "with the xo-chip profile")]
+    fn it_should_parse_a_profile(name: &str, quirks: Quirks) {
+        assert_eq!(Ok(quirks), name.parse());
+    }
+
+    // The shift source, the I increment, the Bnnn register, the wrapping, the VF
+    // reset and the display wait of each platform.
+    #[test_case(Quirks::DEFAULT, true, IndexIncrement::Unchanged, false, false, false, false ; "with the default profile")]
+    #[test_case(Quirks::COSMAC_VIP, false, IndexIncrement::ByXPlusOne, false, false, true, true ; "with the cosmac vip profile")]
+    #[test_case(Quirks::CHIP_48, true, IndexIncrement::ByX, true, false, false, false ; "with the chip-48 profile")]
+    #[test_case(Quirks::SUPER_CHIP, true, IndexIncrement::Unchanged, true, false, false, false ; "with the super-chip profile")]
+    #[test_case(Quirks::OCTO, false, IndexIncrement::ByXPlusOne, false, true, false, false ; "with the octo profile")]
+    #[test_case(Quirks::XO_CHIP, false, IndexIncrement::ByXPlusOne, false, true, false, false ; "with the xo-chip profile")]
+    fn it_should_follow_the_behavior_of_each_platform(
+        quirks: Quirks,
+        shift_vx_in_place: bool,
+        load_store_increment: IndexIncrement,
+        jump_with_vx: bool,
+        wrap_sprites: bool,
+        reset_vf_on_logic: bool,
+        wait_for_display: bool,
+    ) {
+        assert_eq!(shift_vx_in_place, quirks.shift_vx_in_place);
+        assert_eq!(load_store_increment, quirks.load_store_increment);
+        assert_eq!(jump_with_vx, quirks.jump_with_vx);
+        assert_eq!(wrap_sprites, quirks.wrap_sprites);
+        assert_eq!(reset_vf_on_logic, quirks.reset_vf_on_logic);
+        assert_eq!(wait_for_display, quirks.wait_for_display);
+    }
+
+    #[test_case(Quirks::COSMAC_VIP ; "with the cosmac vip profile")]
+    #[test_case(Quirks::CHIP_48 ; "with the chip-48 profile")]
+    #[test_case(Quirks::SUPER_CHIP ; "with the super-chip profile")]
+    #[test_case(Quirks::XO_CHIP ; "with the xo-chip profile")]
+    fn it_should_save_and_load_the_state(quirks: Quirks) {
+        let mut writer: StateWriter = StateWriter::initialize();
+
+        quirks.save_state(&mut writer);
+
+        let bytes: Vec<u8> = writer.into_bytes();
+        let mut reader: StateReader = StateReader::initialize(&bytes).unwrap();
+
+        assert_eq!(Ok(quirks), Quirks::load_state(&mut reader));
+    }
+
+    #[test]
+    fn it_should_fail_to_parse_an_unknown_profile() {
+        assert_eq!(
+            Err(String::from("Unknown quirks profile eti660, expected default, vip, chip48, schip, octo or xochip.")),
+            "eti660".parse::<Quirks>()
+        );
+    }
+}
@@ -0,0 +1,83 @@
+// xorshift64*, which is small enough to keep its whole state in a save state
+// and makes a run reproducible from its seed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Random {
+    state: u64,
+}
+
+impl Random {
+    pub const DEFAULT_SEED: u64 = 0x2545F4914F6CDD1D;
+
+    pub fn initialize(seed: u64) -> Self {
+        let mut random: Random = Random { state: 0x0 };
+
+        random.set_state(seed);
+
+        random
+    }
+
+    pub fn next_byte(&mut self) -> u8 {
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+
+        (self.state.wrapping_mul(0x2545F4914F6CDD1D) >> 56) as u8
+    }
+
+    // The generator would only ever return zeros from a zero state.
+    pub fn set_state(&mut self, state: u64) {
+        self.state = match state {
+            0x0 => Random::DEFAULT_SEED,
+            _ => state,
+        };
+    }
+
+    pub fn get_state(&self) -> u64 {
+        self.state
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_should_repeat_the_bytes_of_a_seed() {
+        let mut first: Random = Random::initialize(0xCAFE);
+        let mut second: Random = Random::initialize(0xCAFE);
+
+        for _ in 0..100 {
+            assert_eq!(first.next_byte(), second.next_byte());
+        }
+    }
+
+    #[test]
+    fn it_should_resume_from_a_saved_state() {
+        let mut random: Random = Random::initialize(0xCAFE);
+
+        random.next_byte();
+
+        let mut restored: Random = Random::initialize(random.get_state());
+
+        assert_eq!(random.next_byte(), restored.next_byte());
+    }
+
+    #[test]
+    fn it_should_replace_a_zero_seed() {
+        let random: Random = Random::initialize(0x0);
+
+        assert_eq!(Random::DEFAULT_SEED, random.get_state());
+    }
+
+    #[test]
+    fn it_should_generate_every_byte() {
+        let mut random: Random = Random::initialize(0x1);
+        let mut generated: [bool; 256] = [false; 256];
+
+        for _ in 0..10000 {
+            generated[random.next_byte() as usize] = true;
+        }
+
+        assert!(generated.iter().all(|generated| *generated));
+    }
+}
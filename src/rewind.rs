@@ -0,0 +1,235 @@
+use std::collections::VecDeque;
+
+// Only the newest state is kept whole. Every older one is stored as the
+// difference to the state that came after it, which is mostly zeros from one
+// frame to the next and compresses down to a few bytes.
+pub struct RewindBuffer {
+    capacity: usize,
+    latest: Option<Vec<u8>>,
+    deltas: VecDeque<Vec<u8>>,
+}
+
+impl RewindBuffer {
+    pub fn initialize(capacity: usize) -> Self {
+        RewindBuffer {
+            capacity,
+            latest: None,
+            deltas: VecDeque::new(),
+        }
+    }
+
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+
+        self.trim();
+    }
+
+    pub fn get_capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn len(&self) -> usize {
+        self.deltas.len() + usize::from(self.latest.is_some())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.latest.is_none()
+    }
+
+    pub fn push(&mut self, state: Vec<u8>) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        if let Some(latest) = self.latest.take() {
+            self.deltas.push_back(compress(&state, &latest));
+        }
+
+        self.latest = Some(state);
+
+        self.trim();
+    }
+
+    pub fn pop(&mut self) -> Option<Vec<u8>> {
+        let latest: Vec<u8> = self.latest.take()?;
+
+        self.latest = self.deltas.pop_back().map(|delta| decompress(&latest, &delta));
+
+        Some(latest)
+    }
+
+    fn trim(&mut self) {
+        while self.len() > self.capacity {
+            if self.deltas.pop_front().is_none() {
+                self.latest = None;
+            }
+        }
+    }
+}
+
+// The target is XORed with the base and the result is stored as runs of
+// unchanged bytes followed by runs of changed ones.
+fn compress(base: &[u8], target: &[u8]) -> Vec<u8> {
+    let mut delta: Vec<u8> = Vec::new();
+    let mut index: usize = 0;
+
+    write_length(&mut delta, target.len());
+
+    while index < target.len() {
+        let unchanged: usize = (index..target.len())
+            .take_while(|position| target[*position] == get_byte(base, *position))
+            .count();
+
+        index += unchanged;
+
+        if index == target.len() {
+            break;
+        }
+
+        let changed: usize = (index..target.len())
+            .take_while(|position| target[*position] != get_byte(base, *position))
+            .count();
+
+        write_length(&mut delta, unchanged);
+        write_length(&mut delta, changed);
+
+        delta.extend((index..index + changed).map(|position| target[position] ^ get_byte(base, position)));
+
+        index += changed;
+    }
+
+    delta
+}
+
+fn decompress(base: &[u8], delta: &[u8]) -> Vec<u8> {
+    let mut position: usize = 0;
+    let length: usize = read_length(delta, &mut position);
+    let mut target: Vec<u8> = (0..length).map(|index| get_byte(base, index)).collect();
+    let mut index: usize = 0;
+
+    while position < delta.len() {
+        index += read_length(delta, &mut position);
+
+        let changed: usize = read_length(delta, &mut position);
+
+        for byte in &delta[position..position + changed] {
+            target[index] ^= byte;
+            index += 1;
+        }
+
+        position += changed;
+    }
+
+    target
+}
+
+// The states change size when the resolution or the memory does, and the
+// missing bytes of the shorter one count as zeros.
+fn get_byte(bytes: &[u8], index: usize) -> u8 {
+    bytes.get(index).copied().unwrap_or(0x0)
+}
+
+// LEB128, so the short runs that make most of a delta take a single byte.
+fn write_length(bytes: &mut Vec<u8>, mut length: usize) {
+    while length >= 0x80 {
+        bytes.push(length as u8 | 0x80);
+        length >>= 7;
+    }
+
+    bytes.push(length as u8);
+}
+
+fn read_length(bytes: &[u8], position: &mut usize) -> usize {
+    let mut length: usize = 0;
+    let mut shift: u32 = 0;
+
+    loop {
+        let byte: u8 = bytes[*position];
+
+        *position += 1;
+        length |= usize::from(byte & 0x7F) << shift;
+        shift += 7;
+
+        if byte & 0x80 == 0x0 {
+            return length;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+    use super::*;
+
+    #[test_case(vec![0x1, 0x2, 0x3], vec![0x1, 0x2, 0x3] ; "with equal states")]
+    #[test_case(vec![0x1, 0x2, 0x3, 0x4], vec![0x1, 0xF, 0x3, 0xE] ; "with changed bytes")]
+    #[test_case(vec![0x1, 0x2], vec![0x1, 0x2, 0x3, 0x0] ; "with a longer target")]
+    #[test_case(vec![0x1, 0x2, 0x3, 0x4], vec![0x1] ; "with a shorter target")]
+    #[test_case(vec![0x0; 0x1000], vec![0xFF; 0x1000] ; "with long runs")]
+    fn it_should_decompress_what_was_compressed(base: Vec<u8>, target: Vec<u8>) {
+        assert_eq!(target, decompress(&base, &compress(&base, &target)));
+    }
+
+    #[test]
+    fn it_should_compress_a_state_barely_changed() {
+        let base: Vec<u8> = vec![0x0; 0x1000];
+        let mut target: Vec<u8> = base.clone();
+
+        target[0x800] = 0xCA;
+
+        // The length, the unchanged run, the changed run and the changed byte.
+        assert_eq!(vec![0x80, 0x20, 0x80, 0x10, 0x1, 0xCA], compress(&base, &target));
+    }
+
+    #[test]
+    fn it_should_pop_the_states_from_the_newest() {
+        let mut buffer: RewindBuffer = RewindBuffer::initialize(10);
+
+        buffer.push(vec![0x1, 0x1]);
+        buffer.push(vec![0x2, 0x1]);
+        buffer.push(vec![0x3, 0x1, 0x1]);
+
+        assert_eq!(3, buffer.len());
+        assert_eq!(Some(vec![0x3, 0x1, 0x1]), buffer.pop());
+        assert_eq!(Some(vec![0x2, 0x1]), buffer.pop());
+        assert_eq!(Some(vec![0x1, 0x1]), buffer.pop());
+        assert_eq!(None, buffer.pop());
+        assert!(buffer.is_empty());
+    }
+
+    #[test]
+    fn it_should_drop_the_oldest_states_over_the_capacity() {
+        let mut buffer: RewindBuffer = RewindBuffer::initialize(2);
+
+        buffer.push(vec![0x1]);
+        buffer.push(vec![0x2]);
+        buffer.push(vec![0x3]);
+
+        assert_eq!(2, buffer.len());
+        assert_eq!(Some(vec![0x3]), buffer.pop());
+        assert_eq!(Some(vec![0x2]), buffer.pop());
+        assert_eq!(None, buffer.pop());
+    }
+
+    #[test]
+    fn it_should_keep_nothing_without_capacity() {
+        let mut buffer: RewindBuffer = RewindBuffer::initialize(0);
+
+        buffer.push(vec![0x1]);
+
+        assert!(buffer.is_empty());
+    }
+
+    #[test]
+    fn it_should_drop_the_states_when_shrinking() {
+        let mut buffer: RewindBuffer = RewindBuffer::initialize(3);
+
+        buffer.push(vec![0x1]);
+        buffer.push(vec![0x2]);
+        buffer.push(vec![0x3]);
+        buffer.set_capacity(1);
+
+        assert_eq!(Some(vec![0x3]), buffer.pop());
+        assert_eq!(None, buffer.pop());
+    }
+}
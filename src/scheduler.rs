@@ -0,0 +1,73 @@
+use std::thread;
+use std::time::{Duration, Instant};
+
+pub struct Scheduler {
+    frame_duration: Duration,
+    next_frame: Instant,
+}
+
+impl Scheduler {
+    pub const FRAMES_PER_SECOND: u32 = 60;
+
+    pub fn initialize() -> Self {
+        let frame_duration: Duration = Duration::from_secs(1) / Scheduler::FRAMES_PER_SECOND;
+
+        Scheduler {
+            frame_duration,
+            next_frame: Instant::now() + frame_duration,
+        }
+    }
+
+    pub fn wait_for_next_frame(&mut self) {
+        let now: Instant = Instant::now();
+
+        if now < self.next_frame {
+            thread::sleep(self.next_frame - now);
+
+            self.next_frame += self.frame_duration;
+        } else {
+            // The host fell behind (e.g. the window was being dragged), so the
+            // missed frames are dropped instead of being run all at once.
+            self.next_frame = now + self.frame_duration;
+        }
+    }
+
+    pub fn get_frame_duration(&self) -> Duration {
+        self.frame_duration
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_should_initialize_the_scheduler_at_60_hz() {
+        let scheduler: Scheduler = Scheduler::initialize();
+
+        assert_eq!(Duration::from_nanos(16_666_666), scheduler.get_frame_duration());
+    }
+
+    #[test]
+    fn it_should_wait_for_the_next_frame() {
+        let mut scheduler: Scheduler = Scheduler::initialize();
+
+        let start: Instant = Instant::now();
+
+        scheduler.wait_for_next_frame();
+        scheduler.wait_for_next_frame();
+
+        assert!(start.elapsed() >= scheduler.get_frame_duration());
+    }
+
+    #[test]
+    fn it_should_drop_frames_when_falling_behind() {
+        let mut scheduler: Scheduler = Scheduler::initialize();
+
+        scheduler.next_frame = Instant::now() - Duration::from_secs(1);
+
+        scheduler.wait_for_next_frame();
+
+        assert!(scheduler.next_frame > Instant::now());
+    }
+}
@@ -0,0 +1,231 @@
+use crate::error::EmulatorError;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+pub struct StateWriter {
+    bytes: Vec<u8>,
+}
+
+impl StateWriter {
+    pub const MAGIC: &'static [u8; 4] = b"C8SS";
+    // Bumped whenever the layout changes, so older states are refused instead
+    // of being read as garbage.
+    pub const VERSION: u8 = 1;
+
+    pub fn initialize() -> Self {
+        let mut writer: StateWriter = StateWriter { bytes: Vec::new() };
+
+        writer.write_bytes(StateWriter::MAGIC);
+        writer.write_u8(StateWriter::VERSION);
+
+        writer
+    }
+
+    pub fn write_u8(&mut self, value: u8) {
+        self.bytes.push(value);
+    }
+
+    pub fn write_bool(&mut self, value: bool) {
+        self.write_u8(value as u8);
+    }
+
+    pub fn write_u16(&mut self, value: u16) {
+        self.write_bytes(&value.to_le_bytes());
+    }
+
+    pub fn write_u32(&mut self, value: u32) {
+        self.write_bytes(&value.to_le_bytes());
+    }
+
+    pub fn write_u64(&mut self, value: u64) {
+        self.write_bytes(&value.to_le_bytes());
+    }
+
+    pub fn write_bytes(&mut self, bytes: &[u8]) {
+        self.bytes.extend_from_slice(bytes);
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+}
+
+pub struct StateReader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> StateReader<'a> {
+    pub fn initialize(bytes: &'a [u8]) -> Result<Self, EmulatorError> {
+        let mut reader: StateReader = StateReader { bytes, position: 0 };
+
+        if reader.read_bytes(StateWriter::MAGIC.len())? != StateWriter::MAGIC {
+            return Err(EmulatorError::InvalidState);
+        }
+
+        match reader.read_u8()? {
+            StateWriter::VERSION => Ok(reader),
+            version => Err(EmulatorError::UnsupportedStateVersion { version }),
+        }
+    }
+
+    pub fn read_u8(&mut self) -> Result<u8, EmulatorError> {
+        Ok(self.read_bytes(1)?[0])
+    }
+
+    pub fn read_bool(&mut self) -> Result<bool, EmulatorError> {
+        match self.read_u8()? {
+            0x0 => Ok(false),
+            0x1 => Ok(true),
+            _ => Err(EmulatorError::InvalidState),
+        }
+    }
+
+    pub fn read_u16(&mut self) -> Result<u16, EmulatorError> {
+        Ok(u16::from_le_bytes(self.read_array()?))
+    }
+
+    pub fn read_u32(&mut self) -> Result<u32, EmulatorError> {
+        Ok(u32::from_le_bytes(self.read_array()?))
+    }
+
+    pub fn read_u64(&mut self) -> Result<u64, EmulatorError> {
+        Ok(u64::from_le_bytes(self.read_array()?))
+    }
+
+    pub fn read_array<const N: usize>(&mut self) -> Result<[u8; N], EmulatorError> {
+        let mut array: [u8; N] = [0x0; N];
+
+        array.copy_from_slice(self.read_bytes(N)?);
+
+        Ok(array)
+    }
+
+    pub fn read_bytes(&mut self, length: usize) -> Result<&'a [u8], EmulatorError> {
+        let bytes: &'a [u8] = self.bytes
+            .get(self.position..self.position + length)
+            .ok_or(EmulatorError::InvalidState)?;
+
+        self.position += length;
+
+        Ok(bytes)
+    }
+
+    pub fn finish(self) -> Result<(), EmulatorError> {
+        match self.position == self.bytes.len() {
+            true => Ok(()),
+            false => Err(EmulatorError::InvalidState),
+        }
+    }
+}
+
+pub struct StateStore {
+    directory: PathBuf,
+}
+
+impl StateStore {
+    pub const SLOTS: usize = 9;
+
+    pub fn initialize(directory: PathBuf) -> Self {
+        StateStore { directory }
+    }
+
+    pub fn load(&self, rom_hash: u64, slot: usize) -> io::Result<Option<Vec<u8>>> {
+        match fs::read(self.get_path(rom_hash, slot)) {
+            Ok(bytes) => Ok(Some(bytes)),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(error) => Err(error),
+        }
+    }
+
+    pub fn save(&self, rom_hash: u64, slot: usize, state: &[u8]) -> io::Result<()> {
+        fs::create_dir_all(&self.directory)?;
+
+        fs::write(self.get_path(rom_hash, slot), state)
+    }
+
+    fn get_path(&self, rom_hash: u64, slot: usize) -> PathBuf {
+        self.directory.join(format!("{:016x}-{}.state", rom_hash, slot))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    fn directory(name: &str) -> PathBuf {
+        let directory: PathBuf = env::temp_dir().join(format!("chip8rs-states-{}-{}", name, std::process::id()));
+
+        let _ = fs::remove_dir_all(&directory);
+
+        directory
+    }
+
+    #[test]
+    fn it_should_read_what_was_written() {
+        let mut writer: StateWriter = StateWriter::initialize();
+
+        writer.write_u8(0xCA);
+        writer.write_bool(true);
+        writer.write_u16(0xCAFE);
+        writer.write_u32(0xDEADBEEF);
+        writer.write_u64(0x0123456789ABCDEF);
+        writer.write_bytes(&[0x1, 0x2, 0x3]);
+
+        let bytes: Vec<u8> = writer.into_bytes();
+        let mut reader: StateReader = StateReader::initialize(&bytes).unwrap();
+
+        assert_eq!(Ok(0xCA), reader.read_u8());
+        assert_eq!(Ok(true), reader.read_bool());
+        assert_eq!(Ok(0xCAFE), reader.read_u16());
+        assert_eq!(Ok(0xDEADBEEF), reader.read_u32());
+        assert_eq!(Ok(0x0123456789ABCDEF), reader.read_u64());
+        assert_eq!(Ok([0x1, 0x2, 0x3]), reader.read_array());
+        assert_eq!(Ok(()), reader.finish());
+    }
+
+    #[test]
+    fn it_should_refuse_bytes_that_are_not_a_state() {
+        assert!(matches!(StateReader::initialize(b"RIFF\x01"), Err(EmulatorError::InvalidState)));
+    }
+
+    #[test]
+    fn it_should_refuse_a_state_of_another_version() {
+        assert!(matches!(
+            StateReader::initialize(b"C8SS\x02"),
+            Err(EmulatorError::UnsupportedStateVersion { version: 0x2 })
+        ));
+    }
+
+    #[test]
+    fn it_should_fail_to_read_past_the_end() {
+        let bytes: Vec<u8> = StateWriter::initialize().into_bytes();
+        let mut reader: StateReader = StateReader::initialize(&bytes).unwrap();
+
+        assert_eq!(Err(EmulatorError::InvalidState), reader.read_u16());
+    }
+
+    #[test]
+    fn it_should_fail_to_finish_with_bytes_left() {
+        let mut writer: StateWriter = StateWriter::initialize();
+
+        writer.write_u8(0x0);
+
+        let bytes: Vec<u8> = writer.into_bytes();
+
+        assert_eq!(Err(EmulatorError::InvalidState), StateReader::initialize(&bytes).unwrap().finish());
+    }
+
+    #[test]
+    fn it_should_save_and_load_a_slot() {
+        let store: StateStore = StateStore::initialize(directory("slot"));
+
+        store.save(0x1234, 0x1, &[0xCA, 0xFE]).unwrap();
+
+        assert_eq!(Some(vec![0xCA, 0xFE]), store.load(0x1234, 0x1).unwrap());
+        assert_eq!(None, store.load(0x1234, 0x2).unwrap());
+        assert_eq!(None, store.load(0x5678, 0x1).unwrap());
+    }
+}
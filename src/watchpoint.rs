@@ -0,0 +1,114 @@
+use std::fmt;
+
+// The memory ranges include both ends.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Watchpoint {
+    Read { start: usize, end: usize },
+    Write { start: usize, end: usize },
+    VRegister { register: usize, value: u8 },
+    IRegister { value: u16 },
+}
+
+// What the program did to set a watchpoint off.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Trigger {
+    Read { address: usize },
+    Write { address: usize, value: u8 },
+    VRegister { register: usize, value: u8 },
+    IRegister { value: u16 },
+}
+
+impl Watchpoint {
+    pub fn is_set_off_by(&self, trigger: &Trigger) -> bool {
+        match (self, trigger) {
+            (Watchpoint::Read { start, end }, Trigger::Read { address }) => (start..=end).contains(&address),
+            (Watchpoint::Write { start, end }, Trigger::Write { address, .. }) => (start..=end).contains(&address),
+            (
+                Watchpoint::VRegister { register, value },
+                Trigger::VRegister { register: changed_register, value: changed_value },
+            ) => register == changed_register && value == changed_value,
+            (Watchpoint::IRegister { value }, Trigger::IRegister { value: changed_value }) => value == changed_value,
+            _ => false,
+        }
+    }
+
+    pub fn is_on_memory(&self) -> bool {
+        matches!(self, Watchpoint::Read { .. } | Watchpoint::Write { .. })
+    }
+}
+
+pub fn check(watchpoints: &[Watchpoint], trigger: Trigger) -> Option<Trigger> {
+    match watchpoints.iter().any(|watchpoint| watchpoint.is_set_off_by(&trigger)) {
+        true => Some(trigger),
+        false => None,
+    }
+}
+
+impl fmt::Display for Watchpoint {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Watchpoint::Read { start, end } if start == end => write!(formatter, "read {:#05X}", start),
+            Watchpoint::Read { start, end } => write!(formatter, "read {:#05X}-{:#05X}", start, end),
+            Watchpoint::Write { start, end } if start == end => write!(formatter, "write {:#05X}", start),
+            Watchpoint::Write { start, end } => write!(formatter, "write {:#05X}-{:#05X}", start, end),
+            Watchpoint::VRegister { register, value } => write!(formatter, "V{:X} = {:#04X}", register, value),
+            Watchpoint::IRegister { value } => write!(formatter, "I = {:#05X}", value),
+        }
+    }
+}
+
+impl fmt::Display for Trigger {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Trigger::Read { address } => write!(formatter, "Read from {:#05X}.", address),
+            Trigger::Write { address, value } => write!(formatter, "Wrote {:#04X} to {:#05X}.", value, address),
+            Trigger::VRegister { register, value } => write!(formatter, "V{:X} was set to {:#04X}.", register, value),
+            Trigger::IRegister { value } => write!(formatter, "I was set to {:#05X}.", value),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+    use super::*;
+
+    #[test_case(Watchpoint::Read { start: 0x300, end: 0x30F }, Trigger::Read { address: 0x30F }, true ; "with a read in range")]
+    #[test_case(Watchpoint::Read { start: 0x300, end: 0x30F }, Trigger::Read { address: 0x310 }, false ; "with a read out of range")]
+    #[test_case(Watchpoint::Read { start: 0x300, end: 0x30F }, Trigger::Write { address: 0x300, value: 0x1 }, false ; "with a write on a read watchpoint")]
+    #[test_case(Watchpoint::Write { start: 0x300, end: 0x300 }, Trigger::Write { address: 0x300, value: 0x1 }, true ; "with a write")]
+    #[test_case(Watchpoint::VRegister { register: 0x3, value: 0x10 }, Trigger::VRegister { register: 0x3, value: 0x10 }, true ; "with a register value")]
+    #[test_case(Watchpoint::VRegister { register: 0x3, value: 0x10 }, Trigger::VRegister { register: 0x3, value: 0x11 }, false ; "with another register value")]
+    #[test_case(Watchpoint::VRegister { register: 0x3, value: 0x10 }, Trigger::VRegister { register: 0x4, value: 0x10 }, false ; "with another register")]
+    #[test_case(Watchpoint::IRegister { value: 0x300 }, Trigger::IRegister { value: 0x300 }, true ; "with an i register value")]
+    fn it_should_check_if_a_watchpoint_is_set_off(watchpoint: Watchpoint, trigger: Trigger, set_off: bool) {
+        assert_eq!(set_off, watchpoint.is_set_off_by(&trigger));
+    }
+
+    #[test]
+    fn it_should_check_a_list_of_watchpoints() {
+        let watchpoints: [Watchpoint; 2] = [
+            Watchpoint::Read { start: 0x300, end: 0x30F },
+            Watchpoint::IRegister { value: 0x300 },
+        ];
+
+        assert_eq!(Some(Trigger::IRegister { value: 0x300 }), check(&watchpoints, Trigger::IRegister { value: 0x300 }));
+        assert_eq!(None, check(&watchpoints, Trigger::Read { address: 0x400 }));
+    }
+
+    #[test_case(Watchpoint::Read { start: 0x300, end: 0x30F }, "read 0x300-0x30F" ; "with a read range")]
+    #[test_case(Watchpoint::Write { start: 0x300, end: 0x300 }, "write 0x300" ; "with a single write address")]
+    #[test_case(Watchpoint::VRegister { register: 0xA, value: 0x10 }, "VA = 0x10" ; "with a register")]
+    #[test_case(Watchpoint::IRegister { value: 0x300 }, "I = 0x300" ; "with the i register")]
+    fn it_should_describe_a_watchpoint(watchpoint: Watchpoint, description: &str) {
+        assert_eq!(description, watchpoint.to_string());
+    }
+
+    #[test_case(Trigger::Read { address: 0x300 }, "Read from 0x300." ; "with a read")]
+    #[test_case(Trigger::Write { address: 0x300, value: 0xCA }, "Wrote 0xCA to 0x300." ; "with a write")]
+    #[test_case(Trigger::VRegister { register: 0xA, value: 0x10 }, "VA was set to 0x10." ; "with a register")]
+    #[test_case(Trigger::IRegister { value: 0x300 }, "I was set to 0x300." ; "with the i register")]
+    fn it_should_describe_a_trigger(trigger: Trigger, description: &str) {
+        assert_eq!(description, trigger.to_string());
+    }
+}